@@ -2,22 +2,147 @@
 
 use crate::{Deserialize, Serialize};
 
+/// The pixel density of a regular (non high-DPI) display.
+const DEFAULT_DENSITY: f32 = 1.0;
+
 /// Canvas details.
-#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct Canvas {
     /// The width of the canvas.
     width: u16,
 
     /// The height of the canvas.
     height: u16,
+
+    /// The insets of the area of the canvas that is not obscured by the
+    /// display (e.g. by a notch or rounded corners).
+    #[serde(default)]
+    insets: Insets,
+
+    /// The number of physical pixels per (logical) pixel of the canvas.
+    ///
+    /// This is `2.0` in "high DPI" mode, and `1.0` otherwise.
+    #[serde(default = "default_density")]
+    density: f32,
+}
+
+/// The density of a canvas that doesn't specify one.
+const fn default_density() -> f32 {
+    DEFAULT_DENSITY
+}
+
+/// The distance (in pixels) from each edge of the canvas to the area that is
+/// safe to draw in.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Insets {
+    /// The inset from the top of the canvas.
+    pub top: f32,
+
+    /// The inset from the bottom of the canvas.
+    pub bottom: f32,
+
+    /// The inset from the left of the canvas.
+    pub left: f32,
+
+    /// The inset from the right of the canvas.
+    pub right: f32,
 }
 
 impl Canvas {
-    /// Create a new canvas.
+    /// Create a new canvas, without any safe-area insets.
     #[inline]
     #[must_use]
     pub const fn new(width: u16, height: u16) -> Self {
-        Self { width, height }
+        Self {
+            width,
+            height,
+            insets: Insets {
+                top: 0.0,
+                bottom: 0.0,
+                left: 0.0,
+                right: 0.0,
+            },
+            density: DEFAULT_DENSITY,
+        }
+    }
+
+    /// Set the pixel density of the canvas.
+    #[inline]
+    #[must_use]
+    pub const fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Get the number of physical pixels per (logical) pixel of the canvas.
+    #[inline]
+    #[must_use]
+    pub const fn density(self) -> f32 {
+        self.density
+    }
+
+    /// Get the size (in physical pixels) at which to render a font of the
+    /// given point size.
+    ///
+    /// Without scaling, text looks tiny on high-DPI displays, or blurry when
+    /// the rendered text is scaled up afterwards.
+    #[inline]
+    #[must_use]
+    pub fn scaled_font_size(self, points: f32) -> f32 {
+        points * self.density
+    }
+
+    /// Set the safe-area insets of the canvas.
+    ///
+    /// The insets are reported by the backend for displays on which part of
+    /// the canvas is obscured (e.g. by a notch). None of the current backends
+    /// expose these, in which case the insets are zero.
+    #[inline]
+    #[must_use]
+    pub const fn with_insets(mut self, insets: Insets) -> Self {
+        self.insets = insets;
+        self
+    }
+
+    /// Get the safe-area insets of the canvas.
+    #[inline]
+    #[must_use]
+    pub const fn insets(self) -> Insets {
+        self.insets
+    }
+
+    /// Get the area `(x, y, width, height)` of the canvas that is not obscured
+    /// by the display, and is thus safe to place (interactive) widgets in.
+    ///
+    /// Insets larger than the canvas result in an empty area.
+    #[inline]
+    #[must_use]
+    pub fn safe_area(self) -> (f32, f32, f32, f32) {
+        let Insets {
+            top,
+            bottom,
+            left,
+            right,
+        } = self.insets;
+
+        let width = (f32::from(self.width) - left - right).max(0.0);
+        let height = (f32::from(self.height) - top - bottom).max(0.0);
+
+        (left, top, width, height)
+    }
+
+    /// Convert normalized coordinates (in the range `[0.0-1.0]`) within the
+    /// safe area to pixel coordinates on the canvas.
+    ///
+    /// This is similar to [`Canvas::from_normalized`], but anchors items to
+    /// the safe area instead, e.g. `(1.0, 0.0)` is the top-right corner of the
+    /// safe area.
+    #[inline]
+    #[must_use]
+    pub fn anchor(self, nx: f32, ny: f32) -> (f32, f32) {
+        let (x, y, width, height) = self.safe_area();
+
+        (x + nx * width, y + ny * height)
     }
 
     /// Get the dimensions (width, height) of the canvas.
@@ -26,4 +151,593 @@ impl Canvas {
     pub const fn dimensions(self) -> (u16, u16) {
         (self.width, self.height)
     }
+
+    /// Get the dimensions (width, height) of the canvas as floating point
+    /// numbers, for use in coordinate calculations.
+    #[inline]
+    #[must_use]
+    pub fn dimensions_f32(self) -> (f32, f32) {
+        (f32::from(self.width), f32::from(self.height))
+    }
+
+    /// Get the position and dimensions `((x, y), (width, height))` of a widget
+    /// exactly covering the canvas, e.g. for a fullscreen background.
+    #[inline]
+    #[must_use]
+    pub fn fit(self) -> ((f32, f32), (f32, f32)) {
+        ((0.0, 0.0), self.dimensions_f32())
+    }
+
+    /// Get the dimensions (width, height) of the canvas in physical pixels,
+    /// based on the pixel density of the canvas.
+    ///
+    /// Dimensions are rounded to the nearest pixel.
+    #[inline]
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::as_conversions
+    )]
+    pub fn physical_dimensions(self) -> (u32, u32) {
+        let (width, height) = self.dimensions_f32();
+        let scale = |size: f32| (size * self.density).round() as u32;
+
+        (scale(width), scale(height))
+    }
+
+    /// Get a copy of the canvas, with its dimensions and insets multiplied by
+    /// the given factor.
+    ///
+    /// This is useful to compute layouts at a different reference resolution.
+    /// Dimensions are rounded to the nearest pixel, and clamped to the range
+    /// of a `u16`.
+    #[inline]
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::as_conversions
+    )]
+    pub fn scaled(self, factor: f32) -> Self {
+        let scale = |size: u16| (f32::from(size) * factor).round() as u16;
+        let Insets {
+            top,
+            bottom,
+            left,
+            right,
+        } = self.insets;
+
+        Self::new(scale(self.width), scale(self.height))
+            .with_insets(Insets {
+                top: top * factor,
+                bottom: bottom * factor,
+                left: left * factor,
+                right: right * factor,
+            })
+            .with_density(self.density)
+    }
+
+    /// Convert normalized coordinates (in the range `[0.0-1.0]`) to pixel
+    /// coordinates on the canvas.
+    ///
+    /// This allows placing items independent of the canvas resolution. For
+    /// example, `(0.5, 0.5)` is always the center of the canvas.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_normalized(self, nx: f32, ny: f32) -> (f32, f32) {
+        (nx * f32::from(self.width), ny * f32::from(self.height))
+    }
+
+    /// Convert pixel coordinates on the canvas to normalized coordinates (in
+    /// the range `[0.0-1.0]` for coordinates within the canvas).
+    ///
+    /// A canvas without a width or height returns `0.0` for that axis.
+    #[inline]
+    #[must_use]
+    pub fn to_normalized(self, x: f32, y: f32) -> (f32, f32) {
+        let normalize = |value: f32, size: u16| match size {
+            0 => 0.0,
+            size => value / f32::from(size),
+        };
+
+        (normalize(x, self.width), normalize(y, self.height))
+    }
+
+    /// Get the largest viewport with the given aspect ratio (width / height)
+    /// that fits within the canvas.
+    ///
+    /// The viewport is centered on the canvas, leaving (black) bars on either
+    /// the top and bottom, or the left and right of the canvas.
+    #[inline]
+    #[must_use]
+    pub fn with_letterbox(self, aspect_ratio: f32) -> Viewport {
+        let width = f32::from(self.width);
+        let height = f32::from(self.height);
+
+        if aspect_ratio <= 0.0 || !aspect_ratio.is_finite() {
+            return Viewport::from(self);
+        }
+
+        let (viewport_width, viewport_height) = if width / height > aspect_ratio {
+            (height * aspect_ratio, height)
+        } else {
+            (width, width / aspect_ratio)
+        };
+
+        Viewport {
+            x: (width - viewport_width) / 2.0,
+            y: (height - viewport_height) / 2.0,
+            width: viewport_width,
+            height: viewport_height,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Default for Canvas {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl From<Viewport> for Canvas {
+    /// Get a canvas of the reference size of the viewport, see
+    /// [`Viewport::reference_dimensions`].
+    ///
+    /// Dimensions are rounded to the nearest pixel.
+    #[inline]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::as_conversions
+    )]
+    fn from(viewport: Viewport) -> Self {
+        let (width, height) = viewport.reference_dimensions();
+
+        Self::new(width.round() as u16, height.round() as u16)
+    }
+}
+
+/// A rectangular area of the canvas in which the game is rendered.
+///
+/// The game is laid out at a reference size, and scaled to fit the viewport.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Viewport {
+    /// The horizontal offset of the viewport from the left of the canvas.
+    pub x: f32,
+
+    /// The vertical offset of the viewport from the top of the canvas.
+    pub y: f32,
+
+    /// The width of the viewport.
+    pub width: f32,
+
+    /// The height of the viewport.
+    pub height: f32,
+
+    /// The factor by which the game is scaled to fit the viewport.
+    ///
+    /// This is the size of the viewport divided by the reference size of the
+    /// game, see [`Viewport::with_reference`].
+    pub scale: f32,
+}
+
+impl Viewport {
+    /// Scale the viewport to fit a game laid out at the given reference size.
+    ///
+    /// If the aspect ratio of the reference size differs from that of the
+    /// viewport, the game is scaled to fit within the viewport. A reference
+    /// size without a width or height leaves the viewport unscaled.
+    #[inline]
+    #[must_use]
+    pub fn with_reference(mut self, width: f32, height: f32) -> Self {
+        if width <= 0.0 || height <= 0.0 {
+            return self;
+        }
+
+        self.scale = (self.width / width).min(self.height / height);
+        self
+    }
+
+    /// Get the dimensions (width, height) of the game laid out within the
+    /// viewport, before it is scaled to fit the viewport.
+    ///
+    /// A viewport without a scale returns its own dimensions.
+    #[inline]
+    #[must_use]
+    pub fn reference_dimensions(self) -> (f32, f32) {
+        if self.scale <= 0.0 {
+            return (self.width, self.height);
+        }
+
+        (self.width / self.scale, self.height / self.scale)
+    }
+
+    /// Get the bars `(x, y, width, height)` covering the area of a canvas of
+    /// the given size outside of the viewport.
+    ///
+    /// Bars without an area are left out, e.g. only the top and bottom bars
+    /// are returned for a viewport spanning the full width of the canvas.
+    #[inline]
+    #[must_use]
+    pub fn letterbox_bars(self, width: f32, height: f32) -> Vec<(f32, f32, f32, f32)> {
+        let right = self.x + self.width;
+        let bottom = self.y + self.height;
+
+        vec![
+            (0.0, 0.0, width, self.y),
+            (0.0, bottom, width, height - bottom),
+            (0.0, self.y, self.x, self.height),
+            (right, self.y, width - right, self.height),
+        ]
+        .into_iter()
+        .filter(|&(_, _, w, h)| w > 0.0 && h > 0.0)
+        .collect()
+    }
+
+    /// Check whether the given canvas coordinates lie within the viewport.
+    #[inline]
+    #[must_use]
+    pub fn contains(self, x: f32, y: f32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+
+    /// Convert coordinates relative to the viewport to canvas coordinates.
+    ///
+    /// The coordinates are scaled by the scale of the viewport, before they
+    /// are offset by its position.
+    #[inline]
+    #[must_use]
+    pub fn to_canvas_coordinates(self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale + self.x, y * self.scale + self.y)
+    }
+
+    /// Convert canvas coordinates to coordinates relative to the viewport.
+    ///
+    /// This is the inverse of [`Viewport::to_canvas_coordinates`]. A viewport
+    /// without a scale only removes its offset.
+    #[inline]
+    #[must_use]
+    pub fn to_viewport_coordinates(self, x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = (x - self.x, y - self.y);
+
+        if self.scale <= 0.0 {
+            return (x, y);
+        }
+
+        (x / self.scale, y / self.scale)
+    }
+}
+
+impl Default for Viewport {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl From<Canvas> for Viewport {
+    /// Get a viewport covering the entire canvas.
+    #[inline]
+    fn from(canvas: Canvas) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: f32::from(canvas.width),
+            height: f32::from(canvas.height),
+            scale: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod with_letterbox {
+        use super::*;
+
+        #[test]
+        fn wider_aspect_ratio() {
+            let viewport = Canvas::new(800, 600).with_letterbox(16.0 / 9.0);
+
+            assert_eq!(
+                viewport,
+                Viewport {
+                    x: 0.0,
+                    y: 75.0,
+                    width: 800.0,
+                    height: 450.0,
+                    scale: 1.0,
+                }
+            );
+        }
+
+        #[test]
+        fn narrower_aspect_ratio() {
+            let viewport = Canvas::new(800, 600).with_letterbox(1.0);
+
+            assert_eq!(
+                viewport,
+                Viewport {
+                    x: 100.0,
+                    y: 0.0,
+                    width: 600.0,
+                    height: 600.0,
+                    scale: 1.0,
+                }
+            );
+        }
+
+        #[test]
+        fn matching_aspect_ratio() {
+            let canvas = Canvas::new(800, 600);
+
+            assert_eq!(canvas.with_letterbox(4.0 / 3.0), Viewport::from(canvas));
+        }
+
+        #[test]
+        fn invalid_aspect_ratio() {
+            let canvas = Canvas::new(800, 600);
+
+            assert_eq!(canvas.with_letterbox(0.0), Viewport::from(canvas));
+        }
+    }
+
+    mod viewport {
+        use super::*;
+
+        #[test]
+        fn with_reference() {
+            // A 16:9 game laid out at 320x180, in a window of 1280x800.
+            let viewport = Canvas::new(1280, 800)
+                .with_letterbox(16.0 / 9.0)
+                .with_reference(320.0, 180.0);
+
+            assert_eq!(viewport.scale, 4.0);
+            assert_eq!(viewport.reference_dimensions(), (320.0, 180.0));
+            assert_eq!(Canvas::from(viewport).dimensions(), (320, 180));
+        }
+
+        #[test]
+        fn empty_reference() {
+            let viewport = Viewport::from(Canvas::new(800, 600)).with_reference(0.0, 600.0);
+
+            assert_eq!(viewport.scale, 1.0);
+        }
+
+        #[test]
+        fn to_canvas_coordinates() {
+            let viewport = Canvas::new(1280, 800)
+                .with_letterbox(16.0 / 9.0)
+                .with_reference(320.0, 180.0);
+
+            assert_eq!(viewport.to_canvas_coordinates(0.0, 0.0), (0.0, 40.0));
+            assert_eq!(viewport.to_canvas_coordinates(10.0, 20.0), (40.0, 120.0));
+            assert_eq!(
+                viewport.to_canvas_coordinates(320.0, 180.0),
+                (1280.0, 760.0)
+            );
+        }
+
+        #[test]
+        fn to_viewport_coordinates() {
+            let viewport = Canvas::new(1280, 800)
+                .with_letterbox(16.0 / 9.0)
+                .with_reference(320.0, 180.0);
+
+            assert_eq!(viewport.to_viewport_coordinates(40.0, 120.0), (10.0, 20.0));
+            assert_eq!(viewport.to_viewport_coordinates(0.0, 0.0), (0.0, -10.0));
+        }
+
+        #[test]
+        fn contains() {
+            let viewport = Canvas::new(800, 600).with_letterbox(16.0 / 9.0);
+
+            assert!(viewport.contains(400.0, 300.0));
+            assert!(!viewport.contains(400.0, 50.0));
+            assert!(!viewport.contains(400.0, 550.0));
+        }
+
+        #[test]
+        fn letterbox_bars() {
+            let viewport = Canvas::new(800, 600).with_letterbox(16.0 / 9.0);
+
+            assert_eq!(
+                viewport.letterbox_bars(800.0, 600.0),
+                vec![(0.0, 0.0, 800.0, 75.0), (0.0, 525.0, 800.0, 75.0)]
+            );
+
+            let viewport = Canvas::new(800, 600).with_letterbox(1.0);
+
+            assert_eq!(
+                viewport.letterbox_bars(800.0, 600.0),
+                vec![(0.0, 0.0, 100.0, 600.0), (700.0, 0.0, 100.0, 600.0)]
+            );
+        }
+
+        #[test]
+        fn into_canvas_rounds() {
+            let viewport = Viewport {
+                width: 99.6,
+                height: 50.4,
+                ..Viewport::default()
+            };
+
+            assert_eq!(Canvas::from(viewport).dimensions(), (100, 50));
+        }
+    }
+
+    mod normalized {
+        use super::*;
+
+        #[test]
+        fn from_normalized() {
+            let canvas = Canvas::new(800, 600);
+
+            assert_eq!(canvas.from_normalized(0.0, 0.0), (0.0, 0.0));
+            assert_eq!(canvas.from_normalized(1.0, 0.0), (800.0, 0.0));
+            assert_eq!(canvas.from_normalized(0.0, 1.0), (0.0, 600.0));
+            assert_eq!(canvas.from_normalized(1.0, 1.0), (800.0, 600.0));
+            assert_eq!(canvas.from_normalized(0.5, 0.5), (400.0, 300.0));
+        }
+
+        #[test]
+        fn to_normalized() {
+            let canvas = Canvas::new(800, 600);
+
+            assert_eq!(canvas.to_normalized(0.0, 0.0), (0.0, 0.0));
+            assert_eq!(canvas.to_normalized(800.0, 600.0), (1.0, 1.0));
+            assert_eq!(canvas.to_normalized(400.0, 300.0), (0.5, 0.5));
+        }
+
+        #[test]
+        fn empty_canvas() {
+            let canvas = Canvas::new(0, 600);
+
+            assert_eq!(canvas.to_normalized(10.0, 300.0), (0.0, 0.5));
+        }
+    }
+
+    mod scaled {
+        use super::*;
+
+        #[test]
+        fn half() {
+            let canvas = Canvas::new(800, 600).scaled(0.5);
+
+            assert_eq!(canvas.dimensions(), (400, 300));
+        }
+
+        #[test]
+        fn insets() {
+            let insets = Insets {
+                top: 10.0,
+                bottom: 20.0,
+                left: 0.0,
+                right: 4.0,
+            };
+
+            let canvas = Canvas::new(100, 100).with_insets(insets).scaled(2.0);
+
+            assert_eq!(canvas.dimensions(), (200, 200));
+            assert_eq!(
+                canvas.insets(),
+                Insets {
+                    top: 20.0,
+                    bottom: 40.0,
+                    left: 0.0,
+                    right: 8.0,
+                }
+            );
+        }
+
+        #[test]
+        fn rounded() {
+            assert_eq!(Canvas::new(3, 5).scaled(0.5).dimensions(), (2, 3));
+        }
+    }
+
+    mod safe_area {
+        use super::*;
+
+        fn notched() -> Canvas {
+            Canvas::new(800, 600).with_insets(Insets {
+                top: 40.0,
+                bottom: 20.0,
+                left: 10.0,
+                right: 30.0,
+            })
+        }
+
+        #[test]
+        fn without_insets() {
+            let canvas = Canvas::new(800, 600);
+
+            assert_eq!(canvas.safe_area(), (0.0, 0.0, 800.0, 600.0));
+            assert_eq!(canvas.anchor(0.5, 0.5), canvas.from_normalized(0.5, 0.5));
+        }
+
+        #[test]
+        fn with_insets() {
+            assert_eq!(notched().safe_area(), (10.0, 40.0, 760.0, 540.0));
+        }
+
+        #[test]
+        fn oversized_insets() {
+            let canvas = Canvas::new(100, 100).with_insets(Insets {
+                left: 80.0,
+                right: 80.0,
+                ..Insets::default()
+            });
+
+            assert_eq!(canvas.safe_area(), (80.0, 0.0, 0.0, 100.0));
+        }
+
+        #[test]
+        fn anchor() {
+            let canvas = notched();
+
+            assert_eq!(canvas.anchor(0.0, 0.0), (10.0, 40.0));
+            assert_eq!(canvas.anchor(1.0, 1.0), (770.0, 580.0));
+            assert_eq!(canvas.anchor(0.5, 0.5), (390.0, 310.0));
+        }
+    }
+
+    mod scaled_font_size {
+        use super::*;
+
+        #[test]
+        fn default_density() {
+            assert_eq!(Canvas::new(800, 600).scaled_font_size(12.0), 12.0);
+        }
+
+        #[test]
+        fn high_density() {
+            let canvas = Canvas::new(800, 600).with_density(2.0);
+
+            assert_eq!(canvas.scaled_font_size(12.0), 24.0);
+            assert_eq!(canvas.scaled(0.5).scaled_font_size(12.0), 24.0);
+        }
+    }
+
+    #[test]
+    fn dimensions_f32() {
+        assert_eq!(Canvas::new(800, 600).dimensions_f32(), (800.0, 600.0));
+    }
+
+    #[test]
+    fn fit() {
+        let canvas = Canvas::new(800, 600);
+
+        assert_eq!(canvas.fit(), ((0.0, 0.0), canvas.dimensions_f32()));
+        assert_eq!(canvas.fit(), ((0.0, 0.0), (800.0, 600.0)));
+    }
+
+    #[test]
+    fn physical_dimensions() {
+        let canvas = Canvas::new(800, 600);
+
+        assert_eq!(canvas.physical_dimensions(), (800, 600));
+        assert_eq!(canvas.with_density(2.0).physical_dimensions(), (1600, 1200));
+        assert_eq!(canvas.with_density(1.5).physical_dimensions(), (1200, 900));
+    }
+
+    #[test]
+    fn viewport_coordinates() {
+        let viewport = Canvas::new(800, 600).with_letterbox(1.0);
+
+        assert_eq!(viewport.to_canvas_coordinates(10.0, 10.0), (110.0, 10.0));
+        assert_eq!(viewport.to_viewport_coordinates(110.0, 10.0), (10.0, 10.0));
+    }
 }
@@ -34,12 +34,28 @@ impl From<Color> for Value {
 
 impl Color {
     /// Create a new `Color` from four `f32`'s in the range `[0.0-1.0]`
+    ///
+    /// The components are not checked. Values outside of the range (or `NaN`)
+    /// can result in rendering artifacts. Use [`Color::new_clamped`] when the
+    /// components originate from an untrusted source.
     #[must_use]
     #[inline]
     pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
         Self { r, g, b, a }
     }
 
+    /// Create a new `Color` from four `f32`'s, clamping each component to the
+    /// range `[0.0-1.0]`.
+    ///
+    /// `NaN` components are replaced by `0.0`.
+    #[must_use]
+    #[inline]
+    pub fn new_clamped(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let clamp = |v: f32| if v.is_nan() { 0.0 } else { v.max(0.0).min(1.0) };
+
+        Self::new(clamp(r), clamp(g), clamp(b), clamp(a))
+    }
+
     /// Create a new `Color` from four `u8`'s in the range `[0-255]`
     #[must_use]
     #[inline]
@@ -99,6 +115,48 @@ impl Color {
         u32::from_be_bytes([r, g, b, a])
     }
 
+    /// Multiply each component of the `Color` with that of another `Color`.
+    ///
+    /// Multiplying by white leaves the color unchanged.
+    #[must_use]
+    #[inline]
+    pub fn multiply(self, other: Self) -> Self {
+        Self::new(
+            self.r * other.r,
+            self.g * other.g,
+            self.b * other.b,
+            self.a * other.a,
+        )
+    }
+
+    /// Multiply the red, green and blue components of the `Color` by its alpha
+    /// component.
+    ///
+    /// Colors are stored with "straight" (non-premultiplied) alpha, which is
+    /// also what both renderers expect. Premultiplied colors are only needed
+    /// when blending colors manually, or when passing colors to APIs that
+    /// expect premultiplied alpha.
+    #[must_use]
+    #[inline]
+    pub fn premultiply(self) -> Self {
+        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Divide the red, green and blue components of a premultiplied `Color` by
+    /// its alpha component.
+    ///
+    /// This is the inverse of [`Color::premultiply`]. A fully transparent color
+    /// has no recoverable color information, and results in transparent black.
+    #[must_use]
+    #[inline]
+    pub fn unpremultiply(self) -> Self {
+        if self.a <= 0.0 {
+            return Self::new(0.0, 0.0, 0.0, 0.0);
+        }
+
+        Self::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
     /// Convert a `Color` into a packed `u32`, containing `0x00RRGGBB` as bytes.
     #[must_use]
     #[inline]
@@ -107,6 +165,151 @@ impl Color {
 
         u32::from_be_bytes([0, r, g, b])
     }
+
+    /// Create a new, opaque `Color` from a hue (in degrees), saturation and
+    /// lightness.
+    ///
+    /// The hue wraps around to the range `[0.0-360.0)`, saturation and
+    /// lightness are in the range `[0.0-1.0]`.
+    #[must_use]
+    #[inline]
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+
+        Self::from_hue_chroma(h, chroma, l - chroma / 2.0)
+    }
+
+    /// Create a new, opaque `Color` from a hue (in degrees), saturation and
+    /// value.
+    ///
+    /// The hue wraps around to the range `[0.0-360.0)`, saturation and value
+    /// are in the range `[0.0-1.0]`.
+    #[must_use]
+    #[inline]
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let chroma = v * s;
+
+        Self::from_hue_chroma(h, chroma, v - chroma)
+    }
+
+    /// Return a tuple of the hue (in degrees), saturation and lightness of the
+    /// `Color`, ignoring the alpha component.
+    ///
+    /// Achromatic colors (greys) have a hue and saturation of `0.0`.
+    #[must_use]
+    #[inline]
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue();
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if chroma <= 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        (hue, saturation, lightness)
+    }
+
+    /// Return a tuple of the hue (in degrees), saturation and value of the
+    /// `Color`, ignoring the alpha component.
+    ///
+    /// Achromatic colors (greys) have a hue and saturation of `0.0`.
+    #[must_use]
+    #[inline]
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue();
+        let saturation = if max <= 0.0 { 0.0 } else { (max - min) / max };
+
+        (hue, saturation, max)
+    }
+
+    /// Create a new, opaque `Color` from a hue (in degrees), the chroma, and
+    /// the amount to add to each component to match the lightness (or value).
+    fn from_hue_chroma(h: f32, chroma: f32, m: f32) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+
+        let (r, g, b) = match h {
+            h if h < 1.0 => (chroma, x, 0.0),
+            h if h < 2.0 => (x, chroma, 0.0),
+            h if h < 3.0 => (0.0, chroma, x),
+            h if h < 4.0 => (0.0, x, chroma),
+            h if h < 5.0 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        Self::new(r + m, g + m, b + m, 1.0)
+    }
+
+    /// Return a tuple of the hue (in degrees), and the largest and smallest
+    /// of the red, green and blue components.
+    fn hue(self) -> (f32, f32, f32) {
+        let Self { r, g, b, .. } = self;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let hue = if chroma <= 0.0 {
+            0.0
+        } else if r >= g && r >= b {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if g >= b {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+
+        (hue * 60.0, max, min)
+    }
+
+    /// Return the relative luminance of the `Color`, as defined by the WCAG,
+    /// ignoring the alpha component.
+    ///
+    /// The luminance ranges from `0.0` for black to `1.0` for white.
+    #[must_use]
+    #[inline]
+    pub fn relative_luminance(&self) -> f32 {
+        let linear = |c: f32| {
+            if c <= 0.039_28 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// Return either opaque black or opaque white, whichever has the highest
+    /// contrast with the `Color` when used as the color of text drawn on top
+    /// of it.
+    #[must_use]
+    #[inline]
+    pub fn contrasting_text(&self) -> Self {
+        if self.relative_luminance() > CONTRAST_THRESHOLD {
+            Self::new(0.0, 0.0, 0.0, 1.0)
+        } else {
+            Self::new(1.0, 1.0, 1.0, 1.0)
+        }
+    }
+
+    /// Look up a `Color` by its CSS name (e.g. `"cornflowerblue"`).
+    ///
+    /// Matching is case-insensitive. Returns `None` for unknown names.
+    #[must_use]
+    #[inline]
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("transparent") {
+            return Some(Self::new(0.0, 0.0, 0.0, 0.0));
+        }
+
+        NAMED_COLORS
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, rgb)| Self::from_rgb_u32(rgb))
+    }
 }
 
 impl From<(u8, u8, u8, u8)> for Color {
@@ -194,3 +397,440 @@ impl From<Color> for [f32; 4] {
         [color.r, color.g, color.b, color.a]
     }
 }
+
+/// Compact (de)serialization of a [`Color`] as an `[r, g, b, a]` array.
+///
+/// By default, a `Color` serializes as a map of its components, which is easy
+/// to read (e.g. in saved games), but verbose. This module can be used with
+/// `#[serde(with = "common::color::compact")]` to shrink payloads sent between
+/// the engine and plugins.
+pub mod compact {
+    use super::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize a `Color` as an `[r, g, b, a]` array.
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer error, if any.
+    #[allow(clippy::trivially_copy_pass_by_ref)] // signature required by serde
+    #[inline]
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        <[f32; 4]>::from(*color).serialize(serializer)
+    }
+
+    /// Deserialize a `Color` from an `[r, g, b, a]` array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not an array of four numbers.
+    #[inline]
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        <[f32; 4]>::deserialize(deserializer).map(Color::from)
+    }
+}
+
+/// The relative luminance at which black and white text have the same contrast
+/// ratio with the background, `sqrt(1.05 * 0.05) - 0.05`.
+const CONTRAST_THRESHOLD: f32 = 0.179_129;
+
+/// The CSS named colors, as `0x00RRGGBB` values.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod hsl_hsv {
+        use super::*;
+
+        fn assert_close(a: (f32, f32, f32), b: (f32, f32, f32)) {
+            let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+
+            assert!(
+                close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2),
+                "{:?} != {:?}",
+                a,
+                b
+            );
+        }
+
+        #[test]
+        fn from_hsl() {
+            assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), Color::from_rgb(255, 0, 0));
+            assert_eq!(Color::from_hsl(120.0, 1.0, 0.5), Color::from_rgb(0, 255, 0));
+            assert_eq!(Color::from_hsl(240.0, 1.0, 0.5), Color::from_rgb(0, 0, 255));
+            assert_eq!(Color::from_hsl(360.0, 1.0, 0.5), Color::from_rgb(255, 0, 0));
+            assert_eq!(
+                Color::from_hsl(0.0, 0.0, 1.0),
+                Color::from_rgb(255, 255, 255)
+            );
+        }
+
+        #[test]
+        fn from_hsv() {
+            assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), Color::from_rgb(255, 0, 0));
+            assert_eq!(
+                Color::from_hsv(-240.0, 1.0, 1.0),
+                Color::from_rgb(0, 255, 0)
+            );
+            assert_eq!(
+                Color::from_hsv(60.0, 1.0, 1.0),
+                Color::from_rgb(255, 255, 0)
+            );
+            assert_eq!(Color::from_hsv(0.0, 0.0, 0.0), Color::from_rgb(0, 0, 0));
+        }
+
+        #[test]
+        fn achromatic() {
+            let grey = Color::new(0.5, 0.5, 0.5, 1.0);
+
+            assert_eq!(grey.to_hsl(), (0.0, 0.0, 0.5));
+            assert_eq!(grey.to_hsv(), (0.0, 0.0, 0.5));
+            assert_eq!(Color::from_hsl(123.0, 0.0, 0.5), grey);
+            assert_eq!(Color::from_hsv(123.0, 0.0, 0.5), grey);
+
+            assert_eq!(Color::new(0.0, 0.0, 0.0, 1.0).to_hsv(), (0.0, 0.0, 0.0));
+            assert_eq!(Color::new(1.0, 1.0, 1.0, 1.0).to_hsl(), (0.0, 0.0, 1.0));
+        }
+
+        #[test]
+        fn round_trip() {
+            for &(h, s, l) in &[(0.0, 1.0, 0.5), (200.0, 0.3, 0.7), (330.0, 0.8, 0.2)] {
+                assert_close(Color::from_hsl(h, s, l).to_hsl(), (h, s, l));
+                assert_close(Color::from_hsv(h, s, l).to_hsv(), (h, s, l));
+            }
+        }
+    }
+
+    mod premultiply {
+        use super::*;
+
+        #[test]
+        fn works() {
+            assert_eq!(
+                Color::new(1.0, 0.5, 0.25, 0.5).premultiply(),
+                Color::new(0.5, 0.25, 0.125, 0.5)
+            );
+        }
+
+        #[test]
+        fn opaque() {
+            let color = Color::new(1.0, 0.5, 0.25, 1.0);
+
+            assert_eq!(color.premultiply(), color);
+        }
+
+        #[test]
+        fn transparent() {
+            assert_eq!(
+                Color::new(1.0, 0.5, 0.25, 0.0).premultiply(),
+                Color::new(0.0, 0.0, 0.0, 0.0)
+            );
+        }
+    }
+
+    mod unpremultiply {
+        use super::*;
+
+        #[test]
+        fn works() {
+            assert_eq!(
+                Color::new(0.5, 0.25, 0.125, 0.5).unpremultiply(),
+                Color::new(1.0, 0.5, 0.25, 0.5)
+            );
+        }
+
+        #[test]
+        fn transparent() {
+            assert_eq!(
+                Color::new(0.5, 0.25, 0.125, 0.0).unpremultiply(),
+                Color::new(0.0, 0.0, 0.0, 0.0)
+            );
+        }
+    }
+
+    mod new_clamped {
+        use super::*;
+
+        #[test]
+        fn in_range() {
+            assert_eq!(
+                Color::new_clamped(0.0, 0.25, 0.5, 1.0),
+                Color::new(0.0, 0.25, 0.5, 1.0)
+            );
+        }
+
+        #[test]
+        fn out_of_range() {
+            assert_eq!(
+                Color::new_clamped(-1.0, 2.0, f32::INFINITY, f32::NEG_INFINITY),
+                Color::new(0.0, 1.0, 1.0, 0.0)
+            );
+        }
+
+        #[test]
+        fn nan() {
+            assert_eq!(
+                Color::new_clamped(f32::NAN, 0.5, f32::NAN, 1.0),
+                Color::new(0.0, 0.5, 0.0, 1.0)
+            );
+        }
+    }
+
+    mod relative_luminance {
+        use super::*;
+
+        fn assert_close(a: f32, b: f32) {
+            assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+        }
+
+        #[test]
+        fn known_values() {
+            assert_close(Color::from_rgb(255, 255, 255).relative_luminance(), 1.0);
+            assert_close(Color::from_rgb(0, 0, 0).relative_luminance(), 0.0);
+            assert_close(Color::from_rgb(255, 0, 0).relative_luminance(), 0.2126);
+            assert_close(Color::from_rgb(0, 255, 0).relative_luminance(), 0.7152);
+            assert_close(Color::from_rgb(0, 0, 255).relative_luminance(), 0.0722);
+            assert_close(Color::from_rgb(128, 128, 128).relative_luminance(), 0.2158);
+        }
+
+        #[test]
+        fn ignores_alpha() {
+            assert_eq!(
+                Color::new(0.5, 0.5, 0.5, 0.0).relative_luminance(),
+                Color::new(0.5, 0.5, 0.5, 1.0).relative_luminance()
+            );
+        }
+    }
+
+    mod contrasting_text {
+        use super::*;
+
+        const BLACK: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+        const WHITE: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+
+        #[test]
+        fn extremes() {
+            assert_eq!(WHITE.contrasting_text(), BLACK);
+            assert_eq!(BLACK.contrasting_text(), WHITE);
+            assert_eq!(Color::from_rgb(255, 255, 0).contrasting_text(), BLACK);
+            assert_eq!(Color::from_rgb(0, 0, 128).contrasting_text(), WHITE);
+        }
+
+        #[test]
+        fn midpoint() {
+            // Greys of 118 (luminance ~0.181) and 117 (~0.178) straddle the
+            // threshold.
+            assert_eq!(Color::from_rgb(118, 118, 118).contrasting_text(), BLACK);
+            assert_eq!(Color::from_rgb(117, 117, 117).contrasting_text(), WHITE);
+        }
+    }
+
+    mod compact {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper(#[serde(with = "super::super::compact")] Color);
+
+        #[test]
+        fn round_trip() {
+            let color = Wrapper(Color::new(1.0, 0.5, 0.25, 0.0));
+            let json = serde_json::to_string(&color).unwrap();
+
+            assert_eq!(json, "[1.0,0.5,0.25,0.0]");
+            assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), color);
+        }
+
+        #[test]
+        fn invalid() {
+            assert!(serde_json::from_str::<Wrapper>("[1.0,0.5,0.25]").is_err());
+            assert!(
+                serde_json::from_str::<Wrapper>(r#"{"r":1.0,"g":0.5,"b":0.25,"a":0.0}"#).is_err()
+            );
+        }
+    }
+
+    mod from_name {
+        use super::*;
+
+        #[test]
+        fn works() {
+            assert_eq!(Color::from_name("red"), Some(Color::from_rgb(255, 0, 0)));
+            assert_eq!(
+                Color::from_name("cornflowerblue"),
+                Some(Color::from_rgb(100, 149, 237))
+            );
+            assert_eq!(
+                Color::from_name("transparent"),
+                Some(Color::new(0.0, 0.0, 0.0, 0.0))
+            );
+        }
+
+        #[test]
+        fn case_insensitive() {
+            assert_eq!(Color::from_name("RED"), Color::from_name("red"));
+            assert_eq!(
+                Color::from_name("CornflowerBlue"),
+                Color::from_name("cornflowerblue")
+            );
+            assert_eq!(Color::from_name("Transparent").map(|c| c.a), Some(0.0));
+        }
+
+        #[test]
+        fn unknown() {
+            assert_eq!(Color::from_name("not-a-color"), None);
+            assert_eq!(Color::from_name(""), None);
+        }
+    }
+}
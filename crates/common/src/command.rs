@@ -0,0 +1,30 @@
+//! Commands sent by plugins to the engine.
+
+use crate::{Color, Deserialize, Serialize};
+
+/// A command a plugin sends to the engine.
+///
+/// Commands are collected while a plugin runs, and processed by the engine
+/// once the plugin finishes running, in the order in which they were issued.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Command {
+    /// Stop the game and close the window.
+    Quit,
+
+    /// Switch the window to (`true`) or from (`false`) fullscreen mode.
+    Fullscreen(bool),
+
+    /// Save the current state of the game.
+    Save,
+
+    /// Hide (`true`) or show (`false`) the mouse cursor while it is over the
+    /// window.
+    CursorHidden(bool),
+
+    /// Change the color in which the background of the game is rendered.
+    Background(#[serde(with = "crate::color::compact")] Color),
+
+    /// Capture the next frame drawn, and pass it to the callback the engine
+    /// was built with.
+    CaptureFrame,
+}
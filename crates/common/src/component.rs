@@ -1,6 +1,6 @@
 //! Component related items.
 
-use crate::{Deserialize, Serialize, Shape};
+use crate::{Color, Deserialize, Serialize, Shape};
 
 /// A piece of a widget.
 ///
@@ -8,12 +8,193 @@ use crate::{Deserialize, Serialize, Shape};
 ///
 /// A component consists of one primitive shape, and the position of that shape
 /// relative to the top-left of the widget.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Component {
     /// The shape of the widget component.
+    #[serde(rename = "s")]
     pub shape: Shape,
 
     /// The relative position of the component measuring from the top-left of
     /// the widget.
+    #[serde(rename = "c")]
     pub coordinates: (f32, f32),
+
+    /// A color multiplied into the colors of the shape when rendered.
+    ///
+    /// This allows recoloring a component (e.g. flashing it red) without
+    /// changing the state of its widget. Defaults to white, which leaves the
+    /// colors unchanged.
+    #[serde(rename = "t", default)]
+    pub tint: Color,
+
+    /// The clockwise rotation of the component (in radians).
+    ///
+    /// The shape is rotated around the origin of the component, which is the
+    /// top-left of the (unrotated) shape, at the coordinates of the component.
+    #[serde(rename = "r", default)]
+    pub rotation: f32,
+}
+
+impl Component {
+    /// Create a new, untinted component.
+    #[inline]
+    #[must_use]
+    pub fn new(shape: Shape, coordinates: (f32, f32)) -> Self {
+        Self {
+            shape,
+            coordinates,
+            tint: Color::default(),
+            rotation: 0.0,
+        }
+    }
+
+    /// Rotate a point relative to the origin of the component by the rotation
+    /// of the component.
+    ///
+    /// This converts points of the shape to where they are rendered.
+    #[inline]
+    #[must_use]
+    pub fn rotate(&self, point: (f32, f32)) -> (f32, f32) {
+        rotate(point, self.rotation)
+    }
+
+    /// Rotate a point relative to the origin of the component in the opposite
+    /// direction of the rotation of the component.
+    ///
+    /// This converts a rendered point back to the point of the (unrotated)
+    /// shape, e.g. to check if it falls within the shape.
+    #[inline]
+    #[must_use]
+    pub fn unrotate(&self, point: (f32, f32)) -> (f32, f32) {
+        rotate(point, -self.rotation)
+    }
+
+    /// Get the color in which to render the given color of the shape, after
+    /// applying the tint of the component.
+    #[inline]
+    #[must_use]
+    pub fn tinted(&self, color: Color) -> Color {
+        color.multiply(self.tint)
+    }
+}
+
+/// Rotate a point clockwise around the origin by the given angle (in radians).
+fn rotate((x, y): (f32, f32), angle: f32) -> (f32, f32) {
+    let (sin, cos) = angle.sin_cos();
+
+    (x * cos - y * sin, x * sin + y * cos)
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::Border;
+
+    mod serialize {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let component = Component {
+                shape: Shape::Circle {
+                    radius: 5.0,
+                    fill: Color::from_rgb(100, 149, 237),
+                    border: Some(Border {
+                        color: Color::default(),
+                        width: 1.0,
+                        glow: None,
+                    }),
+                },
+                coordinates: (10.0, 20.0),
+                tint: Color::from_rgb(255, 0, 0),
+                rotation: 1.5,
+            };
+
+            let json = serde_json::to_string(&component).unwrap();
+            let result: Component = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(result, component);
+        }
+
+        #[test]
+        fn default_tint() {
+            let json = r#"{"s":{"k":"rectangle","w":1.0,"h":1.0,"c":{"r":1.0,"g":1.0,"b":1.0,"a":1.0}},"c":[0.0,0.0]}"#;
+            let component: Component = serde_json::from_str(json).unwrap();
+
+            assert_eq!(component.tint, Color::default());
+            assert_eq!(component.rotation, 0.0);
+        }
+    }
+
+    mod rotate {
+        use super::*;
+
+        fn component(rotation: f32) -> Component {
+            let shape = Shape::Circle {
+                radius: 5.0,
+                fill: Color::default(),
+                border: None,
+            };
+
+            Component {
+                rotation,
+                ..Component::new(shape, (0.0, 0.0))
+            }
+        }
+
+        #[test]
+        fn unrotated() {
+            assert_eq!(component(0.0).rotate((10.0, 5.0)), (10.0, 5.0));
+        }
+
+        #[test]
+        fn quarter_turn() {
+            let component = component(std::f32::consts::FRAC_PI_2);
+
+            // Clockwise on screen, as the y-axis points down.
+            let (x, y) = component.rotate((10.0, 0.0));
+            assert!(x.abs() < 1e-4 && (y - 10.0).abs() < 1e-4);
+
+            let (x, y) = component.unrotate((x, y));
+            assert!((x - 10.0).abs() < 1e-4 && y.abs() < 1e-4);
+        }
+    }
+
+    mod tinted {
+        use super::*;
+
+        #[test]
+        fn red_tint() {
+            let shape = Shape::Rectangle {
+                width: 10.0,
+                height: 10.0,
+                color: Color::from_rgb(255, 255, 255),
+                rotation: 0.0,
+                border: None,
+            };
+
+            let mut component = Component::new(shape, (0.0, 0.0));
+            component.tint = Color::from_rgb(255, 0, 0);
+
+            let color = component.tinted(Color::from_rgb(255, 255, 255));
+            assert_eq!(color, Color::from_rgb(255, 0, 0));
+        }
+
+        #[test]
+        fn no_tint() {
+            let shape = Shape::Rectangle {
+                width: 10.0,
+                height: 10.0,
+                color: Color::from_rgb(100, 149, 237),
+                rotation: 0.0,
+                border: None,
+            };
+
+            let component = Component::new(shape, (0.0, 0.0));
+            let color = Color::from_rgb(100, 149, 237);
+
+            assert_eq!(component.tinted(color), color);
+        }
+    }
 }
@@ -1,7 +1,11 @@
 //! Events used to communicate between player, plugin and widget.
 
 use crate::{Deserialize, Serialize, Value};
-use std::collections::{HashMap, HashSet};
+use serde::Serializer;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+};
 
 /// A list of events the engine can trigger.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -17,6 +21,14 @@ pub enum Event {
     /// Plugins consume widget events and transform their state based on these
     /// events.
     Widget {
+        /// The name of the plugin owning the widget to which this event
+        /// belongs.
+        ///
+        /// Widget names are only unique per plugin, this is used by the engine
+        /// to only pass the event on to the plugin owning the widget.
+        #[serde(default)]
+        plugin: String,
+
         /// The name of widget to which this event belongs.
         ///
         /// This is used by plugins to match events against specific widgets
@@ -29,6 +41,18 @@ pub enum Event {
     },
 }
 
+impl fmt::Display for Event {
+    /// Format the event in a compact, readable form, e.g.
+    /// `widget "circle": move(direction="Up")`.
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(input) => write!(f, "input: {}", input),
+            Self::Widget { name, event, .. } => write!(f, "widget {:?}: {}", name, event),
+        }
+    }
+}
+
 /// An event triggered via an input method.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Input {
@@ -38,15 +62,62 @@ pub enum Input {
     /// A keyboard key event.
     Keyboard {
         /// A set of keys captured in the input event.
+        ///
+        /// The keys are serialized in sorted order, so that two events with
+        /// the same keys always serialize identically.
+        #[serde(serialize_with = "serialize_sorted")]
         keys: HashSet<Key>,
     },
 
+    /// A key that was pressed since the previous input.
+    ///
+    /// Unlike [`Input::Keyboard`], which is triggered for as long as keys are
+    /// held, this event is only triggered once for every key press.
+    KeyDown(Key),
+
+    /// A key that was released since the previous input.
+    KeyUp(Key),
+
     /// A click (down and up) of a button.
     MouseClick { button: MouseButton, x: f32, y: f32 },
 
+    /// A second click of a button, shortly after and close to the first.
+    ///
+    /// The event follows the [`Input::MouseClick`] of the second click.
+    DoubleClick { button: MouseButton, x: f32, y: f32 },
+
     /// A pressed (down) button.
     MousePress { button: MouseButton, x: f32, y: f32 },
 
+    /// A released (up) button, that was pressed during the previous input.
+    MouseRelease { button: MouseButton, x: f32, y: f32 },
+
+    /// The start of a drag, at the position where the button was pressed.
+    ///
+    /// Drag events are not triggered by the player directly. The engine
+    /// tracks a press per widget, and triggers a drag once the pointer moves
+    /// far enough away from where the button was pressed.
+    DragStart { button: MouseButton, x: f32, y: f32 },
+
+    /// The pointer position during a drag, triggered on every update until
+    /// the drag ends.
+    DragMove { button: MouseButton, x: f32, y: f32 },
+
+    /// The end of a drag, at the position where the button was released.
+    DragEnd { button: MouseButton, x: f32, y: f32 },
+
+    /// A gamepad button that was pressed or released.
+    GamepadButton {
+        button: GamepadButton,
+        pressed: bool,
+    },
+
+    /// A change in position of a gamepad axis.
+    ///
+    /// The value ranges from `-1.0` to `1.0`. For sticks, positive values
+    /// point right (on the X axis) or up (on the Y axis).
+    GamepadAxis { axis: GamepadAxis, value: f32 },
+
     // derivatives (TODO: see RFC006)
     /// Something has gained focus.
     Focus,
@@ -55,6 +126,51 @@ pub enum Input {
     Blur,
 }
 
+impl fmt::Display for Input {
+    /// Format the input in a compact, readable form, e.g. `click(Left, 10, 20)`.
+    ///
+    /// Keyboard keys are listed in sorted order.
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pointer(x, y) => write!(f, "pointer({}, {})", x, y),
+            Self::Keyboard { keys } => {
+                let keys: Vec<_> = keys
+                    .iter()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(|key| format!("{:?}", key))
+                    .collect();
+
+                write!(f, "keyboard({})", keys.join(", "))
+            }
+            Self::KeyDown(key) => write!(f, "key_down({:?})", key),
+            Self::KeyUp(key) => write!(f, "key_up({:?})", key),
+            Self::MouseClick { button, x, y } => write!(f, "click({:?}, {}, {})", button, x, y),
+            Self::DoubleClick { button, x, y } => {
+                write!(f, "double_click({:?}, {}, {})", button, x, y)
+            }
+            Self::MousePress { button, x, y } => write!(f, "press({:?}, {}, {})", button, x, y),
+            Self::MouseRelease { button, x, y } => {
+                write!(f, "release({:?}, {}, {})", button, x, y)
+            }
+            Self::DragStart { button, x, y } => {
+                write!(f, "drag_start({:?}, {}, {})", button, x, y)
+            }
+            Self::DragMove { button, x, y } => {
+                write!(f, "drag_move({:?}, {}, {})", button, x, y)
+            }
+            Self::DragEnd { button, x, y } => write!(f, "drag_end({:?}, {}, {})", button, x, y),
+            Self::GamepadButton { button, pressed } => {
+                write!(f, "gamepad_button({:?}, {})", button, pressed)
+            }
+            Self::GamepadAxis { axis, value } => write!(f, "gamepad_axis({:?}, {})", axis, value),
+            Self::Focus => f.write_str("focus"),
+            Self::Blur => f.write_str("blur"),
+        }
+    }
+}
+
 /// An event triggered by a widget.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Widget {
@@ -94,34 +210,107 @@ impl Widget {
     }
 
     /// Add a new attribute to the event.
+    ///
+    /// If the value cannot be serialized, the error is logged and the
+    /// attribute is skipped.
     #[inline]
     pub fn add_attribute<T: serde::ser::Serialize>(&mut self, key: impl Into<String>, value: T) {
-        #[allow(clippy::match_wild_err_arm)]
+        let key = key.into();
+
         match serde_json::to_value(value) {
-            Ok(value) => self.attributes.insert(key.into(), value),
-            Err(_) => todo!("logging"),
+            Ok(value) => {
+                self.attributes.insert(key, value);
+            }
+            Err(err) => log::error!("unable to serialize attribute `{}`: {}", key, err),
         };
     }
 }
 
+impl fmt::Display for Widget {
+    /// Format the event in a compact, readable form, e.g.
+    /// `resized(delta=1.0)`.
+    ///
+    /// Attributes are listed in sorted order, so that two equal events are
+    /// always formatted identically.
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+
+        if self.attributes.is_empty() {
+            return Ok(());
+        }
+
+        let attributes: Vec<_> = self
+            .attributes
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+
+        write!(f, "({})", attributes.join(", "))
+    }
+}
+
 /// A list of keyboard keys supported by the engine.
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// Keys are ordered by their declaration order below.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 #[allow(clippy::missing_docs_in_private_items)]
 pub enum Key {
     // letter keys
     A,
     B,
+    C,
     D,
     E,
+    F,
     G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
     Q,
     R,
     S,
+    T,
+    U,
+    V,
     W,
+    X,
+    Y,
+    Z,
+
+    // digit keys
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
 
     // other keys
+    Enter,
+    Escape,
     Minus,
     Plus,
+    Space,
+    Tab,
+
+    // arrow keys
+    Up,
+    Down,
+    Left,
+    Right,
 
     // modifier keys
     Ctrl,
@@ -137,3 +326,271 @@ pub enum MouseButton {
     Right,
     Other,
 }
+
+/// Buttons of a gamepad.
+///
+/// The action buttons are named after their position on the gamepad, e.g.
+/// `South` is the "A" button on an Xbox controller.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::missing_docs_in_private_items)]
+pub enum GamepadButton {
+    // action buttons
+    South,
+    East,
+    North,
+    West,
+
+    // shoulder buttons
+    LeftTrigger,
+    RightTrigger,
+
+    // directional pad
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+
+    // menu buttons
+    Select,
+    Start,
+
+    Other,
+}
+
+/// Axes of a gamepad.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::missing_docs_in_private_items)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    Other,
+}
+
+/// Merge consecutive pointer moves into the latest one.
+///
+/// High-frequency input can produce many pointer moves within a single tick,
+/// of which only the final position is relevant. Discrete events (clicks, key
+/// presses, etc.) are kept intact, and pointer moves on either side of such an
+/// event are not merged, to retain the order in which the events occurred.
+#[inline]
+pub fn coalesce_pointer_moves(events: &mut Vec<Event>) {
+    let is_pointer = |event: &Event| matches!(event, Event::Input(Input::Pointer(..)));
+    let mut coalesced: Vec<Event> = Vec::with_capacity(events.len());
+
+    for event in events.drain(..) {
+        match coalesced.last_mut() {
+            Some(last) if is_pointer(last) && is_pointer(&event) => *last = event,
+            _ => coalesced.push(event),
+        }
+    }
+
+    *events = coalesced;
+}
+
+/// Compare the keys pressed during the previous and the current input, and
+/// return a [`Input::KeyDown`] event for every newly pressed key, followed by
+/// a [`Input::KeyUp`] event for every released key.
+///
+/// Both groups are sorted by key, so that the same change in pressed keys
+/// always results in the same events.
+#[inline]
+#[must_use]
+pub fn key_transitions(previous: &HashSet<Key>, current: &HashSet<Key>) -> Vec<Input> {
+    let difference = |a: &HashSet<Key>, b: &HashSet<Key>| {
+        a.difference(b)
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+    };
+
+    difference(current, previous)
+        .map(Input::KeyDown)
+        .chain(difference(previous, current).map(Input::KeyUp))
+        .collect()
+}
+
+/// Serialize a set of keys in sorted order.
+fn serialize_sorted<S: Serializer>(keys: &HashSet<Key>, serializer: S) -> Result<S::Ok, S::Error> {
+    keys.iter().collect::<BTreeSet<_>>().serialize(serializer)
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod keyboard {
+        use super::*;
+
+        #[test]
+        fn serialize_sorted() {
+            let keyboard = |keys: Vec<Key>| {
+                let keys = keys.into_iter().collect();
+                serde_json::to_string(&Input::Keyboard { keys }).unwrap()
+            };
+
+            let a = keyboard(vec![Key::Shift, Key::W, Key::A, Key::Ctrl]);
+            let b = keyboard(vec![Key::A, Key::Ctrl, Key::W, Key::Shift]);
+
+            assert_eq!(a, b);
+            assert_eq!(a, r#"{"Keyboard":{"keys":["A","W","Ctrl","Shift"]}}"#);
+        }
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn widget_event() {
+            let event = |attributes: Vec<(&str, Value)>| {
+                let mut event = Widget::new("move");
+                for (key, value) in attributes {
+                    event.add_attribute(key, value);
+                }
+
+                Event::Widget {
+                    plugin: "test".to_owned(),
+                    name: "circle".to_owned(),
+                    event,
+                }
+            };
+
+            let a = event(vec![
+                ("speed", "Fast".into()),
+                ("direction", "Up".into()),
+                ("amount", 2.5.into()),
+            ]);
+            let b = event(vec![
+                ("amount", 2.5.into()),
+                ("direction", "Up".into()),
+                ("speed", "Fast".into()),
+            ]);
+
+            assert_eq!(a.to_string(), b.to_string());
+            assert_eq!(
+                a.to_string(),
+                r#"widget "circle": move(amount=2.5, direction="Up", speed="Fast")"#
+            );
+            assert_eq!(event(vec![]).to_string(), r#"widget "circle": move"#);
+        }
+
+        #[test]
+        fn input_event() {
+            let keys = vec![Key::Shift, Key::W, Key::A].into_iter().collect();
+            let click = Input::MouseClick {
+                button: MouseButton::Left,
+                x: 10.0,
+                y: 20.5,
+            };
+
+            assert_eq!(
+                Event::Input(Input::Keyboard { keys }).to_string(),
+                "input: keyboard(A, W, Shift)"
+            );
+            assert_eq!(
+                Event::Input(click).to_string(),
+                "input: click(Left, 10, 20.5)"
+            );
+            assert_eq!(Event::Input(Input::Focus).to_string(), "input: focus");
+            assert_eq!(
+                Event::Input(Input::KeyUp(Key::Space)).to_string(),
+                "input: key_up(Space)"
+            );
+            assert_eq!(
+                Event::Input(Input::DragMove {
+                    button: MouseButton::Left,
+                    x: 1.0,
+                    y: 2.5
+                })
+                .to_string(),
+                "input: drag_move(Left, 1, 2.5)"
+            );
+            assert_eq!(
+                Event::Input(Input::GamepadAxis {
+                    axis: GamepadAxis::LeftStickX,
+                    value: -0.5
+                })
+                .to_string(),
+                "input: gamepad_axis(LeftStickX, -0.5)"
+            );
+        }
+    }
+
+    mod coalesce_pointer_moves {
+        use super::*;
+
+        #[test]
+        fn consecutive_moves() {
+            let mut events = vec![
+                Event::Input(Input::Pointer(1.0, 1.0)),
+                Event::Input(Input::Pointer(2.0, 2.0)),
+                Event::Input(Input::Pointer(3.0, 3.0)),
+            ];
+
+            coalesce_pointer_moves(&mut events);
+
+            assert_eq!(events, vec![Event::Input(Input::Pointer(3.0, 3.0))]);
+        }
+
+        #[test]
+        fn discrete_events() {
+            let click = Event::Input(Input::MouseClick {
+                button: MouseButton::Left,
+                x: 2.0,
+                y: 2.0,
+            });
+
+            let mut events = vec![
+                Event::Input(Input::Pointer(1.0, 1.0)),
+                Event::Input(Input::Pointer(2.0, 2.0)),
+                click.clone(),
+                Event::Input(Input::Pointer(3.0, 3.0)),
+                Event::Input(Input::Focus),
+            ];
+
+            coalesce_pointer_moves(&mut events);
+
+            assert_eq!(
+                events,
+                vec![
+                    Event::Input(Input::Pointer(2.0, 2.0)),
+                    click,
+                    Event::Input(Input::Pointer(3.0, 3.0)),
+                    Event::Input(Input::Focus),
+                ]
+            );
+        }
+    }
+
+    mod key_transitions {
+        use super::*;
+
+        fn keys(keys: Vec<Key>) -> HashSet<Key> {
+            keys.into_iter().collect()
+        }
+
+        #[test]
+        fn pressed_and_released() {
+            let previous = keys(vec![Key::A, Key::Shift]);
+            let current = keys(vec![Key::W, Key::D, Key::Shift]);
+
+            assert_eq!(
+                key_transitions(&previous, &current),
+                vec![
+                    Input::KeyDown(Key::D),
+                    Input::KeyDown(Key::W),
+                    Input::KeyUp(Key::A),
+                ]
+            );
+        }
+
+        #[test]
+        fn held() {
+            let held = keys(vec![Key::A, Key::Shift]);
+
+            assert!(key_transitions(&held, &held).is_empty());
+        }
+    }
+}
@@ -21,27 +21,196 @@
 )]
 
 mod canvas;
-mod color;
+pub mod color;
+mod command;
 mod component;
 pub mod event;
 mod registration;
 mod run_result;
 mod shape;
 mod state;
+mod time;
 pub mod widget;
 
-pub use canvas::Canvas;
+pub use canvas::{Canvas, Insets, Viewport};
 pub use color::Color;
+pub use command::Command;
 pub use component::Component;
 pub use event::{Event, Key};
-pub use registration::Registration;
+pub use registration::{Problem as RegistrationProblem, Registration};
 pub use run_result::RunResult;
 pub use shape::{Border, Shape};
 pub use state::{
-    Game as GameState, Plugin as PluginState, Transfer as StateTransfer, Widget as WidgetState,
-    WidgetWithPosition,
+    Game as GameState, Observer as StateObserver, Plugin as PluginState, Transfer as StateTransfer,
+    Widget as WidgetState, WidgetWithPosition,
 };
+pub use time::Time;
+pub use widget::Error as WidgetError;
 
 // A list of third-party exposed types used by both the engine and SDK.
 pub use serde::{de::DeserializeOwned, Deserialize, Serialize};
 pub use serde_json::{self, Value};
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    /// Snapshots of the JSON wire format shared by the engine and plugins.
+    ///
+    /// The state types use short (renamed) keys, which are easy to change by
+    /// accident. Any such change breaks compatibility between plugins and the
+    /// engine, so these tests assert the exact serialized output.
+    ///
+    /// Fixtures only contain maps with at most one entry, as the order of
+    /// `HashMap` keys is not stable.
+    mod wire_format {
+        use super::*;
+        use std::collections::HashMap;
+
+        const WIDGET: &str = r#"{"r":1,"c":[10.0,20.0],"f":false,"h":{"activated":"quit"},"v":true,"z":0,"l":"World","t":[1.0,1.0,1.0,1.0],"w":{"k":"MovingCircle","s":{"radius":10.0}},"p":0,"o":null,"d":false}"#;
+
+        fn widget() -> WidgetWithPosition {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+
+            let state = WidgetState::new(widget::Kind::MovingCircle, state);
+            let mut widget = WidgetWithPosition::new((10.0, 20.0), true, state);
+            widget.set_handler("activated", "quit");
+
+            widget
+        }
+
+        fn plugin() -> PluginState {
+            let mut state = HashMap::new();
+            state.insert("score", 1);
+
+            let mut widgets = HashMap::new();
+            widgets.insert("player", widget());
+
+            PluginState::new(state, widgets)
+        }
+
+        fn plugin_json() -> String {
+            format!(r#"{{"s":{{"score":1}},"w":{{"player":{}}}}}"#, WIDGET)
+        }
+
+        fn transfer() -> StateTransfer {
+            let mut borrowed = HashMap::new();
+            borrowed.insert("hud".to_owned(), PluginState::default());
+
+            StateTransfer {
+                owned: plugin(),
+                borrowed,
+                events: vec![
+                    Event::Input(event::Input::Pointer(1.0, 2.0)),
+                    Event::Widget {
+                        plugin: "game".to_owned(),
+                        name: "player".to_owned(),
+                        event: event::Widget::new("activated"),
+                    },
+                ],
+                history: vec![vec![Event::Input(event::Input::Focus)]],
+                shared: HashMap::new(),
+                time: Time::new(20_000_000, 10_000_000),
+                canvas: Canvas::new(800, 600),
+            }
+        }
+
+        fn transfer_json() -> String {
+            format!(
+                concat!(
+                    r#"{{"o":{},"b":{{"hud":{{"s":{{}},"w":{{}}}}}},"#,
+                    r#""e":[{{"Input":{{"Pointer":[1.0,2.0]}}}},"#,
+                    r#"{{"Widget":{{"plugin":"game","name":"player","event":{{"name":"activated","attributes":{{}}}}}}}}],"#,
+                    r#""h":[[{{"Input":"Focus"}}]],"#,
+                    r#""t":{{"e":20000000,"d":10000000}},"#,
+                    r#""c":{{"width":800,"height":600,"insets":{{"top":0.0,"bottom":0.0,"left":0.0,"right":0.0}},"density":1.0}}}}"#,
+                ),
+                plugin_json()
+            )
+        }
+
+        /// Serialize `value`, assert the output matches `expected`, and assert
+        /// that deserializing the output and serializing it again yields the
+        /// same output.
+        fn assert_wire_format<T: Serialize + DeserializeOwned>(value: &T, expected: &str) {
+            let json = serde_json::to_string(value).unwrap();
+            assert_eq!(json, expected);
+
+            let value: T = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&value).unwrap(), expected);
+        }
+
+        #[test]
+        fn widget_with_position() {
+            assert_wire_format(&widget(), WIDGET);
+        }
+
+        #[test]
+        fn plugin_state() {
+            assert_wire_format(&plugin(), &plugin_json());
+        }
+
+        #[test]
+        fn state_transfer() {
+            assert_wire_format(&transfer(), &transfer_json());
+        }
+
+        #[test]
+        fn state_transfer_without_history() {
+            let transfer = StateTransfer {
+                history: vec![],
+                ..transfer()
+            };
+
+            let expected = transfer_json().replace(r#""h":[[{"Input":"Focus"}]],"#, "");
+            assert_wire_format(&transfer, &expected);
+        }
+
+        #[test]
+        fn state_transfer_without_time() {
+            // Transfers serialized before the time was added default to zero.
+            let json = transfer_json().replace(r#""t":{"e":20000000,"d":10000000},"#, "");
+            let transfer: StateTransfer = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(transfer.time, Time::default());
+        }
+
+        #[test]
+        fn run_result() {
+            let mut paused = HashMap::new();
+            paused.insert("hud".to_owned(), true);
+
+            let result = RunResult {
+                error: Some("oops".to_owned()),
+                state: Some(transfer()),
+                paused: Some(paused),
+                commands: Some(vec![
+                    Command::Quit,
+                    Command::Fullscreen(true),
+                    Command::Save,
+                    Command::Background(Color::new(1.0, 0.0, 0.0, 1.0)),
+                ]),
+            };
+
+            let expected = format!(
+                concat!(
+                    r#"{{"e":"oops","s":{},"p":{{"hud":true}},"#,
+                    r#""c":["Quit",{{"Fullscreen":true}},"Save",{{"Background":[1.0,0.0,0.0,1.0]}}]}}"#,
+                ),
+                transfer_json()
+            );
+
+            assert_wire_format(&result, &expected);
+        }
+
+        #[test]
+        fn empty_run_result() {
+            assert_wire_format(
+                &RunResult::empty(),
+                r#"{"e":null,"s":null,"p":null,"c":null}"#,
+            );
+        }
+    }
+}
@@ -1,7 +1,7 @@
 //! Registration details of a plugin.
 
 use crate::{widget, Deserialize, Serialize, Value, WidgetWithPosition};
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 /// The `Registration` type is used by plugins in the `init` function to expose
 /// relevant details to the engine before the plugin is added to the engine's
@@ -25,6 +25,69 @@ pub struct Registration {
     /// A plugin can read the state of other plugins it depends on.
     #[serde(rename = "d")]
     pub dependencies: Option<Vec<String>>,
+
+    /// The keys of the state shared between plugins, which this plugin wants
+    /// to read and write, with their initial values.
+    ///
+    /// See [`GameState::write_shared`](crate::GameState::write_shared) for
+    /// how writes of multiple plugins to the same key are merged.
+    #[serde(rename = "x")]
+    pub shared: Option<HashMap<String, Value>>,
+
+    /// The minimum version of the engine the plugin is compatible with.
+    ///
+    /// The engine refuses to load a plugin that requires a newer version.
+    #[serde(rename = "v")]
+    pub min_engine_version: Option<String>,
+
+    /// The minimum number of engine ticks between two runs of the plugin.
+    ///
+    /// `None` means the plugin runs on every tick.
+    #[serde(rename = "u")]
+    pub update_interval: Option<u32>,
+
+    /// A list of problems found while building the registration.
+    ///
+    /// These are reported by the engine when the plugin is registered.
+    #[serde(rename = "p", default, skip_serializing_if = "Vec::is_empty")]
+    problems: Vec<Problem>,
+}
+
+/// A problem with the registration of a plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Problem {
+    /// The plugin has no name.
+    EmptyName,
+
+    /// A widget has no name.
+    EmptyWidgetName,
+
+    /// Two or more widgets share the same name.
+    DuplicateWidgetName(String),
+
+    /// A widget attribute is missing, unknown, or has a value of the wrong
+    /// type.
+    InvalidWidget {
+        /// The name of the widget.
+        widget: String,
+
+        /// A description of the problem.
+        reason: String,
+    },
+}
+
+impl fmt::Display for Problem {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => f.write_str("empty plugin name"),
+            Self::EmptyWidgetName => f.write_str("empty widget name"),
+            Self::DuplicateWidgetName(name) => write!(f, "duplicate widget name `{}`", name),
+            Self::InvalidWidget { widget, reason } => {
+                write!(f, "invalid widget `{}`: {}", widget, reason)
+            }
+        }
+    }
 }
 
 impl Registration {
@@ -48,22 +111,180 @@ impl Registration {
     }
 
     /// Define a key/value pair of a widget this plugin wants to control.
+    ///
+    /// Widget names have to be unique. If a widget with the same name already
+    /// exists, the existing widget is kept, and a problem is reported.
+    ///
+    /// The attributes of the widget are validated (see
+    /// [`widget::Builder::validate`]), and a problem is reported for each
+    /// invalid attribute.
     #[inline]
     #[must_use]
     pub fn widget(mut self, widget: widget::Builder) -> Self {
+        let errors = widget.validate().err().unwrap_or_default();
         let (name, widget) = widget.build();
+        let widgets = self.widgets.get_or_insert(HashMap::default());
+
+        if name.is_empty() {
+            self.problems.push(Problem::EmptyWidgetName);
+        }
+
+        for error in errors {
+            self.problems.push(Problem::InvalidWidget {
+                widget: name.clone(),
+                reason: error.to_string(),
+            });
+        }
+
+        if widgets.contains_key(&name) {
+            self.problems.push(Problem::DuplicateWidgetName(name));
+        } else {
+            widgets.insert(name, widget);
+        }
+
+        self
+    }
 
-        self.widgets
+    /// Define a key of the state shared between plugins, which this plugin
+    /// wants to read and write.
+    ///
+    /// The value is only used if no other plugin declared the key before.
+    #[inline]
+    pub fn shared(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.shared
             .get_or_insert(HashMap::default())
-            .insert(name, widget);
+            .insert(key.into(), value);
 
         self
     }
 
+    /// Set the minimum version of the engine (e.g. `"0.2.0"`) the plugin is
+    /// compatible with.
+    #[inline]
+    pub fn min_engine_version(mut self, version: impl Into<String>) -> Self {
+        self.min_engine_version = Some(version.into());
+        self
+    }
+
+    /// Run the plugin once every `ticks` engine ticks, instead of on every
+    /// tick.
+    ///
+    /// This is useful for plugins with slow-changing logic (e.g. turn-based
+    /// games), to avoid wasting CPU time. Events that happen on skipped ticks
+    /// are not delivered to the plugin, use the event history to observe them.
+    #[inline]
+    pub fn update_interval(mut self, ticks: u32) -> Self {
+        self.update_interval = Some(ticks);
+        self
+    }
+
     /// Add a dependency to the plugin.
     #[inline]
     pub fn dependency(mut self, name: impl Into<String>) -> Self {
         self.dependencies.get_or_insert(vec![]).push(name.into());
         self
     }
+
+    /// Finalize the registration.
+    ///
+    /// # Errors
+    ///
+    /// Returns all problems found while building the registration.
+    #[inline]
+    pub fn build(self) -> Result<Self, Vec<Problem>> {
+        let mut problems = self.problems.clone();
+
+        if self.name.is_empty() {
+            problems.insert(0, Problem::EmptyName);
+        }
+
+        if problems.is_empty() {
+            Ok(self)
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod build {
+        use super::*;
+        use crate::Color;
+
+        fn circle(name: &str) -> widget::Builder {
+            widget::Builder::new(name, widget::Kind::MovingCircle).attribute("radius", 10.0)
+        }
+
+        #[test]
+        fn works() {
+            let registration = Registration::new("foo")
+                .widget(circle("bar"))
+                .widget(circle("baz"))
+                .build()
+                .unwrap();
+
+            assert_eq!(registration.widgets.unwrap().len(), 2);
+        }
+
+        #[test]
+        fn duplicate_widget_name() {
+            let problems = Registration::new("foo")
+                .widget(circle("bar"))
+                .widget(
+                    widget::Builder::new("bar", widget::Kind::ButtonRectangle)
+                        .attribute("width", 10.0)
+                        .attribute("height", 10.0)
+                        .attribute("idle_color", Color::default()),
+                )
+                .build()
+                .unwrap_err();
+
+            assert_eq!(
+                problems,
+                vec![Problem::DuplicateWidgetName("bar".to_owned())]
+            );
+        }
+
+        #[test]
+        fn multiple_problems() {
+            let problems = Registration::new("")
+                .widget(circle(""))
+                .build()
+                .unwrap_err();
+
+            assert_eq!(problems, vec![Problem::EmptyName, Problem::EmptyWidgetName]);
+        }
+
+        #[test]
+        fn invalid_widget() {
+            let problems = Registration::new("foo")
+                .widget(circle("bar").attribute("radius", "large"))
+                .build()
+                .unwrap_err();
+
+            assert_eq!(problems.len(), 1);
+            assert!(problems[0].to_string().starts_with(
+                "invalid widget `bar`: malformed `radius` attribute of MovingCircle widget"
+            ));
+        }
+
+        #[test]
+        fn serialized_problems() {
+            let registration = Registration::new("foo")
+                .widget(circle("bar"))
+                .widget(circle("bar"));
+
+            let json = serde_json::to_string(&registration).unwrap();
+            let registration: Registration = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(
+                registration.build().unwrap_err(),
+                vec![Problem::DuplicateWidgetName("bar".to_owned())]
+            );
+        }
+    }
 }
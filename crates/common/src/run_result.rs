@@ -1,10 +1,11 @@
 //! The result of the run of a plugin.
 
-use crate::StateTransfer;
+use crate::{Command, StateTransfer};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// All details of the result of a `run` of the plugin.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RunResult {
     /// Details about the error occurred while running the plugin.
     ///
@@ -15,4 +16,39 @@ pub struct RunResult {
     /// The game state after the plugin finished running.
     #[serde(rename = "s")]
     pub state: Option<StateTransfer>,
+
+    /// A list of plugins to pause (`true`) or resume (`false`).
+    ///
+    /// This returns `None` if the plugin did not request any changes.
+    #[serde(rename = "p")]
+    pub paused: Option<HashMap<String, bool>>,
+
+    /// A list of commands for the engine to process.
+    ///
+    /// This returns `None` if the plugin did not issue any commands.
+    #[serde(rename = "c")]
+    pub commands: Option<Vec<Command>>,
+}
+
+impl RunResult {
+    /// Create a run result without any error or state changes.
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            error: None,
+            state: None,
+            paused: None,
+            commands: None,
+        }
+    }
+
+    /// Create a run result for a failed plugin run.
+    #[inline]
+    pub fn with_error(error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::empty()
+        }
+    }
 }
@@ -1,41 +1,1130 @@
 //! Everything related to "primitive shapes" used to draw to the screen.
 
-use crate::{Color, Deserialize, Serialize};
+use crate::{Color, Deserialize, Serialize, Value};
+
+/// The number of strokes used to draw the glow of a border.
+const GLOW_STROKES: u8 = 4;
 
 /// A list of primitive shapes the engine knows how to draw.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+///
+/// Shapes are serialized with an explicit, stable tag per variant, so that
+/// plugins can send draw commands over FFI without depending on the Rust names
+/// of the variants.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "k")]
 pub enum Shape {
     /// A circle with a given radius and color.
+    #[serde(rename = "circle")]
     Circle {
         /// Radius of the circle.
+        #[serde(rename = "r")]
         radius: f32,
 
         /// The fill color of the circle.
+        #[serde(rename = "f")]
         fill: Color,
 
         /// The border details.
+        #[serde(rename = "b", default)]
         border: Option<Border>,
     },
 
+    /// A ring (annulus) between an outer and inner radius, e.g. to draw
+    /// donuts or progress rings.
+    ///
+    /// Like a circle, the ring is centered at `(outer_radius, outer_radius)`.
+    #[serde(rename = "ring")]
+    Ring {
+        /// The outer radius of the ring.
+        #[serde(rename = "r")]
+        outer_radius: f32,
+
+        /// The radius of the hole in the center of the ring.
+        ///
+        /// The inner radius is clamped between zero and the outer radius.
+        #[serde(rename = "i")]
+        inner_radius: f32,
+
+        /// The color of the ring.
+        #[serde(rename = "c")]
+        color: Color,
+    },
+
     /// A rectangle with a width, height and color.
+    #[serde(rename = "rectangle")]
     Rectangle {
         /// The width of the rectangle.
+        #[serde(rename = "w")]
         width: f32,
 
         /// The height of the rectangle.
+        #[serde(rename = "h")]
         height: f32,
 
         /// The color of the rectangle.
+        #[serde(rename = "c")]
+        color: Color,
+
+        /// The clockwise rotation of the rectangle (in radians) around its
+        /// center.
+        #[serde(rename = "a", default)]
+        rotation: f32,
+
+        /// The border details.
+        ///
+        /// The border is drawn inside the rectangle, so that it doesn't extend
+        /// beyond the dimensions of the rectangle.
+        #[serde(rename = "b", default)]
+        border: Option<Border>,
+    },
+
+    /// A triangle defined by its three points.
+    ///
+    /// The points are relative to the top-left of the component.
+    #[serde(rename = "triangle")]
+    Triangle {
+        /// The first point of the triangle.
+        #[serde(rename = "a")]
+        a: (f32, f32),
+
+        /// The second point of the triangle.
+        #[serde(rename = "b")]
+        b: (f32, f32),
+
+        /// The third point of the triangle.
+        #[serde(rename = "c")]
+        c: (f32, f32),
+
+        /// The fill color of the triangle.
+        #[serde(rename = "f")]
+        fill: Color,
+
+        /// The border details.
+        #[serde(rename = "o", default)]
+        border: Option<Border>,
+    },
+
+    /// A straight line between two points.
+    ///
+    /// The points are relative to the top-left of the component.
+    #[serde(rename = "line")]
+    Line {
+        /// The start of the line.
+        #[serde(rename = "a")]
+        from: (f32, f32),
+
+        /// The end of the line.
+        #[serde(rename = "b")]
+        to: (f32, f32),
+
+        /// The width of the line, centered on the line between both points.
+        #[serde(rename = "w")]
+        width: f32,
+
+        /// The color of the line.
+        #[serde(rename = "c")]
+        color: Color,
+    },
+
+    /// A polygon defined by a list of (at least three) points.
+    ///
+    /// The points are relative to the top-left of the component. Polygons
+    /// with fewer than three points are not rendered.
+    #[serde(rename = "polygon")]
+    Polygon {
+        /// The points of the polygon, in order. The last point connects back
+        /// to the first one.
+        #[serde(rename = "p")]
+        points: Vec<(f32, f32)>,
+
+        /// The fill color of the polygon.
+        #[serde(rename = "f")]
+        fill: Color,
+
+        /// The border details.
+        #[serde(rename = "o", default)]
+        border: Option<Border>,
+    },
+
+    /// A single line of text, rendered in the default font of the engine.
+    ///
+    /// The text starts at the top-left of the component. Because the
+    /// dimensions of the text depend on the font, text has no bounds, and is
+    /// ignored when checking if a point falls within a shape.
+    #[serde(rename = "text")]
+    Text {
+        /// The text to render.
+        #[serde(rename = "t")]
+        content: String,
+
+        /// The size (in pixels) of the font.
+        #[serde(rename = "s")]
+        size: f32,
+
+        /// The color of the text.
+        #[serde(rename = "c")]
         color: Color,
     },
 }
 
+impl Shape {
+    /// Check if the given point falls within the shape.
+    ///
+    /// The coordinates are relative to the top-left of the shape's bounding
+    /// box, e.g. the center of a circle is at `(radius, radius)`.
+    ///
+    /// For rotated rectangles, the bounding box is that of the rectangle
+    /// before it is rotated. For triangles, the coordinates are relative to the
+    /// same origin as the points of the triangle (or line, or polygon).
+    #[inline]
+    #[must_use]
+    pub fn contains_point(&self, x: f32, y: f32) -> bool {
+        match *self {
+            Self::Text { .. } => false,
+            Self::Polygon { ref points, .. } => {
+                if points.len() < 3 {
+                    return false;
+                }
+
+                // Toggle for each edge crossed by a ray from the point to the
+                // right. An odd number of crossings means the point lies within
+                // the polygon.
+                let edges = points.iter().zip(points.iter().cycle().skip(1));
+                edges
+                    .filter(|&(&(x1, y1), &(x2, y2))| {
+                        (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1)
+                    })
+                    .fold(false, |inside, _| !inside)
+            }
+            Self::Line {
+                from, to, width, ..
+            } => segment_distance((x, y), from, to) <= width / 2.0,
+            Self::Triangle { a, b, c, .. } => {
+                // The point lies within the triangle if it is on the same side
+                // of all three edges.
+                let side = |(x1, y1): (f32, f32), (x2, y2): (f32, f32)| {
+                    (x2 - x1) * (y - y1) - (y2 - y1) * (x - x1)
+                };
+
+                let sides = [side(a, b), side(b, c), side(c, a)];
+                let negative = sides.iter().any(|&side| side < 0.0);
+                let positive = sides.iter().any(|&side| side > 0.0);
+
+                !(negative && positive)
+            }
+            Self::Circle { radius, .. } => (radius - x).hypot(radius - y) <= radius,
+            Self::Ring {
+                outer_radius,
+                inner_radius,
+                ..
+            } => {
+                // Points in the hole of the ring fall outside of it.
+                let distance = (outer_radius - x).hypot(outer_radius - y);
+
+                distance <= outer_radius && distance >= inner_radius
+            }
+            Self::Rectangle {
+                width,
+                height,
+                rotation,
+                ..
+            } => {
+                // Rotate the point in the opposite direction around the center
+                // of the rectangle, to check it against the unrotated
+                // rectangle.
+                let (sin, cos) = rotation.sin_cos();
+                let (dx, dy) = (x - width / 2.0, y - height / 2.0);
+                let x = dx * cos + dy * sin;
+                let y = -dx * sin + dy * cos;
+
+                x.abs() <= width / 2.0 && y.abs() <= height / 2.0
+            }
+        }
+    }
+
+    /// Get the axis-aligned bounding box `(x, y, width, height)` of the shape.
+    ///
+    /// The position is relative to the top-left of the (unrotated) shape. A
+    /// rotated rectangle extends beyond its unrotated size, resulting in a
+    /// negative position. The bounding box of a triangle (or line, or polygon)
+    /// is relative to the origin of its points. Text has an empty bounding box.
+    #[inline]
+    #[must_use]
+    pub fn bounding_box(&self) -> (f32, f32, f32, f32) {
+        match *self {
+            Self::Text { .. } => (0.0, 0.0, 0.0, 0.0),
+            Self::Polygon { ref points, .. } => {
+                let mut points = points.iter();
+                let (x, y) = match points.next() {
+                    Some(&point) => point,
+                    None => return (0.0, 0.0, 0.0, 0.0),
+                };
+
+                let (x_min, y_min, x_max, y_max) =
+                    points.fold((x, y, x, y), |(x_min, y_min, x_max, y_max), &(x, y)| {
+                        (x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y))
+                    });
+
+                (x_min, y_min, x_max - x_min, y_max - y_min)
+            }
+            Self::Line {
+                from, to, width, ..
+            } => {
+                let half_width = width / 2.0;
+                let (x_min, y_min) = (from.0.min(to.0) - half_width, from.1.min(to.1) - half_width);
+                let (x_max, y_max) = (from.0.max(to.0) + half_width, from.1.max(to.1) + half_width);
+
+                (x_min, y_min, x_max - x_min, y_max - y_min)
+            }
+            Self::Triangle { a, b, c, .. } => {
+                let (x_min, y_min) = (a.0.min(b.0).min(c.0), a.1.min(b.1).min(c.1));
+                let (x_max, y_max) = (a.0.max(b.0).max(c.0), a.1.max(b.1).max(c.1));
+
+                (x_min, y_min, x_max - x_min, y_max - y_min)
+            }
+            Self::Circle { radius, .. } => (0.0, 0.0, radius * 2.0, radius * 2.0),
+            Self::Ring { outer_radius, .. } => (0.0, 0.0, outer_radius * 2.0, outer_radius * 2.0),
+            Self::Rectangle {
+                width,
+                height,
+                rotation,
+                ..
+            } => {
+                let (sin, cos) = rotation.sin_cos();
+                let (sin, cos) = (sin.abs(), cos.abs());
+
+                let rotated_width = width * cos + height * sin;
+                let rotated_height = width * sin + height * cos;
+
+                (
+                    (width - rotated_width) / 2.0,
+                    (height - rotated_height) / 2.0,
+                    rotated_width,
+                    rotated_height,
+                )
+            }
+        }
+    }
+
+    /// Get the corners of a rectangle, in clockwise order starting at the
+    /// (unrotated) top-left corner, after rotating the rectangle around its
+    /// center.
+    ///
+    /// The corners are relative to the top-left of the unrotated rectangle.
+    /// Returns `None` for shapes other than rectangles.
+    #[inline]
+    #[must_use]
+    pub fn corners(&self) -> Option<[(f32, f32); 4]> {
+        match *self {
+            Self::Rectangle {
+                width,
+                height,
+                rotation,
+                ..
+            } => {
+                let (sin, cos) = rotation.sin_cos();
+                let (cx, cy) = (width / 2.0, height / 2.0);
+
+                let rotate = |x: f32, y: f32| {
+                    let (dx, dy) = (x - cx, y - cy);
+                    (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+                };
+
+                Some([
+                    rotate(0.0, 0.0),
+                    rotate(width, 0.0),
+                    rotate(width, height),
+                    rotate(0.0, height),
+                ])
+            }
+            Self::Circle { .. }
+            | Self::Ring { .. }
+            | Self::Triangle { .. }
+            | Self::Line { .. }
+            | Self::Polygon { .. }
+            | Self::Text { .. } => None,
+        }
+    }
+
+    /// Get the corners of the path along which the border of a rectangle is
+    /// drawn, in the same order as [`Shape::corners`].
+    ///
+    /// Borders are drawn centered on their path. To keep the border within
+    /// the rectangle, the path is inset by half the width of the border.
+    ///
+    /// Returns `None` for shapes other than rectangles, or rectangles without
+    /// a border.
+    #[inline]
+    #[must_use]
+    pub fn border_corners(&self) -> Option<[(f32, f32); 4]> {
+        match *self {
+            Self::Rectangle {
+                width,
+                height,
+                color,
+                rotation,
+                border: Some(border),
+            } => {
+                let inset = (border.width / 2.0).min(width / 2.0).min(height / 2.0);
+
+                // Rotating the inset rectangle around its center is the same as
+                // rotating it around the center of the rectangle itself.
+                let [a, b, c, d] = Self::Rectangle {
+                    width: width - inset * 2.0,
+                    height: height - inset * 2.0,
+                    color,
+                    rotation,
+                    border: None,
+                }
+                .corners()?;
+
+                let offset = |(x, y): (f32, f32)| (x + inset, y + inset);
+
+                Some([offset(a), offset(b), offset(c), offset(d)])
+            }
+            Self::Rectangle { border: None, .. }
+            | Self::Circle { .. }
+            | Self::Ring { .. }
+            | Self::Triangle { .. }
+            | Self::Line { .. }
+            | Self::Polygon { .. }
+            | Self::Text { .. } => None,
+        }
+    }
+
+    /// Get the `(radius, width)` of the circular stroke with which a ring is
+    /// drawn.
+    ///
+    /// The stroke is centered between the inner and outer radius of the ring,
+    /// so that drawing it leaves the hole in the center of the ring empty.
+    ///
+    /// Returns `None` for shapes other than rings.
+    #[inline]
+    #[must_use]
+    pub fn ring_stroke(&self) -> Option<(f32, f32)> {
+        match *self {
+            Self::Ring {
+                outer_radius,
+                inner_radius,
+                ..
+            } => {
+                let inner_radius = inner_radius.max(0.0).min(outer_radius);
+                let width = outer_radius - inner_radius;
+
+                Some((inner_radius + width / 2.0, width))
+            }
+            Self::Circle { .. }
+            | Self::Rectangle { .. }
+            | Self::Triangle { .. }
+            | Self::Line { .. }
+            | Self::Polygon { .. }
+            | Self::Text { .. } => None,
+        }
+    }
+}
+
+/// The distance between a point and the closest point on a line segment.
+fn segment_distance((x, y): (f32, f32), (x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> f32 {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length_squared = dx * dx + dy * dy;
+
+    // How far along the segment the closest point lies, from `0.0` (the start)
+    // to `1.0` (the end).
+    let t = if length_squared > 0.0 {
+        (((x - x1) * dx + (y - y1) * dy) / length_squared)
+            .max(0.0)
+            .min(1.0)
+    } else {
+        0.0
+    };
+
+    (x - (x1 + t * dx)).hypot(y - (y1 + t * dy))
+}
+
 /// A border belonging to a shape.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Border {
     /// The color of the border.
+    #[serde(rename = "c")]
     pub color: Color,
 
     /// The width of the border.
+    #[serde(rename = "w")]
     pub width: f32,
+
+    /// An (optional) soft halo around the outside of the shape, given as its
+    /// color and radius.
+    #[serde(rename = "g", default, skip_serializing_if = "Option::is_none")]
+    pub glow: Option<(Color, f32)>,
+}
+
+impl Border {
+    /// Get the strokes making up the glow of the border, from the outermost to
+    /// the innermost stroke.
+    ///
+    /// The strokes are centered on the outline of the shape, and are drawn
+    /// before the shape itself, leaving only their outer halves visible. Each
+    /// stroke is narrower and more opaque than the previous one, so that the
+    /// glow fades out away from the shape.
+    ///
+    /// Returns no strokes if the border has no glow.
+    #[inline]
+    #[must_use]
+    pub fn glow_strokes(&self) -> Vec<Self> {
+        let (color, radius) = match self.glow {
+            Some(glow) => glow,
+            None => return vec![],
+        };
+
+        let count = f32::from(GLOW_STROKES);
+
+        (1..=GLOW_STROKES)
+            .rev()
+            .map(f32::from)
+            .map(|i| Self {
+                color: Color {
+                    a: color.a * (count - i + 1.0) / count,
+                    ..color
+                },
+                width: radius * 2.0 * i / count,
+                glow: None,
+            })
+            .collect()
+    }
+}
+
+impl From<Border> for Value {
+    #[inline]
+    fn from(border: Border) -> Self {
+        #[allow(clippy::result_expect_used)] // known to be valid
+        serde_json::to_value(border).expect("valid")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod contains_point {
+        use super::*;
+
+        #[test]
+        fn circle() {
+            let shape = Shape::Circle {
+                radius: 10.0,
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert!(shape.contains_point(10.0, 10.0));
+            assert!(shape.contains_point(0.0, 10.0));
+            assert!(shape.contains_point(10.0, 20.0));
+
+            // inside the bounding box, outside the circle
+            assert!(!shape.contains_point(1.0, 1.0));
+            assert!(!shape.contains_point(19.0, 19.0));
+        }
+
+        #[test]
+        fn ring() {
+            let shape = Shape::Ring {
+                outer_radius: 10.0,
+                inner_radius: 6.0,
+                color: Color::default(),
+            };
+
+            assert!(shape.contains_point(0.0, 10.0));
+            assert!(shape.contains_point(10.0, 18.0));
+            assert!(shape.contains_point(10.0, 4.0));
+
+            // inside the hole of the ring
+            assert!(!shape.contains_point(10.0, 10.0));
+            assert!(!shape.contains_point(14.0, 10.0));
+
+            // inside the bounding box, outside the ring
+            assert!(!shape.contains_point(1.0, 1.0));
+        }
+
+        #[test]
+        fn rectangle() {
+            let shape = Shape::Rectangle {
+                width: 20.0,
+                height: 10.0,
+                color: Color::default(),
+                rotation: 0.0,
+                border: None,
+            };
+
+            assert!(shape.contains_point(0.0, 0.0));
+            assert!(shape.contains_point(20.0, 10.0));
+            assert!(!shape.contains_point(21.0, 5.0));
+            assert!(!shape.contains_point(5.0, -1.0));
+        }
+
+        #[test]
+        fn rotated_rectangle() {
+            let shape = Shape::Rectangle {
+                width: 20.0,
+                height: 10.0,
+                color: Color::default(),
+                rotation: std::f32::consts::FRAC_PI_2,
+                border: None,
+            };
+
+            // The center remains in place.
+            assert!(shape.contains_point(10.0, 5.0));
+
+            // The rotated rectangle is 10 wide, and 20 high.
+            assert!(shape.contains_point(10.0, -4.0));
+            assert!(shape.contains_point(10.0, 14.0));
+            assert!(!shape.contains_point(1.0, 5.0));
+            assert!(!shape.contains_point(19.0, 5.0));
+        }
+
+        #[test]
+        fn triangle() {
+            let triangle = |a, b, c| Shape::Triangle {
+                a,
+                b,
+                c,
+                fill: Color::default(),
+                border: None,
+            };
+
+            let clockwise = triangle((0.0, 0.0), (10.0, 0.0), (0.0, 10.0));
+            let counter_clockwise = triangle((0.0, 0.0), (0.0, 10.0), (10.0, 0.0));
+
+            for shape in &[clockwise, counter_clockwise] {
+                assert!(shape.contains_point(1.0, 1.0));
+                assert!(shape.contains_point(0.0, 0.0));
+                assert!(shape.contains_point(5.0, 5.0));
+                assert!(!shape.contains_point(6.0, 6.0));
+                assert!(!shape.contains_point(-1.0, 5.0));
+            }
+        }
+
+        #[test]
+        fn line() {
+            let shape = Shape::Line {
+                from: (0.0, 0.0),
+                to: (10.0, 0.0),
+                width: 4.0,
+                color: Color::default(),
+            };
+
+            assert!(shape.contains_point(5.0, 0.0));
+            assert!(shape.contains_point(5.0, 2.0));
+            assert!(shape.contains_point(11.0, 0.0));
+            assert!(!shape.contains_point(5.0, 3.0));
+            assert!(!shape.contains_point(13.0, 0.0));
+        }
+
+        #[test]
+        fn polygon() {
+            // A "U" shape, to verify concave polygons.
+            let shape = Shape::Polygon {
+                points: vec![
+                    (0.0, 0.0),
+                    (3.0, 0.0),
+                    (3.0, 7.0),
+                    (7.0, 7.0),
+                    (7.0, 0.0),
+                    (10.0, 0.0),
+                    (10.0, 10.0),
+                    (0.0, 10.0),
+                ],
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert!(shape.contains_point(1.0, 1.0));
+            assert!(shape.contains_point(5.0, 8.0));
+            assert!(shape.contains_point(9.0, 1.0));
+            assert!(!shape.contains_point(5.0, 3.0));
+            assert!(!shape.contains_point(11.0, 5.0));
+        }
+
+        #[test]
+        fn degenerate_polygon() {
+            let shape = Shape::Polygon {
+                points: vec![(0.0, 0.0), (10.0, 10.0)],
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert!(!shape.contains_point(5.0, 5.0));
+        }
+    }
+
+    mod segment_distance {
+        use super::*;
+
+        #[test]
+        fn distances() {
+            assert_eq!(segment_distance((5.0, 3.0), (0.0, 0.0), (10.0, 0.0)), 3.0);
+            assert_eq!(segment_distance((13.0, 4.0), (0.0, 0.0), (10.0, 0.0)), 5.0);
+            assert_eq!(segment_distance((3.0, 4.0), (0.0, 0.0), (0.0, 0.0)), 5.0);
+        }
+    }
+
+    mod bounding_box {
+        use super::*;
+
+        fn assert_box(actual: (f32, f32, f32, f32), expected: (f32, f32, f32, f32)) {
+            let (a, b) = (actual, expected);
+            let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+
+            assert!(
+                close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2) && close(a.3, b.3),
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+
+        #[test]
+        fn circle() {
+            let shape = Shape::Circle {
+                radius: 10.0,
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert_eq!(shape.bounding_box(), (0.0, 0.0, 20.0, 20.0));
+        }
+
+        #[test]
+        fn ring() {
+            let shape = Shape::Ring {
+                outer_radius: 10.0,
+                inner_radius: 6.0,
+                color: Color::default(),
+            };
+
+            assert_eq!(shape.bounding_box(), (0.0, 0.0, 20.0, 20.0));
+        }
+
+        #[test]
+        fn rectangle() {
+            let shape = Shape::Rectangle {
+                width: 20.0,
+                height: 10.0,
+                color: Color::default(),
+                rotation: 0.0,
+                border: None,
+            };
+
+            assert_eq!(shape.bounding_box(), (0.0, 0.0, 20.0, 10.0));
+        }
+
+        #[test]
+        fn triangle() {
+            let shape = Shape::Triangle {
+                a: (5.0, 2.0),
+                b: (15.0, 10.0),
+                c: (0.0, 12.0),
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert_eq!(shape.bounding_box(), (0.0, 2.0, 15.0, 10.0));
+        }
+
+        #[test]
+        fn line() {
+            let shape = Shape::Line {
+                from: (10.0, 5.0),
+                to: (0.0, 15.0),
+                width: 2.0,
+                color: Color::default(),
+            };
+
+            assert_eq!(shape.bounding_box(), (-1.0, 4.0, 12.0, 12.0));
+        }
+
+        #[test]
+        fn polygon() {
+            let shape = |points| Shape::Polygon {
+                points,
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert_eq!(
+                shape(vec![(2.0, 1.0), (10.0, 3.0), (4.0, 9.0), (-1.0, 5.0)]).bounding_box(),
+                (-1.0, 1.0, 11.0, 8.0)
+            );
+            assert_eq!(shape(vec![]).bounding_box(), (0.0, 0.0, 0.0, 0.0));
+        }
+
+        #[test]
+        fn rotated_rectangle() {
+            let shape = |rotation| Shape::Rectangle {
+                width: 20.0,
+                height: 10.0,
+                color: Color::default(),
+                rotation,
+                border: None,
+            };
+
+            assert_box(
+                shape(std::f32::consts::FRAC_PI_2).bounding_box(),
+                (5.0, -5.0, 10.0, 20.0),
+            );
+
+            let diagonal = 30.0 * std::f32::consts::FRAC_1_SQRT_2;
+            assert_box(
+                shape(std::f32::consts::FRAC_PI_4).bounding_box(),
+                (
+                    10.0 - diagonal / 2.0,
+                    5.0 - diagonal / 2.0,
+                    diagonal,
+                    diagonal,
+                ),
+            );
+        }
+    }
+
+    mod corners {
+        use super::*;
+
+        #[test]
+        fn rotated_rectangle() {
+            let shape = Shape::Rectangle {
+                width: 20.0,
+                height: 10.0,
+                color: Color::default(),
+                rotation: std::f32::consts::PI,
+                border: None,
+            };
+
+            let corners = shape.corners().unwrap();
+            let (x, y) = corners[0];
+
+            // The top-left corner is rotated to the bottom-right.
+            assert!((x - 20.0).abs() < 1e-4 && (y - 10.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn circle() {
+            let shape = Shape::Circle {
+                radius: 10.0,
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert_eq!(shape.corners(), None);
+        }
+    }
+
+    mod ring_stroke {
+        use super::*;
+
+        fn ring(outer_radius: f32, inner_radius: f32) -> Shape {
+            Shape::Ring {
+                outer_radius,
+                inner_radius,
+                color: Color::default(),
+            }
+        }
+
+        #[test]
+        fn centered() {
+            assert_eq!(ring(10.0, 6.0).ring_stroke(), Some((8.0, 4.0)));
+        }
+
+        #[test]
+        fn clamped_inner_radius() {
+            // Without a hole, the ring is a filled circle.
+            assert_eq!(ring(10.0, -2.0).ring_stroke(), Some((5.0, 10.0)));
+            assert_eq!(ring(10.0, 12.0).ring_stroke(), Some((10.0, 0.0)));
+        }
+
+        #[test]
+        fn circle() {
+            let shape = Shape::Circle {
+                radius: 10.0,
+                fill: Color::default(),
+                border: None,
+            };
+
+            assert_eq!(shape.ring_stroke(), None);
+        }
+    }
+
+    mod glow_strokes {
+        use super::*;
+
+        #[test]
+        fn falloff() {
+            let border = Border {
+                color: Color::default(),
+                width: 1.0,
+                glow: Some((Color::new(1.0, 1.0, 0.0, 0.8), 8.0)),
+            };
+
+            let strokes = border.glow_strokes();
+            let widths: Vec<_> = strokes.iter().map(|stroke| stroke.width).collect();
+            let alphas: Vec<_> = strokes.iter().map(|stroke| stroke.color.a).collect();
+
+            assert_eq!(strokes.len(), 4);
+            assert_eq!(widths, vec![16.0, 12.0, 8.0, 4.0]);
+            assert_eq!(alphas, vec![0.2, 0.4, 0.6, 0.8]);
+            assert!(strokes
+                .iter()
+                .all(|stroke| stroke.glow.is_none() && stroke.color.g == 1.0));
+        }
+
+        #[test]
+        fn without_glow() {
+            let border = Border {
+                color: Color::default(),
+                width: 1.0,
+                glow: None,
+            };
+
+            assert!(border.glow_strokes().is_empty());
+        }
+    }
+
+    mod border_corners {
+        use super::*;
+
+        fn rectangle(rotation: f32, border: Option<Border>) -> Shape {
+            Shape::Rectangle {
+                width: 20.0,
+                height: 10.0,
+                color: Color::default(),
+                rotation,
+                border,
+            }
+        }
+
+        fn border(width: f32) -> Option<Border> {
+            Some(Border {
+                color: Color::default(),
+                width,
+                glow: None,
+            })
+        }
+
+        #[test]
+        fn inset() {
+            let corners = rectangle(0.0, border(2.0)).border_corners().unwrap();
+
+            assert_eq!(corners, [(1.0, 1.0), (19.0, 1.0), (19.0, 9.0), (1.0, 9.0)]);
+        }
+
+        #[test]
+        fn rotated() {
+            let corners = rectangle(std::f32::consts::PI, border(2.0))
+                .border_corners()
+                .unwrap();
+            let (x, y) = corners[0];
+
+            // The inset top-left corner is rotated to the bottom-right.
+            assert!((x - 19.0).abs() < 1e-4 && (y - 9.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn wider_than_rectangle() {
+            let corners = rectangle(0.0, border(40.0)).border_corners().unwrap();
+
+            // The path collapses onto the center line of the rectangle.
+            assert_eq!(corners[0], (5.0, 5.0));
+            assert_eq!(corners[2], (15.0, 5.0));
+        }
+
+        #[test]
+        fn without_border() {
+            assert_eq!(rectangle(0.0, None).border_corners(), None);
+        }
+    }
+
+    mod serialize {
+        use super::*;
+
+        fn round_trip(shape: &Shape) -> Shape {
+            let json = serde_json::to_string(shape).unwrap();
+            serde_json::from_str(&json).unwrap()
+        }
+
+        #[test]
+        fn circle() {
+            let shape = Shape::Circle {
+                radius: 10.0,
+                fill: Color::from_rgb(255, 0, 0),
+                border: None,
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        #[test]
+        fn ring() {
+            let shape = Shape::Ring {
+                outer_radius: 10.0,
+                inner_radius: 6.0,
+                color: Color::from_rgb(255, 0, 0),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+
+            let value = serde_json::to_value(&shape).unwrap();
+
+            assert_eq!(value["k"], "ring");
+            assert_eq!(value["r"], 10.0);
+            assert_eq!(value["i"], 6.0);
+        }
+
+        #[test]
+        fn circle_with_border() {
+            let shape = Shape::Circle {
+                radius: 10.0,
+                fill: Color::from_rgb(255, 0, 0),
+                border: Some(Border {
+                    color: Color::from_rgba(0, 0, 255, 128),
+                    width: 2.5,
+                    glow: None,
+                }),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        #[test]
+        fn rectangle() {
+            let shape = Shape::Rectangle {
+                width: 20.0,
+                height: 5.0,
+                color: Color::new(0.1, 0.2, 0.3, 0.4),
+                rotation: 0.5,
+                border: None,
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        #[test]
+        fn rectangle_with_border() {
+            let shape = Shape::Rectangle {
+                width: 20.0,
+                height: 5.0,
+                color: Color::default(),
+                rotation: 0.0,
+                border: Some(Border {
+                    color: Color::from_rgb(0, 0, 0),
+                    width: 1.5,
+                    glow: None,
+                }),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        #[test]
+        fn border_with_glow() {
+            let shape = Shape::Circle {
+                radius: 5.0,
+                fill: Color::default(),
+                border: Some(Border {
+                    color: Color::from_rgb(0, 0, 0),
+                    width: 1.0,
+                    glow: Some((Color::from_rgba(255, 255, 0, 128), 4.0)),
+                }),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        #[test]
+        fn rectangle_without_border_field() {
+            let json = r#"{"k":"rectangle","w":1.0,"h":2.0,"c":{"r":1.0,"g":1.0,"b":1.0,"a":1.0}}"#;
+            let shape: Shape = serde_json::from_str(json).unwrap();
+
+            assert_eq!(
+                shape,
+                Shape::Rectangle {
+                    width: 1.0,
+                    height: 2.0,
+                    color: Color::new(1.0, 1.0, 1.0, 1.0),
+                    rotation: 0.0,
+                    border: None,
+                }
+            );
+        }
+
+        #[test]
+        fn triangle() {
+            let shape = Shape::Triangle {
+                a: (0.0, 0.0),
+                b: (10.0, 5.0),
+                c: (2.0, 8.0),
+                fill: Color::from_rgb(0, 255, 0),
+                border: Some(Border {
+                    color: Color::default(),
+                    width: 1.0,
+                    glow: None,
+                }),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+            assert_eq!(serde_json::to_value(&shape).unwrap()["k"], "triangle");
+        }
+
+        #[test]
+        fn line() {
+            let shape = Shape::Line {
+                from: (1.0, 2.0),
+                to: (30.0, 40.0),
+                width: 1.5,
+                color: Color::from_rgb(0, 0, 255),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+            assert_eq!(serde_json::to_value(&shape).unwrap()["k"], "line");
+        }
+
+        #[test]
+        fn polygon() {
+            let shape = Shape::Polygon {
+                points: vec![(0.0, 0.0), (10.0, 0.0), (12.0, 8.0), (-2.0, 8.0)],
+                fill: Color::from_rgb(255, 0, 255),
+                border: None,
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+            assert_eq!(serde_json::to_value(&shape).unwrap()["k"], "polygon");
+        }
+
+        #[test]
+        fn text() {
+            let shape = Shape::Text {
+                content: "Start game".to_owned(),
+                size: 16.0,
+                color: Color::from_rgb(255, 255, 255),
+            };
+
+            assert_eq!(round_trip(&shape), shape);
+            assert_eq!(serde_json::to_value(&shape).unwrap()["k"], "text");
+        }
+
+        #[test]
+        fn stable_tag() {
+            let shape = Shape::Rectangle {
+                width: 1.0,
+                height: 2.0,
+                color: Color::default(),
+                rotation: 0.0,
+                border: None,
+            };
+
+            let value = serde_json::to_value(&shape).unwrap();
+
+            assert_eq!(value["k"], "rectangle");
+            assert_eq!(value["w"], 1.0);
+            assert_eq!(value["h"], 2.0);
+        }
+    }
 }
@@ -1,7 +1,35 @@
 //! All state tracked by the engine.
 
-use crate::{widget, Canvas, Deserialize, DeserializeOwned, Event, Serialize, Value};
-use std::collections::HashMap;
+use crate::{
+    event, widget, Canvas, Color, Deserialize, DeserializeOwned, Event, Serialize, Time, Value,
+};
+use serde::{Deserializer, Serializer};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The current version of the serialized [`WidgetWithPosition`] format.
+///
+/// New fields should be marked `#[serde(default)]` so that widgets stored by
+/// older versions of the engine (e.g. in save games) can still be loaded. If a
+/// change can't be expressed using defaults, bump this version and add a
+/// migration step to [`WidgetWithPosition::migrate`].
+const WIDGET_VERSION: u16 = 1;
+
+/// The revision given to the next widget state, see
+/// [`WidgetWithPosition::revision`].
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+/// Get a new, unique widget state revision.
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
 
 /// The state of the game.
 ///
@@ -12,17 +40,127 @@ use std::collections::HashMap;
 /// This struct stores that state, and hands off a mutable (for the plugin that
 /// owns its `PluginState`) or an immutable (for plugins that want to read the
 /// state of other plugins) reference to the relevant state objects.
-#[derive(Debug, Default)]
+///
+/// The game state can be serialized (e.g. to save a game), which excludes any
+/// uncommitted writes to the shared state, and the observer.
+#[derive(Default, Serialize, Deserialize)]
 pub struct Game {
     /// The internal game state (segregated by plugin).
+    #[serde(rename = "s")]
     state: HashMap<String, Plugin>,
+
+    /// A list of plugins that are currently paused.
+    ///
+    /// Paused plugins keep their state, but are skipped when the engine runs
+    /// its plugins.
+    #[serde(rename = "p", default)]
+    paused: HashSet<String>,
+
+    /// The state shared between plugins, which all plugins declaring a key are
+    /// allowed to mutate.
+    #[serde(rename = "h", default)]
+    shared: HashMap<String, Value>,
+
+    /// The writes to the shared state since they were last committed.
+    #[serde(skip)]
+    shared_writes: HashMap<String, Value>,
+
+    /// An optional callback triggered whenever the state of a plugin is
+    /// registered.
+    #[serde(skip)]
+    observer: Option<Observer>,
+}
+
+/// A callback receiving the name of the plugin of which the state changed.
+pub type Observer = Box<dyn FnMut(&str)>;
+
+impl fmt::Debug for Game {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Game")
+            .field("state", &self.state)
+            .field("paused", &self.paused)
+            .field("shared", &self.shared)
+            .field("shared_writes", &self.shared_writes)
+            .field("observer", &self.observer.as_ref().map(|_| "Observer"))
+            .finish()
+    }
 }
 
 impl Game {
+    /// Save the game state to the file at the given path, as JSON.
+    ///
+    /// The saved state can be restored using [`Game::load_from_path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    #[inline]
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+
+        writer.flush()
+    }
+
+    /// Load a game state from the file at the given path, previously saved
+    /// using [`Game::save_to_path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or if it doesn't contain a
+    /// valid game state (in which case the error is of the
+    /// [`io::ErrorKind::InvalidData`] kind).
+    #[inline]
+    pub fn load_from_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        serde_json::from_reader(reader).map_err(Into::into)
+    }
+
     /// Register the state of a plugin.
+    ///
+    /// Widgets that are equal to the widget of the same name in the previously
+    /// registered state keep their revision (see
+    /// [`WidgetWithPosition::revision`]), so that replacing the state of a
+    /// plugin doesn't mark all of its widgets as changed.
+    ///
+    /// If an observer is set, it is called with the name of the plugin.
     #[inline]
-    pub fn register_plugin_state(&mut self, plugin: impl Into<String>, state: Plugin) {
-        self.state.insert(plugin.into(), state);
+    pub fn register_plugin_state(&mut self, plugin: impl Into<String>, mut state: Plugin) {
+        let plugin = plugin.into();
+
+        if let Some(old) = self.state.get(&plugin) {
+            for (name, widget) in &mut state.widgets {
+                match old.widgets.get(name) {
+                    Some(old) if old == widget => widget.revision = old.revision,
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(observer) = &mut self.observer {
+            observer(&plugin);
+        }
+
+        self.state.insert(plugin, state);
+    }
+
+    /// Set a callback that is called whenever the state of a plugin is
+    /// registered (e.g. when a plugin changed its state while running).
+    ///
+    /// This allows external tooling, such as live state inspectors, to react
+    /// to state changes without polling. Only one observer can be set at a
+    /// time, setting a new one replaces the existing one.
+    #[inline]
+    pub fn set_observer(&mut self, observer: impl FnMut(&str) + 'static) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Remove the observer, if any.
+    #[inline]
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
     }
 
     /// Get an immutable reference to the state of a plugin.
@@ -37,6 +175,180 @@ impl Game {
         self.state.get_mut(&plugin.into())
     }
 
+    /// Pause or resume a plugin.
+    ///
+    /// A paused plugin does not run until it is resumed, but its widgets are
+    /// still rendered.
+    #[inline]
+    pub fn set_plugin_paused(&mut self, plugin: impl Into<String>, paused: bool) {
+        let plugin = plugin.into();
+
+        if paused {
+            self.paused.insert(plugin);
+        } else {
+            self.paused.remove(&plugin);
+        }
+    }
+
+    /// Check whether a plugin is paused.
+    #[inline]
+    pub fn is_plugin_paused(&self, plugin: impl Into<String>) -> bool {
+        self.paused.contains(&plugin.into())
+    }
+
+    /// Get the events a plugin is allowed to see.
+    ///
+    /// Input events are visible to all plugins, but widget events are only
+    /// visible to the plugin owning the widget that triggered the event, even
+    /// if other plugins own a widget of the same name.
+    #[inline]
+    #[must_use]
+    pub fn plugin_events(&self, plugin: impl Into<String>, events: &[Event]) -> Vec<Event> {
+        let plugin = plugin.into();
+        let widgets = self.get(&plugin).map(|state| &state.widgets);
+
+        events
+            .iter()
+            .filter(|event| match event {
+                Event::Input(_) => true,
+                Event::Widget {
+                    plugin: owner,
+                    name,
+                    ..
+                } => owner == &plugin && widgets.map_or(false, |w| w.contains_key(name)),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Declare a key of the state shared between plugins, with its initial
+    /// value.
+    ///
+    /// If the key is already declared (e.g. by another plugin), its current
+    /// value is kept.
+    #[inline]
+    pub fn declare_shared(&mut self, key: impl Into<String>, value: Value) {
+        self.shared.entry(key.into()).or_insert(value);
+    }
+
+    /// Get an immutable reference to a value of the shared state.
+    #[inline]
+    pub fn shared(&self, key: impl Into<String>) -> Option<&Value> {
+        self.shared.get(&key.into())
+    }
+
+    /// Write a value to the shared state.
+    ///
+    /// Writes only become visible once they are committed using
+    /// [`Game::commit_shared`], which the engine does after all plugins ran
+    /// for a tick. This gives all plugins the same view of the shared state
+    /// during a tick. If multiple plugins write the same key during a tick,
+    /// the last write wins.
+    #[inline]
+    pub fn write_shared(&mut self, key: impl Into<String>, value: Value) {
+        self.shared_writes.insert(key.into(), value);
+    }
+
+    /// Apply all writes to the shared state since the last commit.
+    #[inline]
+    pub fn commit_shared(&mut self) {
+        self.shared.extend(self.shared_writes.drain());
+    }
+
+    /// Get immutable references to the state of all plugins, together with the
+    /// name of the plugin.
+    #[inline]
+    #[must_use]
+    pub fn plugins(&self) -> Vec<(&str, &Plugin)> {
+        self.state
+            .iter()
+            .map(|(name, plugin)| (name.as_str(), plugin))
+            .collect()
+    }
+
+    /// Estimate the memory footprint (in bytes) of the state of all plugins.
+    ///
+    /// See [`Game::plugin_footprints`] for details.
+    #[inline]
+    #[must_use]
+    pub fn memory_footprint(&self) -> usize {
+        self.plugin_footprints()
+            .into_iter()
+            .fold(0, |total, (_, size)| total.saturating_add(size))
+    }
+
+    /// Estimate the memory footprint (in bytes) of the state of each plugin.
+    ///
+    /// The estimate is the size of the serialized plugin state, which is also
+    /// what is transferred to a plugin every time it runs. This can be used to
+    /// spot plugins with runaway state.
+    #[inline]
+    #[must_use]
+    pub fn plugin_footprints(&self) -> Vec<(&str, usize)> {
+        self.state
+            .iter()
+            .map(|(name, plugin)| {
+                let size = serde_json::to_vec(plugin).map_or(0, |vec| vec.len());
+
+                (name.as_str(), size)
+            })
+            .collect()
+    }
+
+    /// Export the state of the game as a structured, human-readable JSON value,
+    /// for external tools such as inspectors or web dashboards.
+    ///
+    /// The export lists every plugin by name, with its state and widgets, and
+    /// the state shared between plugins. Unlike the compact format used to
+    /// transfer state to plugins, it uses descriptive keys, and leaves out
+    /// engine internals (such as the drag state of widgets). The export can't
+    /// be loaded back into a game state.
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let plugins: serde_json::Map<_, _> = self
+            .state
+            .iter()
+            .map(|(name, plugin)| {
+                let widgets: serde_json::Map<_, _> = plugin
+                    .widgets
+                    .iter()
+                    .map(|(name, widget)| (name.clone(), widget.to_json()))
+                    .collect();
+
+                let plugin = serde_json::json!({
+                    "paused": self.paused.contains(name),
+                    "state": plugin.state,
+                    "widgets": widgets,
+                });
+
+                (name.clone(), plugin)
+            })
+            .collect();
+
+        serde_json::json!({
+            "plugins": plugins,
+            "shared": self.shared,
+        })
+    }
+
+    /// Get the total number of widgets managed by plugins.
+    #[inline]
+    #[must_use]
+    pub fn widget_count(&self) -> usize {
+        self.state.values().fold(0, |total, plugin| {
+            total.saturating_add(plugin.widgets.len())
+        })
+    }
+
+    /// Get the number of widgets owned by a plugin.
+    ///
+    /// Returns `None` if no state is registered for the plugin.
+    #[inline]
+    pub fn plugin_widget_count(&self, plugin: impl Into<String>) -> Option<usize> {
+        self.get(plugin).map(|plugin| plugin.widgets.len())
+    }
+
     /// Get immutable references to all widgets (and their positions) managed by
     /// plugins.
     #[inline]
@@ -52,29 +364,84 @@ impl Game {
         widgets
     }
 
+    /// Get immutable references to all widgets (and their positions) managed by
+    /// plugins, in the order in which they are rendered.
+    ///
+    /// Widgets are ordered by their layer, and then by their z-index within
+    /// that layer, so that widgets in a higher layer, or with a higher z-index,
+    /// are drawn on top. Widgets with an equal layer and z-index are ordered
+    /// by the name of their plugin, and then by their own name, to render
+    /// them in the same order every frame.
+    #[inline]
+    #[must_use]
+    pub fn widgets_in_render_order(&self) -> Vec<&WidgetWithPosition> {
+        self.named_widgets_in_render_order()
+            .into_iter()
+            .map(|(_, _, widget)| widget)
+            .collect()
+    }
+
+    /// Get immutable references to all widgets (and their positions) managed by
+    /// plugins, in the order in which they are rendered, together with the
+    /// name of the plugin they belong to, and their own name.
+    ///
+    /// See [`Game::widgets_in_render_order`].
+    #[inline]
+    #[must_use]
+    pub fn named_widgets_in_render_order(&self) -> Vec<(&str, &str, &WidgetWithPosition)> {
+        let mut widgets: Vec<_> = self
+            .state
+            .iter()
+            .flat_map(|(plugin, state)| {
+                state.widgets.iter().map(move |(name, widget)| {
+                    (
+                        (widget.render_key(), plugin.as_str(), name.as_str()),
+                        widget,
+                    )
+                })
+            })
+            .collect();
+
+        widgets.sort_by(|(a, _), (b, _)| a.cmp(b));
+        widgets
+            .into_iter()
+            .map(|((_, plugin, name), widget)| (plugin, name, widget))
+            .collect()
+    }
+
     /// Get mutable references to all widgets (and their positions) managed by
     /// plugins.
     ///
-    /// The returned tuple also contains the widget name as named by the plugin
-    /// the widget belongs to. This is relevant for when we track plugin events
-    /// and send them to a plugin, as the plugin might want to know which widget
-    /// the event originated from.
+    /// The returned tuple also contains the name of the plugin the widget
+    /// belongs to, and the widget name as named by that plugin. This is
+    /// relevant for when we track plugin events and send them to a plugin, as
+    /// the plugin might want to know which widget the event originated from.
+    ///
+    /// The widgets are returned in render order, see
+    /// [`Game::widgets_in_render_order`].
     #[inline]
     #[must_use]
-    pub fn widgets_mut(&mut self) -> Vec<(&str, &mut WidgetWithPosition)> {
+    pub fn widgets_mut(&mut self) -> Vec<(&str, &str, &mut WidgetWithPosition)> {
         let mut widgets = vec![];
-        for plugin in self.state.values_mut() {
-            for (name, widget) in &mut plugin.widgets {
-                widgets.push((name.as_str(), widget));
+        for (plugin, state) in &mut self.state {
+            for (name, widget) in &mut state.widgets {
+                widgets.push((
+                    (widget.render_key(), plugin.as_str(), name.as_str()),
+                    widget,
+                ));
             }
         }
 
+        widgets.sort_by(|(a, _), (b, _)| a.cmp(b));
         widgets
+            .into_iter()
+            .map(|((_, plugin, name), widget)| (plugin, name, widget))
+            .collect()
     }
 }
 
 /// The state of a plugin.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Plugin {
     /// The state of the plugin.
     #[serde(rename = "s")]
@@ -123,12 +490,78 @@ impl Plugin {
             .and_then(|v| serde_json::from_value(v).ok())
     }
 
+    /// Get immutable references to all widgets (and their positions) owned by
+    /// the plugin, together with the name of the widget.
+    #[inline]
+    #[must_use]
+    pub fn widgets(&self) -> Vec<(&str, &WidgetWithPosition)> {
+        self.widgets
+            .iter()
+            .map(|(name, widget)| (name.as_str(), widget))
+            .collect()
+    }
+
+    /// Get an immutable reference to a widget (and its position) owned by the
+    /// plugin.
+    #[inline]
+    pub fn widget(&self, key: impl Into<String>) -> Option<&WidgetWithPosition> {
+        self.widgets.get(&key.into())
+    }
+
     /// Get a mutable reference to a widget (and its position) owned by the
     /// plugin.
     #[inline]
     pub fn get_widget_mut(&mut self, key: impl Into<String>) -> Option<&mut WidgetWithPosition> {
         self.widgets.get_mut(&key.into())
     }
+
+    /// Add a widget (and its position) to the plugin, under the given name.
+    ///
+    /// Returns the widget previously stored under the same name, if any.
+    #[inline]
+    pub fn insert_widget(
+        &mut self,
+        key: impl Into<String>,
+        widget: WidgetWithPosition,
+    ) -> Option<WidgetWithPosition> {
+        self.widgets.insert(key.into(), widget)
+    }
+
+    /// Get a typed view of a widget owned by the plugin.
+    ///
+    /// The typed widget (e.g. [`MovingCircle`](crate::widget::MovingCircle))
+    /// is reconstructed from the widget state. Returns `None` if the widget
+    /// does not exist, or if its state can't be converted to the requested
+    /// type.
+    #[inline]
+    pub fn widget_as<T>(&self, key: impl Into<String>) -> Option<T>
+    where
+        T: for<'a> TryFrom<&'a Widget>,
+    {
+        self.widgets
+            .get(&key.into())
+            .and_then(|widget| T::try_from(widget.state()).ok())
+    }
+
+    /// Get all widget events for which the plugin registered an action on the
+    /// widget that triggered the event.
+    ///
+    /// Returns a list of `(action, event)` tuples, in the order in which the
+    /// events were triggered.
+    #[inline]
+    #[must_use]
+    pub fn handled_events<'a>(&'a self, events: &'a [Event]) -> Vec<(&'a str, &'a event::Widget)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Widget { name, event, .. } => {
+                    let action = self.widgets.get(name)?.handler(event.name())?;
+                    Some((action, event))
+                }
+                Event::Input(_) => None,
+            })
+            .collect()
+    }
 }
 
 /// A wrapper type that wraps the [`Widget`] state with its global coordinates
@@ -137,8 +570,19 @@ impl Plugin {
 /// This type exists because widgets themselves have no control over if, and
 /// where they should be drawn. Instead, their owners (plugins) control that
 /// state through this struct.
+//
+// The `remote = "Self"` attribute allows the manual `Deserialize`
+// implementation below to wrap the derived one, to migrate older versions of
+// the widget format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct WidgetWithPosition {
+    /// The version of the format in which the widget was serialized.
+    ///
+    /// Widgets serialized before versioning was introduced default to `0`.
+    #[serde(rename = "r", default)]
+    version: u16,
+
     /// The coordinates on the canvas where the top-left point of the widget
     /// begins.
     #[serde(rename = "c")]
@@ -147,41 +591,132 @@ pub struct WidgetWithPosition {
     /// Whether or not the widget currently has "focus".
     ///
     /// Focus in this case means the mouse pointer is within its bounds.
-    #[serde(rename = "f")]
+    #[serde(rename = "f", default)]
     focus: bool,
 
+    /// A list of widget event names, mapped to the action the owning plugin
+    /// wants to take when the event is triggered.
+    #[serde(rename = "h", default)]
+    handlers: HashMap<String, String>,
+
     /// Wether or not the widget should be drawn.
     #[serde(rename = "v")]
     visible: bool,
 
+    /// The order in which the widget is drawn, relative to other widgets.
+    ///
+    /// Widgets with a higher z-index are drawn on top of widgets with a lower
+    /// z-index.
+    #[serde(rename = "z", default)]
+    z_index: i32,
+
+    /// The layer in which the widget is drawn.
+    ///
+    /// Layers take precedence over the z-index, see
+    /// [`Game::widgets_in_render_order`].
+    #[serde(rename = "l", default)]
+    layer: widget::Layer,
+
+    /// A color multiplied into the colors of all components of the widget when
+    /// it is drawn, see [`Component::tint`](crate::Component::tint).
+    ///
+    /// Defaults to white, which leaves the colors unchanged.
+    #[serde(rename = "t", default, with = "crate::color::compact")]
+    tint: Color,
+
     /// The state of the widget which exists at the given position.
     #[serde(rename = "w")]
     state: Widget,
 
+    /// The revision of the widget state, see [`WidgetWithPosition::revision`].
+    #[serde(skip, default = "next_revision")]
+    revision: u64,
+
     /// Track how many subsequent "button presses" this widget has received.
     ///
-    /// This is used to distinguish short presses (which turn into clicks) from
-    /// a long press while holding down the mouse button.
-    ///
     /// TODO: Does this counter belong on a widget, or should there be a global
-    /// "drag" property? I think this should become a `dragging` boolean or
-    /// something, and have the "press" event be different from "drag".
-    #[serde(rename = "p")]
+    /// "drag" property?
+    #[serde(rename = "p", default)]
     pub press_counter: usize,
+
+    /// The pointer coordinates at which the current button press started.
+    ///
+    /// This is used to distinguish clicks from drags: a press only turns into
+    /// a drag once the pointer moves far enough away from this point.
+    #[serde(rename = "o", default)]
+    pub press_origin: Option<(f32, f32)>,
+
+    /// Whether or not the widget is being dragged.
+    #[serde(rename = "d", default)]
+    pub dragging: bool,
+}
+
+// The revision only tracks changes to the widget state, it isn't part of the
+// value of a widget.
+impl PartialEq for WidgetWithPosition {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version
+            && self.coordinates == other.coordinates
+            && self.focus == other.focus
+            && self.handlers == other.handlers
+            && self.visible == other.visible
+            && self.z_index == other.z_index
+            && self.layer == other.layer
+            && self.tint == other.tint
+            && self.state == other.state
+            && self.press_counter == other.press_counter
+            && self.press_origin == other.press_origin
+            && self.dragging == other.dragging
+    }
+}
+
+impl Serialize for WidgetWithPosition {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WidgetWithPosition {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer).map(Self::migrate)
+    }
 }
 
 impl WidgetWithPosition {
     /// Create a new widget at the given position.
     #[inline]
     #[must_use]
-    pub const fn new(coordinates: (f32, f32), visible: bool, state: Widget) -> Self {
+    pub fn new(coordinates: (f32, f32), visible: bool, state: Widget) -> Self {
         Self {
+            version: WIDGET_VERSION,
             coordinates,
             focus: false,
+            handlers: HashMap::new(),
             visible,
+            z_index: 0,
+            layer: widget::Layer::default(),
+            tint: Color::default(),
             state,
+            revision: next_revision(),
             press_counter: 0,
+            press_origin: None,
+            dragging: false,
+        }
+    }
+
+    /// Migrate a widget deserialized from an older format version to the
+    /// current version.
+    fn migrate(mut self) -> Self {
+        // Version `0` predates versioning. Any fields added since then are
+        // filled in by their `#[serde(default)]` values.
+        if self.version == 0 {
+            self.version = 1;
         }
+
+        self
     }
 
     /// Get the widget coordinates on the canvas.
@@ -197,6 +732,19 @@ impl WidgetWithPosition {
         self.coordinates = (x, y);
     }
 
+    /// Check if the point `(x, y)` on the canvas falls within the box of the
+    /// given dimensions, starting at the coordinates of the widget.
+    ///
+    /// The widget doesn't know its own dimensions, these are provided by the
+    /// widget runtime. Points on the edge of the box are inside the box.
+    #[inline]
+    #[must_use]
+    pub fn is_point_inside(&self, (width, height): (f32, f32), x: f32, y: f32) -> bool {
+        let (x_min, y_min) = self.coordinates;
+
+        x >= x_min && y >= y_min && x <= x_min + width && y <= y_min + height
+    }
+
     /// Is the widget visible or not.
     #[inline]
     #[must_use]
@@ -204,6 +752,95 @@ impl WidgetWithPosition {
         self.visible
     }
 
+    /// Show or hide the widget.
+    ///
+    /// Hidden widgets are not rendered.
+    #[inline]
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Get the z-index of the widget.
+    ///
+    /// See [`Game::widgets_in_render_order`] for details.
+    #[inline]
+    #[must_use]
+    pub const fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    /// Set the z-index of the widget.
+    #[inline]
+    pub fn set_z_index(&mut self, z_index: i32) {
+        self.z_index = z_index;
+    }
+
+    /// Get the layer in which the widget is drawn.
+    ///
+    /// See [`Game::widgets_in_render_order`] for details.
+    #[inline]
+    #[must_use]
+    pub const fn layer(&self) -> widget::Layer {
+        self.layer
+    }
+
+    /// Set the layer in which the widget is drawn.
+    #[inline]
+    pub fn set_layer(&mut self, layer: widget::Layer) {
+        self.layer = layer;
+    }
+
+    /// Get the color multiplied into the colors of the widget when drawn.
+    #[inline]
+    #[must_use]
+    pub const fn tint(&self) -> Color {
+        self.tint
+    }
+
+    /// Set the color multiplied into the colors of the widget when drawn, e.g.
+    /// to flash the widget red without changing its state.
+    ///
+    /// Set the tint to white to draw the widget in its own colors.
+    #[inline]
+    pub fn set_tint(&mut self, tint: Color) {
+        self.tint = tint;
+    }
+
+    /// Export the widget as a human-readable JSON value, see
+    /// [`Game::to_json`].
+    fn to_json(&self) -> Value {
+        let (x, y) = self.coordinates;
+
+        serde_json::json!({
+            "kind": self.state.kind,
+            "position": { "x": x, "y": y },
+            "visible": self.visible,
+            "focused": self.focus,
+            "layer": self.layer,
+            "z_index": self.z_index,
+            "tint": self.tint,
+            "handlers": self.handlers,
+            "state": self.state.state,
+        })
+    }
+
+    /// The key by which the widget is ordered when rendering.
+    const fn render_key(&self) -> (widget::Layer, i32) {
+        (self.layer, self.z_index)
+    }
+
+    /// Whether or not the widget captured the pointer.
+    ///
+    /// A widget captures the pointer when a mouse button is pressed within its
+    /// bounds, and releases it once the button is released. While captured,
+    /// the widget receives all pointer events, even when the pointer moves
+    /// outside of its bounds.
+    #[inline]
+    #[must_use]
+    pub const fn has_pointer_capture(&self) -> bool {
+        self.press_origin.is_some()
+    }
+
     /// Does the widget have focus or not.
     #[inline]
     #[must_use]
@@ -223,6 +860,19 @@ impl WidgetWithPosition {
         self.focus = true;
     }
 
+    /// Get the action registered for the given widget event, if any.
+    #[inline]
+    #[must_use]
+    pub fn handler(&self, event: &str) -> Option<&str> {
+        self.handlers.get(event).map(String::as_str)
+    }
+
+    /// Register an action to take when the widget triggers the given event.
+    #[inline]
+    pub fn set_handler(&mut self, event: impl Into<String>, action: impl Into<String>) {
+        self.handlers.insert(event.into(), action.into());
+    }
+
     /// Get an immutable reference to the widget state.
     #[inline]
     #[must_use]
@@ -231,10 +881,40 @@ impl WidgetWithPosition {
     }
 
     /// Get a mutable reference to the widget state.
+    ///
+    /// This marks the state as changed, see [`WidgetWithPosition::revision`].
     #[inline]
     pub fn state_mut(&mut self) -> &mut Widget {
+        self.revision = next_revision();
         &mut self.state
     }
+
+    /// Replace the widget state, if it differs from the current state.
+    ///
+    /// Returns whether the state changed.
+    #[inline]
+    pub fn set_state(&mut self, state: Widget) -> bool {
+        if self.state == state {
+            return false;
+        }
+
+        self.state = state;
+        self.revision = next_revision();
+        true
+    }
+
+    /// Get the revision of the widget state.
+    ///
+    /// The revision changes whenever the widget state changes. Revisions are
+    /// unique across widgets, so a widget that is replaced by another widget
+    /// (e.g. after deserializing it) gets a different revision as well. This
+    /// allows caching anything derived from the widget state, without having
+    /// to compare the state itself.
+    #[inline]
+    #[must_use]
+    pub const fn revision(&self) -> u64 {
+        self.revision
+    }
 }
 
 /// The state of a widget.
@@ -247,7 +927,7 @@ impl WidgetWithPosition {
 /// When a widget is updated or drawn, its scripts run, which in turn sends this
 /// state object over FFI to the wasm memory. Once the script is done, the state
 /// is serialized and sent back to the engine for safe-keeping.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Widget {
     /// The widget kind for which the state is stored.
     ///
@@ -319,6 +999,26 @@ pub struct Transfer {
     #[serde(rename = "e")]
     pub events: Vec<Event>,
 
+    /// The events of previous runs, one list of events per run, starting with
+    /// the oldest run.
+    ///
+    /// This is only populated if the engine is configured to keep a history
+    /// of events.
+    #[serde(rename = "h", default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<Vec<Event>>,
+
+    /// The values of the shared state keys the plugin declared.
+    ///
+    /// Unlike the borrowed state, the plugin is allowed to mutate these values,
+    /// see [`Game::write_shared`] for how the writes of multiple plugins are
+    /// merged.
+    #[serde(rename = "x", default, skip_serializing_if = "HashMap::is_empty")]
+    pub shared: HashMap<String, Value>,
+
+    /// The time details of the update during which the plugin runs.
+    #[serde(rename = "t", default)]
+    pub time: Time,
+
     /// Details about the canvas.
     #[serde(rename = "c")]
     pub canvas: Canvas,
@@ -332,14 +1032,613 @@ impl Transfer {
     ///
     /// This requires `ptr` to point to the correct pointer, and `len` to be the
     /// correct length of the Vec.
+    ///
+    /// If the data cannot be deserialized, the error is logged and an empty
+    /// transfer is returned.
     #[inline]
     pub unsafe fn from_raw(ptr: *mut u8, len: usize) -> Self {
         let vec = Vec::from_raw_parts(ptr, len, len);
 
-        #[allow(clippy::match_wild_err_arm)]
         match serde_json::from_slice(&vec) {
             Ok(value) => value,
-            Err(_) => todo!("logging"),
+            Err(err) => {
+                log::error!("invalid state transfer: {}", err);
+                Self::default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod game {
+        use super::*;
+        use std::{cell::RefCell, rc::Rc};
+
+        #[test]
+        fn observer() {
+            let names = Rc::new(RefCell::new(vec![]));
+            let mut game = Game::default();
+
+            game.register_plugin_state("foo", Plugin::default());
+
+            let observed = Rc::clone(&names);
+            game.set_observer(move |name| observed.borrow_mut().push(name.to_owned()));
+
+            let mut state = HashMap::new();
+            state.insert("bar", "baz");
+            let widgets = HashMap::<String, _>::new();
+
+            game.register_plugin_state("foo", Plugin::new(state, widgets));
+            game.register_plugin_state("qux", Plugin::default());
+
+            assert_eq!(*names.borrow(), vec!["foo".to_owned(), "qux".to_owned()]);
+            assert_eq!(
+                game.get("foo").and_then(|p| p.get("bar")),
+                Some(&Value::from("baz"))
+            );
+
+            game.clear_observer();
+            game.register_plugin_state("foo", Plugin::default());
+
+            assert_eq!(names.borrow().len(), 2);
+        }
+
+        #[test]
+        fn save_and_load() {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let (name, widget) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .position(5.0, 6.0)
+                .build();
+
+            let mut state = HashMap::new();
+            state.insert("score", Value::from(42));
+
+            let mut widgets = HashMap::new();
+            widgets.insert(name, widget);
+
+            let mut game = Game::default();
+            game.register_plugin_state("foo", Plugin::new(state, widgets));
+            game.set_plugin_paused("foo", true);
+            game.declare_shared("turn", Value::from(3));
+            game.write_shared("turn", Value::from(4));
+            game.save_to_path(file.path()).unwrap();
+
+            let loaded = Game::load_from_path(file.path()).unwrap();
+
+            assert_eq!(loaded.get("foo"), game.get("foo"));
+            assert!(loaded.is_plugin_paused("foo"));
+
+            // Uncommitted writes to the shared state aren't saved.
+            assert_eq!(loaded.shared("turn"), Some(&Value::from(3)));
+        }
+
+        #[test]
+        fn register_keeps_widget_revisions() {
+            let plugin = |radius: f32| {
+                let (name, widget) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                    .attribute("radius", radius)
+                    .build();
+
+                let mut widgets = HashMap::new();
+                widgets.insert(name, widget);
+
+                Plugin::new(HashMap::<String, Value>::new(), widgets)
+            };
+            let revision = |game: &Game| {
+                game.get("foo")
+                    .unwrap()
+                    .widget("circle")
+                    .unwrap()
+                    .revision()
+            };
+
+            let mut game = Game::default();
+            game.register_plugin_state("foo", plugin(10.0));
+            let first = revision(&game);
+
+            game.register_plugin_state("foo", plugin(10.0));
+            assert_eq!(revision(&game), first);
+
+            game.register_plugin_state("foo", plugin(20.0));
+            assert_ne!(revision(&game), first);
+        }
+
+        #[test]
+        fn load_invalid() {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(b"not a game state").unwrap();
+
+            let err = Game::load_from_path(file.path()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+            let err = Game::load_from_path("missing.json").unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        }
+
+        #[test]
+        fn widgets_in_render_order() {
+            let widget = |name: &str, x: f32, z_index: i32| {
+                widget::Builder::new(name, widget::Kind::MovingCircle)
+                    .position(x, 0.0)
+                    .z_index(z_index)
+                    .build()
+            };
+
+            let plugin = |widgets: Vec<(String, WidgetWithPosition)>| {
+                Plugin::new(
+                    HashMap::<String, Value>::new(),
+                    widgets.into_iter().collect::<HashMap<_, _>>(),
+                )
+            };
+
+            let mut game = Game::default();
+            game.register_plugin_state(
+                "b",
+                plugin(vec![widget("top", 1.0, 10), widget("bottom", 2.0, -1)]),
+            );
+            game.register_plugin_state(
+                "a",
+                plugin(vec![widget("second", 3.0, 0), widget("first", 4.0, 0)]),
+            );
+            game.register_plugin_state("c", plugin(vec![widget("third", 5.0, 0)]));
+
+            let order: Vec<_> = game
+                .widgets_in_render_order()
+                .iter()
+                .map(|widget| widget.coordinates().0)
+                .collect();
+
+            assert_eq!(order, vec![2.0, 4.0, 3.0, 5.0, 1.0]);
+
+            let names: Vec<_> = game
+                .named_widgets_in_render_order()
+                .into_iter()
+                .map(|(plugin, name, _)| (plugin, name))
+                .collect();
+
+            assert_eq!(
+                names,
+                vec![
+                    ("b", "bottom"),
+                    ("a", "first"),
+                    ("a", "second"),
+                    ("c", "third"),
+                    ("b", "top")
+                ]
+            );
+
+            let names: Vec<_> = game
+                .widgets_mut()
+                .into_iter()
+                .map(|(_, name, _)| name.to_owned())
+                .collect();
+
+            assert_eq!(names, vec!["bottom", "first", "second", "third", "top"]);
+        }
+
+        #[test]
+        fn layers_before_z_index() {
+            let widget = |name: &str, layer: widget::Layer, z_index: i32| {
+                widget::Builder::new(name, widget::Kind::MovingCircle)
+                    .layer(layer)
+                    .z_index(z_index)
+                    .build()
+            };
+
+            let widgets = vec![
+                widget("hud", widget::Layer::Ui, -10),
+                widget("player", widget::Layer::World, 10),
+                widget("sky", widget::Layer::Background, 100),
+            ];
+
+            let mut game = Game::default();
+            game.register_plugin_state(
+                "a",
+                Plugin::new(
+                    HashMap::<String, Value>::new(),
+                    widgets.into_iter().collect::<HashMap<_, _>>(),
+                ),
+            );
+
+            let layers: Vec<_> = game
+                .widgets_in_render_order()
+                .iter()
+                .map(|widget| widget.layer())
+                .collect();
+
+            assert_eq!(
+                layers,
+                vec![
+                    widget::Layer::Background,
+                    widget::Layer::World,
+                    widget::Layer::Ui
+                ]
+            );
+
+            let names: Vec<_> = game
+                .widgets_mut()
+                .into_iter()
+                .map(|(_, name, _)| name.to_owned())
+                .collect();
+
+            assert_eq!(names, vec!["sky", "player", "hud"]);
+        }
+    }
+
+    mod widget_count {
+        use super::*;
+
+        #[test]
+        fn per_plugin() {
+            let widgets = |names: &[&str]| {
+                let widgets: HashMap<_, _> = names
+                    .iter()
+                    .map(|name| widget::Builder::new(*name, widget::Kind::MovingCircle).build())
+                    .collect();
+
+                Plugin::new(HashMap::<String, Value>::new(), widgets)
+            };
+
+            let mut game = Game::default();
+            assert_eq!(game.widget_count(), 0);
+
+            game.register_plugin_state("foo", widgets(&["a", "b"]));
+            game.register_plugin_state("bar", widgets(&["c", "d", "e"]));
+            game.register_plugin_state("baz", Plugin::default());
+
+            assert_eq!(game.widget_count(), 5);
+            assert_eq!(game.plugin_widget_count("foo"), Some(2));
+            assert_eq!(game.plugin_widget_count("bar"), Some(3));
+            assert_eq!(game.plugin_widget_count("baz"), Some(0));
+            assert_eq!(game.plugin_widget_count("qux"), None);
+        }
+    }
+
+    mod shared {
+        use super::*;
+
+        #[test]
+        fn last_write_wins() {
+            let mut game = Game::default();
+            game.declare_shared("score", 1.into());
+            game.declare_shared("score", 2.into());
+
+            assert_eq!(game.shared("score"), Some(&Value::from(1)));
+
+            game.write_shared("score", 3.into());
+            game.write_shared("score", 4.into());
+
+            // Writes are invisible until committed.
+            assert_eq!(game.shared("score"), Some(&Value::from(1)));
+
+            game.commit_shared();
+            assert_eq!(game.shared("score"), Some(&Value::from(4)));
+
+            game.commit_shared();
+            assert_eq!(game.shared("score"), Some(&Value::from(4)));
+        }
+    }
+
+    mod to_json {
+        use super::*;
+
+        #[test]
+        fn plugins_and_widgets() {
+            let widgets: HashMap<_, _> = vec![
+                widget::Builder::new("player", widget::Kind::MovingCircle)
+                    .attribute("radius", 10.0)
+                    .position(1.0, 2.0)
+                    .build(),
+                widget::Builder::new("enemy", widget::Kind::MovingCircle).build(),
+            ]
+            .into_iter()
+            .collect();
+
+            let mut state = HashMap::new();
+            state.insert("score", 3);
+
+            let mut game = Game::default();
+            game.register_plugin_state("foo", Plugin::new(state, widgets));
+            game.set_plugin_paused("foo", true);
+
+            let json = game.to_json();
+            let plugin = &json["plugins"]["foo"];
+
+            assert_eq!(plugin["paused"], true);
+            assert_eq!(plugin["state"]["score"], 3);
+
+            let mut names: Vec<_> = plugin["widgets"].as_object().unwrap().keys().collect();
+            names.sort();
+            assert_eq!(names, vec!["enemy", "player"]);
+
+            let player = &plugin["widgets"]["player"];
+            assert_eq!(player["kind"], "MovingCircle");
+            assert_eq!(player["position"]["x"], 1.0);
+            assert_eq!(player["state"]["radius"], 10.0);
+        }
+    }
+
+    mod memory_footprint {
+        use super::*;
+
+        #[test]
+        fn grows_with_state() {
+            let mut game = Game::default();
+            assert_eq!(game.memory_footprint(), 0);
+
+            game.register_plugin_state("foo", Plugin::default());
+            game.register_plugin_state("bar", Plugin::default());
+            let empty = game.memory_footprint();
+            assert!(empty > 0);
+
+            let value = game.get_mut("foo").unwrap();
+            let mut state = HashMap::new();
+            state.insert("baz", "a long string value");
+            *value = Plugin::new(state, HashMap::<String, _>::new());
+
+            assert!(game.memory_footprint() > empty);
+
+            let footprints: HashMap<_, _> = game.plugin_footprints().into_iter().collect();
+            assert!(footprints["foo"] > footprints["bar"]);
+            assert_eq!(
+                footprints["foo"] + footprints["bar"],
+                game.memory_footprint()
+            );
+        }
+    }
+
+    mod is_point_inside {
+        use super::*;
+
+        fn widget() -> WidgetWithPosition {
+            let state = Widget::new(widget::Kind::MovingCircle, HashMap::<String, _>::new());
+
+            WidgetWithPosition::new((10.0, 20.0), true, state)
+        }
+
+        #[test]
+        fn inside() {
+            let widget = widget();
+
+            assert!(widget.is_point_inside((30.0, 40.0), 25.0, 35.0));
+            assert!(widget.is_point_inside((30.0, 40.0), 10.0, 20.0));
+            assert!(widget.is_point_inside((30.0, 40.0), 40.0, 60.0));
+        }
+
+        #[test]
+        fn outside() {
+            let widget = widget();
+
+            assert!(!widget.is_point_inside((30.0, 40.0), 9.0, 35.0));
+            assert!(!widget.is_point_inside((30.0, 40.0), 25.0, 19.0));
+            assert!(!widget.is_point_inside((30.0, 40.0), 41.0, 35.0));
+            assert!(!widget.is_point_inside((30.0, 40.0), 25.0, 61.0));
+        }
+    }
+
+    mod plugin {
+        use super::*;
+
+        #[test]
+        fn borrowed_widget() {
+            let (name, widget) = widget::Builder::new("player", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .position(20.0, 30.0)
+                .build();
+
+            let mut widgets = HashMap::new();
+            widgets.insert(name, widget);
+
+            // The "game" plugin owns the player, the "hud" plugin borrows it.
+            let mut game = Game::default();
+            game.register_plugin_state(
+                "game",
+                Plugin::new(HashMap::<String, Value>::new(), widgets),
+            );
+            game.register_plugin_state("hud", Plugin::default());
+
+            let mut borrowed = HashMap::new();
+            borrowed.insert("game".to_owned(), game.get("game").cloned().unwrap());
+
+            let transfer = Transfer {
+                owned: game.get("hud").cloned().unwrap(),
+                borrowed,
+                ..Transfer::default()
+            };
+
+            let json = serde_json::to_vec(&transfer).unwrap();
+            let transfer: Transfer = serde_json::from_slice(&json).unwrap();
+
+            let player = transfer.borrowed["game"].widget("player").unwrap();
+            assert_eq!(player.coordinates(), (20.0, 30.0));
+            assert!(transfer.owned.widget("player").is_none());
+        }
+
+        #[test]
+        fn insert_widget() {
+            let widget = |x: f32| {
+                widget::Builder::new("circle", widget::Kind::MovingCircle)
+                    .position(x, 0.0)
+                    .build()
+                    .1
+            };
+
+            let mut plugin = Plugin::default();
+            assert!(plugin.insert_widget("circle", widget(1.0)).is_none());
+
+            let previous = plugin.insert_widget("circle", widget(2.0)).unwrap();
+            assert_eq!(previous.coordinates(), (1.0, 0.0));
+
+            // The widget survives a state transfer.
+            let json = serde_json::to_vec(&plugin).unwrap();
+            let plugin: Plugin = serde_json::from_slice(&json).unwrap();
+            assert_eq!(plugin.widget("circle").unwrap().coordinates(), (2.0, 0.0));
+        }
+
+        #[test]
+        fn handled_events() {
+            let (name, widget) = widget::Builder::new("button", widget::Kind::ButtonRectangle)
+                .on_event("activated", "start_game")
+                .build();
+
+            let mut widgets = HashMap::new();
+            widgets.insert(name, widget);
+            let plugin = Plugin::new(HashMap::<String, Value>::new(), widgets);
+
+            let events = vec![
+                Event::Widget {
+                    plugin: "test".to_owned(),
+                    name: "button".to_owned(),
+                    event: event::Widget::new("activated"),
+                },
+                Event::Widget {
+                    plugin: "test".to_owned(),
+                    name: "button".to_owned(),
+                    event: event::Widget::new("hovered"),
+                },
+                Event::Widget {
+                    plugin: "test".to_owned(),
+                    name: "other".to_owned(),
+                    event: event::Widget::new("activated"),
+                },
+            ];
+
+            let handled = plugin.handled_events(&events);
+
+            assert_eq!(handled.len(), 1);
+            assert_eq!(handled[0].0, "start_game");
+            assert_eq!(handled[0].1.name(), "activated");
+        }
+
+        #[test]
+        fn widget_as() {
+            use widget::Runtime;
+
+            let (name, widget) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .build();
+
+            let mut widgets = HashMap::new();
+            widgets.insert(name, widget);
+            let plugin = Plugin::new(HashMap::<String, Value>::new(), widgets);
+
+            let circle = plugin.widget_as::<widget::MovingCircle>("circle").unwrap();
+            assert_eq!(circle.attribute("radius"), Some(10.0.into()));
+
+            assert!(plugin
+                .widget_as::<widget::ButtonRectangle>("circle")
+                .is_none());
+            assert!(plugin
+                .widget_as::<widget::MovingCircle>("unknown")
+                .is_none());
+        }
+    }
+
+    mod widget_with_position {
+        use super::*;
+
+        #[test]
+        fn set_state() {
+            let state = |radius: f32| {
+                let mut state = HashMap::new();
+                state.insert("radius", radius.into());
+
+                Widget::new(widget::Kind::MovingCircle, state)
+            };
+
+            let mut widget = WidgetWithPosition::new((0.0, 0.0), true, state(10.0));
+
+            assert!(!widget.set_state(state(10.0)));
+            assert!(widget.set_state(state(20.0)));
+            assert_eq!(widget.state(), &state(20.0));
+        }
+
+        #[test]
+        fn revision() {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+            let state = Widget::new(widget::Kind::MovingCircle, state);
+
+            let mut widget = WidgetWithPosition::new((0.0, 0.0), true, state.clone());
+            let revision = widget.revision();
+
+            widget.set_coordinates(10.0, 10.0);
+            assert!(!widget.set_state(state.clone()));
+            assert_eq!(widget.revision(), revision);
+
+            let _ = widget.state_mut();
+            assert_ne!(widget.revision(), revision);
+
+            // Equal widgets don't necessarily share a revision.
+            let other = WidgetWithPosition::new((10.0, 10.0), true, state);
+            assert_eq!(other, widget);
+            assert_ne!(other.revision(), widget.revision());
+        }
+
+        #[test]
+        fn tint() {
+            let (_, mut widget) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .tint(Color::new(1.0, 0.0, 0.0, 1.0))
+                .build();
+
+            assert_eq!(widget.tint(), Color::new(1.0, 0.0, 0.0, 1.0));
+
+            let revision = widget.revision();
+            widget.set_tint(Color::default());
+
+            assert_eq!(widget.tint(), Color::default());
+            assert_eq!(widget.revision(), revision);
+        }
+
+        #[test]
+        fn deserialize_legacy() {
+            let json = r#"{"c":[10.0,20.0],"v":true,"w":{"k":"MovingCircle","s":{}}}"#;
+            let widget: WidgetWithPosition = serde_json::from_str(json).unwrap();
+
+            assert_eq!(widget.version, WIDGET_VERSION);
+            assert_eq!(widget.coordinates(), (10.0, 20.0));
+            assert!(widget.is_visible());
+            assert!(!widget.focussed());
+            assert_eq!(widget.tint(), Color::default());
+            assert_eq!(widget.press_counter, 0);
+        }
+
+        #[test]
+        fn round_trip() {
+            let state = Widget::new(widget::Kind::MovingCircle, HashMap::<String, _>::new());
+            let widget = WidgetWithPosition::new((1.0, 2.0), false, state);
+
+            let json = serde_json::to_string(&widget).unwrap();
+            let widget: WidgetWithPosition = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(widget.version, WIDGET_VERSION);
+            assert_eq!(widget.coordinates(), (1.0, 2.0));
+            assert!(!widget.is_visible());
+        }
+
+        #[test]
+        fn set_visible() {
+            let widgets: HashMap<_, _> =
+                vec![widget::Builder::new("circle", widget::Kind::MovingCircle).build()]
+                    .into_iter()
+                    .collect();
+
+            let mut plugin = Plugin::new(HashMap::<String, Value>::new(), widgets);
+            plugin.get_widget_mut("circle").unwrap().set_visible(false);
+
+            let visible: Vec<_> = plugin
+                .widgets()
+                .into_iter()
+                .filter(|(_, widget)| widget.is_visible())
+                .collect();
+
+            assert!(visible.is_empty());
+            assert!(!plugin.widget("circle").unwrap().is_visible());
         }
     }
 }
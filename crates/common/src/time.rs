@@ -0,0 +1,56 @@
+//! Details about the passage of (game) time.
+
+use crate::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Time details of a single game update.
+///
+/// The engine updates the game at a fixed interval, the elapsed time is the
+/// sum of all those intervals, not the wall-clock time since the game started.
+/// Time does not pass while the game is paused.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Time {
+    /// The total time (in nanoseconds) the game was updated for, before the
+    /// current update.
+    #[serde(rename = "e")]
+    elapsed: u64,
+
+    /// The fixed time (in nanoseconds) between two updates.
+    #[serde(rename = "d")]
+    delta: u64,
+}
+
+impl Time {
+    /// Create a new time from the elapsed time and the update interval, both
+    /// in nanoseconds.
+    #[inline]
+    #[must_use]
+    pub const fn new(elapsed: u64, delta: u64) -> Self {
+        Self { elapsed, delta }
+    }
+
+    /// Get the total time the game was updated for, before the current update.
+    #[inline]
+    #[must_use]
+    pub const fn elapsed(self) -> Duration {
+        Duration::from_nanos(self.elapsed)
+    }
+
+    /// Get the fixed time between two updates.
+    #[inline]
+    #[must_use]
+    pub const fn delta(self) -> Duration {
+        Duration::from_nanos(self.delta)
+    }
+
+    /// Combine this update with the update directly following it, into a
+    /// single update spanning the time of both.
+    #[inline]
+    #[must_use]
+    pub const fn merge(self, next: Self) -> Self {
+        Self {
+            elapsed: self.elapsed,
+            delta: self.delta.saturating_add(next.delta),
+        }
+    }
+}
@@ -1,26 +1,281 @@
 //! Widget related items.
 
 mod button_rectangle;
+mod checkbox;
 mod moving_circle;
+mod progress_bar;
+mod slider;
 
 use crate::{
-    event, Component, Deserialize, Event, Serialize, Value, WidgetState, WidgetWithPosition,
+    event, Color, Component, Deserialize, DeserializeOwned, Event, Serialize, Shape, Value,
+    WidgetState, WidgetWithPosition,
 };
 pub use button_rectangle::ButtonRectangle;
+pub use checkbox::Checkbox;
 pub use moving_circle::MovingCircle;
-use std::collections::HashMap;
+pub use progress_bar::ProgressBar;
+pub use slider::Slider;
+use std::{collections::HashMap, fmt, str::FromStr};
 
 /// List of supported widget kinds.
 ///
 /// The engine exposes a set of default widgets, and a "custom" widget kind
 /// which calls out to registered Wasm-based widgets.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Kind {
     /// An example widget of a circle that can be manipulated from a plugin.
     MovingCircle,
 
     /// A (work in progress) rectangular button.
     ButtonRectangle,
+
+    /// A horizontal slider to select a value from a range.
+    Slider,
+
+    /// A checkbox that can be toggled on and off.
+    Checkbox,
+
+    /// A (non-interactive) horizontal progress bar.
+    ProgressBar,
+}
+
+/// The semantic role of a widget.
+///
+/// Together with [`Runtime::accessibility_label`], this describes what a
+/// widget is to assistive technologies (e.g. screen readers), or to UI tests
+/// that want to find widgets without depending on their visual appearance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Role {
+    /// A widget without a more specific role.
+    Generic,
+
+    /// A (possibly interactive) graphical element.
+    Graphic,
+
+    /// A button that triggers an action when activated.
+    Button,
+
+    /// A control to select a value from a range.
+    Slider,
+
+    /// A control that can be checked or unchecked.
+    Checkbox,
+
+    /// An indicator of the progress of a task.
+    ProgressBar,
+}
+
+/// The layer in which a widget is rendered.
+///
+/// Layers are drawn in a fixed order, from the background up to the UI, so
+/// that for example a HUD is always drawn on top of the game world. Within a
+/// layer, widgets are ordered by their z-index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Layer {
+    /// The layer drawn first, behind all other layers.
+    Background,
+
+    /// The layer containing the game world.
+    World,
+
+    /// The layer drawn last, on top of all other layers.
+    Ui,
+}
+
+impl Default for Layer {
+    #[inline]
+    fn default() -> Self {
+        Self::World
+    }
+}
+
+impl Kind {
+    /// Get the default attributes of the widget kind.
+    ///
+    /// These are the attributes a widget falls back to when they aren't
+    /// configured. They are added to the widget state when building a widget,
+    /// so that the state explicitly describes the widget.
+    #[inline]
+    #[must_use]
+    pub fn default_attributes(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            Self::MovingCircle => MovingCircle::default_attributes(),
+            Self::ButtonRectangle => ButtonRectangle::default_attributes(),
+            Self::Slider => Slider::default_attributes(),
+            Self::Checkbox => Checkbox::default_attributes(),
+            Self::ProgressBar => ProgressBar::default_attributes(),
+        }
+    }
+
+    /// Get the attributes the widget kind supports.
+    fn attributes(&self) -> Vec<Attribute> {
+        match self {
+            Self::MovingCircle => MovingCircle::attributes(),
+            Self::ButtonRectangle => ButtonRectangle::attributes(),
+            Self::Slider => Slider::attributes(),
+            Self::Checkbox => Checkbox::attributes(),
+            Self::ProgressBar => ProgressBar::attributes(),
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = UnknownKind;
+
+    /// Parse a widget kind from its name, either in the form used when
+    /// serializing (e.g. `"MovingCircle"`), or in snake case (e.g.
+    /// `"moving_circle"`).
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "MovingCircle" | "moving_circle" => Ok(Self::MovingCircle),
+            "ButtonRectangle" | "button_rectangle" => Ok(Self::ButtonRectangle),
+            "Slider" | "slider" => Ok(Self::Slider),
+            "Checkbox" | "checkbox" => Ok(Self::Checkbox),
+            "ProgressBar" | "progress_bar" => Ok(Self::ProgressBar),
+            _ => Err(UnknownKind(s.to_owned())),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown widget kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKind(pub String);
+
+impl fmt::Display for UnknownKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown widget kind `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownKind {}
+
+/// The error returned when a widget cannot be constructed from its state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// The kind of widget that failed to be constructed.
+    pub widget_kind: Kind,
+
+    /// The name of the attribute that caused the failure.
+    pub attribute: String,
+
+    /// Why the attribute caused the failure.
+    pub reason: ErrorReason,
+}
+
+/// The reason an attribute of a widget is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorReason {
+    /// The attribute is required, but missing.
+    Missing,
+
+    /// The attribute has a value of the wrong type or format, with a
+    /// description of the problem.
+    Malformed(String),
+
+    /// The attribute is not supported by the widget kind.
+    Unknown,
+}
+
+impl Error {
+    /// Create a new error for the given widget kind and attribute.
+    #[inline]
+    pub fn new(widget_kind: Kind, attribute: impl Into<String>, reason: ErrorReason) -> Self {
+        Self {
+            widget_kind,
+            attribute: attribute.into(),
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            ErrorReason::Missing => write!(
+                f,
+                "missing `{}` attribute of {:?} widget",
+                self.attribute, self.widget_kind
+            ),
+            ErrorReason::Malformed(reason) => write!(
+                f,
+                "malformed `{}` attribute of {:?} widget: {}",
+                self.attribute, self.widget_kind, reason
+            ),
+            ErrorReason::Unknown => write!(
+                f,
+                "unknown `{}` attribute of {:?} widget",
+                self.attribute, self.widget_kind
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Get a required attribute from the state of a widget.
+///
+/// Returns an error naming the attribute if it is missing, or if its value
+/// cannot be converted to the requested type.
+fn required<T: DeserializeOwned>(state: &WidgetState, attribute: &str) -> Result<T, Error> {
+    let kind = state.kind().clone();
+    let value = state
+        .get(attribute)
+        .ok_or_else(|| Error::new(kind.clone(), attribute, ErrorReason::Missing))?;
+
+    serde_json::from_value(value.clone())
+        .map_err(|err| Error::new(kind, attribute, ErrorReason::Malformed(err.to_string())))
+}
+
+/// An attribute supported by a widget kind.
+///
+/// Used to validate the attributes of a widget before it is built.
+struct Attribute {
+    /// The name of the attribute.
+    name: &'static str,
+
+    /// Whether the widget cannot be constructed without the attribute.
+    required: bool,
+
+    /// Check if a value has the type the widget expects, returning a
+    /// description of the problem if it doesn't.
+    check: fn(&Value) -> Result<(), String>,
+}
+
+impl Attribute {
+    /// An attribute the widget cannot be constructed without.
+    fn required<T: DeserializeOwned>(name: &'static str) -> Self {
+        Self {
+            name,
+            required: true,
+            check: check_type::<T>,
+        }
+    }
+
+    /// An attribute the widget falls back to a default value for.
+    fn optional<T: DeserializeOwned>(name: &'static str) -> Self {
+        Self {
+            name,
+            required: false,
+            check: check_type::<T>,
+        }
+    }
+}
+
+/// Check if a value can be converted to the requested type.
+fn check_type<T: DeserializeOwned>(value: &Value) -> Result<(), String> {
+    serde_json::from_value::<T>(value.clone())
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+impl Default for Role {
+    #[inline]
+    fn default() -> Self {
+        Self::Generic
+    }
 }
 
 /// An enumeration of widgets with their respective states..
@@ -29,6 +284,9 @@ pub enum Kind {
 pub enum Widget {
     MovingCircle(WidgetState),
     ButtonRectangle(WidgetState),
+    Slider(WidgetState),
+    Checkbox(WidgetState),
+    ProgressBar(WidgetState),
 }
 
 impl From<WidgetState> for Widget {
@@ -37,6 +295,9 @@ impl From<WidgetState> for Widget {
         match state.kind() {
             Kind::MovingCircle => Self::MovingCircle(state),
             Kind::ButtonRectangle => Self::ButtonRectangle(state),
+            Kind::Slider => Self::Slider(state),
+            Kind::Checkbox => Self::Checkbox(state),
+            Kind::ProgressBar => Self::ProgressBar(state),
         }
     }
 }
@@ -55,8 +316,22 @@ pub struct Builder {
     /// The position of the widget within the canvas.
     position: (f32, f32),
 
+    /// The layer in which the widget is drawn.
+    layer: Layer,
+
+    /// The order in which the widget is drawn, relative to other widgets in
+    /// the same layer.
+    z_index: i32,
+
+    /// The color multiplied into the colors of the widget when drawn.
+    tint: Color,
+
     /// A list of attributes with which to configure the widget.
     attributes: HashMap<String, Value>,
+
+    /// A list of widget event names, mapped to the action the owning plugin
+    /// wants to take when the event is triggered.
+    handlers: HashMap<String, String>,
 }
 
 impl Builder {
@@ -69,10 +344,27 @@ impl Builder {
             kind,
             visible: true,
             position: (0.0, 0.0),
+            layer: Layer::World,
+            z_index: 0,
+            tint: Color::default(),
             attributes: HashMap::default(),
+            handlers: HashMap::default(),
         }
     }
 
+    /// Create a new widget builder for a widget kind given by name.
+    ///
+    /// This allows creating widgets from external data, such as level files.
+    /// See [`Kind::from_str`] for the supported names.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kind is unknown.
+    #[inline]
+    pub fn from_kind_str(name: impl Into<String>, kind: &str) -> Result<Self, UnknownKind> {
+        Ok(Self::new(name, kind.parse()?))
+    }
+
     /// Add an attribute to the widget configuration.
     #[inline]
     #[must_use]
@@ -99,17 +391,122 @@ impl Builder {
         self
     }
 
+    /// Set the layer in which the widget is drawn.
+    ///
+    /// Defaults to [`Layer::World`].
+    #[inline]
+    #[must_use]
+    pub const fn layer(mut self, layer: Layer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// Set the z-index of the widget.
+    ///
+    /// Widgets with a higher z-index are drawn on top of widgets with a lower
+    /// z-index in the same layer. Defaults to `0`.
+    #[inline]
+    #[must_use]
+    pub const fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Set the color multiplied into the colors of the widget when drawn.
+    ///
+    /// Defaults to white, which draws the widget in its own colors.
+    #[inline]
+    #[must_use]
+    pub const fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// Register an action to take when the widget triggers the named event.
+    ///
+    /// The action is a name chosen by the plugin. Any matching events are
+    /// returned, together with their action, by
+    /// [`PluginState::handled_events`](crate::PluginState::handled_events),
+    /// so that the plugin doesn't have to match on the events itself.
+    #[inline]
+    #[must_use]
+    pub fn on_event(mut self, event: impl Into<String>, action: impl Into<String>) -> Self {
+        self.handlers.insert(event.into(), action.into());
+        self
+    }
+
+    /// Check the configured attributes against the attributes supported by
+    /// the widget kind.
+    ///
+    /// [`Registration::widget`](crate::Registration::widget) uses this to
+    /// report misconfigured widgets when the plugin is registered, instead of
+    /// when the widget is first used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for every missing required attribute, every attribute
+    /// with a value of the wrong type, and every unknown attribute.
+    #[inline]
+    pub fn validate(&self) -> Result<(), Vec<Error>> {
+        let supported = self.kind.attributes();
+        let mut errors = vec![];
+
+        for attribute in &supported {
+            let reason = match self.attributes.get(attribute.name) {
+                Some(value) => match (attribute.check)(value) {
+                    Ok(()) => continue,
+                    Err(reason) => ErrorReason::Malformed(reason),
+                },
+                None if attribute.required => ErrorReason::Missing,
+                None => continue,
+            };
+
+            errors.push(Error::new(self.kind.clone(), attribute.name, reason));
+        }
+
+        let mut unknown: Vec<_> = self
+            .attributes
+            .keys()
+            .filter(|key| !supported.iter().any(|attribute| attribute.name == **key))
+            .collect();
+        unknown.sort();
+
+        for key in unknown {
+            errors.push(Error::new(self.kind.clone(), key, ErrorReason::Unknown));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// Finalize building the widget and get back a tuple of the name of the
     /// widget and the widget itself.
+    ///
+    /// Any attributes that weren't configured are set to the default
+    /// attributes of the widget kind. The attributes are not validated, use
+    /// [`Builder::validate`] to check them beforehand.
     #[inline]
     #[must_use]
-    pub fn build(self) -> (String, WidgetWithPosition) {
+    pub fn build(mut self) -> (String, WidgetWithPosition) {
+        for (key, value) in self.kind.default_attributes() {
+            self.attributes.entry(key.to_owned()).or_insert(value);
+        }
+
         let widget = WidgetState::new(self.kind, self.attributes);
 
-        (
-            self.name,
-            WidgetWithPosition::new(self.position, self.visible, widget),
-        )
+        let mut widget = WidgetWithPosition::new(self.position, self.visible, widget);
+        widget.set_layer(self.layer);
+        widget.set_z_index(self.z_index);
+        widget.set_tint(self.tint);
+
+        for (event, action) in self.handlers {
+            widget.set_handler(event, action);
+        }
+
+        (self.name, widget)
     }
 }
 
@@ -152,6 +549,10 @@ pub trait Runtime {
     /// called. The event contains the interaction type (e.g. mouse-over, key
     /// press, etc.).
     ///
+    /// The `delta` is the amount of time (in seconds) that passed since the
+    /// previous update, which allows widgets to animate independent of the
+    /// update rate of the engine.
+    ///
     /// When a widget acts on an interaction, it can itself trigger one or more
     /// events based on that interaction.
     ///
@@ -161,10 +562,63 @@ pub trait Runtime {
     /// By default a widget is non-interactive.
     #[inline]
     #[allow(unused)]
-    fn interact(&mut self, event: &Event) -> Vec<event::Widget> {
+    fn interact(&mut self, event: &Event, delta: f32) -> Vec<event::Widget> {
+        vec![]
+    }
+
+    /// Tick is called once for every update of the engine, after the widget
+    /// interacted with any events of that update.
+    ///
+    /// The `delta` is the amount of time (in seconds) that passed since the
+    /// previous update. This allows widgets to change over time, without
+    /// depending on player interaction (e.g. to simulate physics).
+    ///
+    /// Similar to [`Runtime::interact`], the widget can trigger one or more
+    /// events.
+    ///
+    /// By default a widget does nothing on tick.
+    #[inline]
+    #[allow(unused)]
+    fn tick(&mut self, delta: f32) -> Vec<event::Widget> {
         vec![]
     }
 
+    /// The shape used to check if the pointer is over the widget.
+    ///
+    /// The shape is positioned at the top-left of the widget. Only its
+    /// dimensions are used, not its colors.
+    ///
+    /// By default this is a rectangle matching the widget's dimensions.
+    #[inline]
+    fn bounds_shape(&self) -> Shape {
+        let (width, height) = self.dimensions();
+
+        Shape::Rectangle {
+            width,
+            height,
+            color: Color::default(),
+            rotation: 0.0,
+            border: None,
+        }
+    }
+
+    /// A human-readable label describing the widget, used by assistive
+    /// technologies.
+    ///
+    /// By default a widget has no label.
+    #[inline]
+    fn accessibility_label(&self) -> Option<String> {
+        None
+    }
+
+    /// The semantic role of the widget.
+    ///
+    /// By default a widget has the [`Role::Generic`] role.
+    #[inline]
+    fn role(&self) -> Role {
+        Role::default()
+    }
+
     /// Check if the given (relative) coordinates fall within the bounds of the
     /// widget. (0, 0) is the top-left of the widget.
     ///
@@ -182,3 +636,168 @@ pub trait Runtime {
         true
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    mod kind {
+        use super::*;
+
+        #[test]
+        fn from_str() {
+            assert_eq!("MovingCircle".parse(), Ok(Kind::MovingCircle));
+            assert_eq!("moving_circle".parse(), Ok(Kind::MovingCircle));
+            assert_eq!("ButtonRectangle".parse(), Ok(Kind::ButtonRectangle));
+            assert_eq!("button_rectangle".parse(), Ok(Kind::ButtonRectangle));
+            assert_eq!("Slider".parse(), Ok(Kind::Slider));
+            assert_eq!("slider".parse(), Ok(Kind::Slider));
+            assert_eq!("Checkbox".parse(), Ok(Kind::Checkbox));
+            assert_eq!("checkbox".parse(), Ok(Kind::Checkbox));
+            assert_eq!("ProgressBar".parse(), Ok(Kind::ProgressBar));
+            assert_eq!("progress_bar".parse(), Ok(Kind::ProgressBar));
+        }
+
+        #[test]
+        fn unknown() {
+            let err = "Triangle".parse::<Kind>().unwrap_err();
+
+            assert_eq!(err, UnknownKind("Triangle".to_owned()));
+            assert_eq!(err.to_string(), "unknown widget kind `Triangle`");
+        }
+
+        #[test]
+        fn builder() {
+            let (name, widget) = Builder::from_kind_str("circle", "moving_circle")
+                .unwrap()
+                .attribute("radius", 10.0)
+                .build();
+
+            assert_eq!(name, "circle");
+            assert_eq!(widget.state().kind(), &Kind::MovingCircle);
+            assert!(Builder::from_kind_str("circle", "circle").is_err());
+        }
+    }
+
+    mod build {
+        use super::*;
+
+        #[test]
+        fn default_attributes() {
+            let (_, circle) = Builder::new("circle", Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .build();
+
+            let state = circle.state();
+            assert_eq!(state.get_as::<f32>("radius"), Some(10.0));
+            assert_eq!(state.get_as::<Color>("fill_color"), Some(Color::default()));
+            assert_eq!(
+                state.get_as::<Color>("border_color"),
+                Some(Color::default())
+            );
+            assert_eq!(state.get_as::<f32>("border_width"), Some(0.0));
+        }
+
+        #[test]
+        fn configured_attributes() {
+            let red = Color::from_rgb(255, 0, 0);
+            let (_, circle) = Builder::new("circle", Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .attribute("border_color", red)
+                .build();
+
+            assert_eq!(circle.state().get_as::<Color>("border_color"), Some(red));
+        }
+
+        #[test]
+        fn z_index() {
+            let builder = || Builder::new("circle", Kind::MovingCircle).attribute("radius", 10.0);
+
+            assert_eq!(builder().build().1.z_index(), 0);
+            assert_eq!(builder().z_index(3).build().1.z_index(), 3);
+        }
+
+        #[test]
+        fn layer() {
+            let builder = || Builder::new("circle", Kind::MovingCircle).attribute("radius", 10.0);
+
+            assert_eq!(builder().build().1.layer(), Layer::World);
+            assert_eq!(builder().layer(Layer::Ui).build().1.layer(), Layer::Ui);
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn valid() {
+            let builder = Builder::new("circle", Kind::MovingCircle)
+                .attribute("radius", 10)
+                .attribute("fill_color", Color::default());
+
+            assert_eq!(builder.validate(), Ok(()));
+        }
+
+        #[test]
+        fn malformed() {
+            let errors = Builder::new("circle", Kind::MovingCircle)
+                .attribute("radius", "10")
+                .validate()
+                .unwrap_err();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].attribute, "radius");
+            assert!(matches!(errors[0].reason, ErrorReason::Malformed(_)));
+        }
+
+        #[test]
+        fn missing_and_unknown() {
+            let errors = Builder::new("button", Kind::ButtonRectangle)
+                .attribute("width", 10.0)
+                .attribute("height", 10.0)
+                .attribute("idle_colour", Color::default())
+                .validate()
+                .unwrap_err();
+
+            assert_eq!(
+                errors,
+                vec![
+                    Error::new(Kind::ButtonRectangle, "idle_color", ErrorReason::Missing),
+                    Error::new(Kind::ButtonRectangle, "idle_colour", ErrorReason::Unknown),
+                ]
+            );
+            assert_eq!(
+                errors[1].to_string(),
+                "unknown `idle_colour` attribute of ButtonRectangle widget"
+            );
+        }
+    }
+
+    mod runtime {
+        use super::*;
+
+        #[test]
+        fn role() {
+            let (_, circle) = Builder::new("circle", Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .build();
+
+            let circle = MovingCircle::try_from(circle.state()).unwrap();
+            assert_eq!(circle.role(), Role::Graphic);
+            assert_eq!(circle.accessibility_label(), None);
+
+            let (_, button) = Builder::new("button", Kind::ButtonRectangle)
+                .attribute("width", 10.0)
+                .attribute("height", 10.0)
+                .attribute("idle_color", Color::default())
+                .attribute("label", "Start game")
+                .build();
+
+            let button = ButtonRectangle::try_from(button.state()).unwrap();
+            assert_eq!(button.role(), Role::Button);
+            assert_eq!(button.accessibility_label(), Some("Start game".to_owned()));
+        }
+    }
+}
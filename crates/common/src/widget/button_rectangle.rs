@@ -1,14 +1,26 @@
 //! A rectangular button.
 
 use crate::{
-    event, widget, Color, Component, Deserialize, Event, Serialize, Shape, Value, WidgetState,
+    event, widget, Border, Color, Component, Deserialize, Event, Key, Serialize, Shape, Value,
+    WidgetState,
 };
 use std::{collections::HashMap, convert::TryFrom};
 
+/// The font size of the label, relative to the height of the button.
+const LABEL_SIZE: f32 = 0.5;
+
+/// The (estimated) average width of a character of the label, relative to its
+/// font size.
+///
+/// Widgets have no access to the font metrics, so this is used to
+/// approximately center the label on the button.
+const LABEL_CHARACTER_WIDTH: f32 = 0.55;
+
 /// A rectangular button.
 ///
-/// This widget renders a button with hover/active states, a button text, and
-/// emits the "activated" event when the button is clicked.
+/// This widget renders a button with hover/active states, a label, and
+/// emits the "activated" event when the button is clicked, or when the `Enter`
+/// or `Space` key is pressed while the button has focus.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ButtonRectangle {
     /// The width of the button.
@@ -26,6 +38,23 @@ pub struct ButtonRectangle {
     /// The color of the button in active state.
     active_color: Color,
 
+    /// The caption rendered on top of the button, also used as its
+    /// accessibility label. An empty label is not rendered.
+    label: String,
+
+    /// The color of the label.
+    ///
+    /// Defaults to black or white, whichever is most readable on top of the
+    /// idle color of the button.
+    label_color: Color,
+
+    /// The (optional) border of the button, drawn inside its dimensions.
+    border: Option<Border>,
+
+    /// Whether or not the button has focus, either because the pointer is
+    /// hovering over it, or because it was focused using the keyboard.
+    focus: bool,
+
     /// The state of the button.
     state: ButtonState,
 }
@@ -38,22 +67,54 @@ enum ButtonState {
     Active,
 }
 
+impl ButtonRectangle {
+    /// The attributes used when they aren't configured for the widget.
+    ///
+    /// The dimensions and idle color of a button have no sensible default, and
+    /// the focus and active colors fall back to the (configured) idle color.
+    pub(super) const fn default_attributes() -> Vec<(&'static str, Value)> {
+        vec![]
+    }
+
+    /// The attributes the widget supports.
+    pub(super) fn attributes() -> Vec<widget::Attribute> {
+        use widget::Attribute;
+
+        vec![
+            Attribute::required::<f32>("width"),
+            Attribute::required::<f32>("height"),
+            Attribute::required::<Color>("idle_color"),
+            Attribute::optional::<Color>("focus_color"),
+            Attribute::optional::<Color>("active_color"),
+            Attribute::optional::<String>("label"),
+            Attribute::optional::<Color>("label_color"),
+            Attribute::optional::<Border>("border"),
+            Attribute::optional::<bool>("focus"),
+            Attribute::optional::<ButtonState>("state"),
+        ]
+    }
+}
+
 impl TryFrom<&WidgetState> for ButtonRectangle {
-    type Error = String;
+    type Error = widget::Error;
 
     #[inline]
     #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
     fn try_from(state: &WidgetState) -> Result<Self, Self::Error> {
-        let width = state.get_as("width").ok_or("missing `width` attribute")?;
-        let height = state.get_as("height").ok_or("missing `height` attribute")?;
+        let width = widget::required(state, "width")?;
+        let height = widget::required(state, "height")?;
 
-        let idle_color = state
-            .get_as("idle_color")
-            .ok_or("missing `idle_color` attribute")?;
+        let idle_color = widget::required(state, "idle_color")?;
 
         let focus_color = state.get_as("focus_color").unwrap_or(idle_color);
         let active_color = state.get_as("active_color").unwrap_or(idle_color);
 
+        let label = state.get_as("label").unwrap_or_default();
+        let label_color = state
+            .get_as("label_color")
+            .unwrap_or_else(|| idle_color.contrasting_text());
+        let border = state.get_as("border");
+        let focus = state.get_as("focus").unwrap_or(false);
         let state = state.get_as("state").unwrap_or(ButtonState::Idle);
 
         Ok(Self {
@@ -62,6 +123,10 @@ impl TryFrom<&WidgetState> for ButtonRectangle {
             idle_color,
             focus_color,
             active_color,
+            label,
+            label_color,
+            border,
+            focus,
             state,
         })
     }
@@ -76,6 +141,9 @@ impl widget::Runtime for ButtonRectangle {
             "idle_color" => Some(self.idle_color.into()),
             "focus_color" => Some(self.focus_color.into()),
             "active_color" => Some(self.active_color.into()),
+            "label" => Some(self.label.as_str().into()),
+            "label_color" => Some(self.label_color.into()),
+            "border" => self.border.map(Into::into),
             _ => None,
         }
     }
@@ -86,11 +154,15 @@ impl widget::Runtime for ButtonRectangle {
         match key {
             "width" => match attribute_cb(self.width, cb).as_f64() {
                 Some(width) => self.width = width as f32,
-                None => todo!("logging"),
+                None => log::warn!("ignoring invalid `width` value"),
             },
             "height" => match attribute_cb(self.height, cb).as_f64() {
                 Some(height) => self.height = height as f32,
-                None => todo!("logging"),
+                None => log::warn!("ignoring invalid `height` value"),
+            },
+            "label" => match attribute_cb(self.label.as_str(), cb).as_str() {
+                Some(label) => self.label = label.to_owned(),
+                None => log::warn!("ignoring invalid `label` value"),
             },
             _ => cb(None),
         }
@@ -101,6 +173,27 @@ impl widget::Runtime for ButtonRectangle {
         (self.width, self.height)
     }
 
+    #[inline]
+    fn bounds_shape(&self) -> Shape {
+        Shape::Rectangle {
+            width: self.width,
+            height: self.height,
+            color: self.idle_color,
+            rotation: 0.0,
+            border: None,
+        }
+    }
+
+    #[inline]
+    fn accessibility_label(&self) -> Option<String> {
+        Some(self.label.clone()).filter(|label| !label.is_empty())
+    }
+
+    #[inline]
+    fn role(&self) -> widget::Role {
+        widget::Role::Button
+    }
+
     #[inline]
     fn state(&self) -> WidgetState {
         let mut state = HashMap::new();
@@ -109,6 +202,13 @@ impl widget::Runtime for ButtonRectangle {
         state.insert("idle_color", self.idle_color.into());
         state.insert("focus_color", self.focus_color.into());
         state.insert("active_color", self.active_color.into());
+        state.insert("label", self.label.as_str().into());
+        state.insert("label_color", self.label_color.into());
+        state.insert("focus", self.focus.into());
+
+        if let Some(border) = self.border {
+            state.insert("border", border.into());
+        }
 
         if let Ok(value) = serde_json::to_value(self.state) {
             state.insert("state", value);
@@ -118,12 +218,18 @@ impl widget::Runtime for ButtonRectangle {
     }
 
     #[inline]
-    fn interact(&mut self, event: &Event) -> Vec<event::Widget> {
+    fn interact(&mut self, event: &Event, _: f32) -> Vec<event::Widget> {
         let mut output = vec![];
 
         match event {
-            Event::Input(event::Input::Focus) => self.state = ButtonState::Focus,
-            Event::Input(event::Input::Blur) => self.state = ButtonState::Idle,
+            Event::Input(event::Input::Focus) => {
+                self.focus = true;
+                self.state = ButtonState::Focus;
+            }
+            Event::Input(event::Input::Blur) => {
+                self.focus = false;
+                self.state = ButtonState::Idle;
+            }
             Event::Input(event::Input::MousePress { button, .. })
                 if button == &event::MouseButton::Left =>
             {
@@ -135,6 +241,26 @@ impl widget::Runtime for ButtonRectangle {
                 self.state = ButtonState::Active;
                 output.push(event::Widget::new("activated"));
             }
+            // The button is released when the pointer was dragged outside of
+            // its bounds while pressed.
+            Event::Input(event::Input::MouseRelease { button, x, y })
+                if button == &event::MouseButton::Left =>
+            {
+                let inside = (0.0..=self.width).contains(x) && (0.0..=self.height).contains(y);
+
+                self.state = if inside {
+                    ButtonState::Focus
+                } else {
+                    ButtonState::Idle
+                };
+            }
+            // Only pressing the key activates the button, holding it down
+            // doesn't activate it again.
+            Event::Input(event::Input::KeyDown(key))
+                if self.focus && (key == &Key::Enter || key == &Key::Space) =>
+            {
+                output.push(event::Widget::new("activated"));
+            }
             _ if self.state == ButtonState::Active => self.state = ButtonState::Focus,
             _ => {}
         }
@@ -154,14 +280,29 @@ impl widget::Runtime for ButtonRectangle {
             width: self.width,
             height: self.height,
             color,
+            rotation: 0.0,
+            border: self.border,
         };
 
-        let component = Component {
-            shape,
-            coordinates: (0.0, 0.0),
-        };
+        let mut components = vec![Component::new(shape, (0.0, 0.0))];
 
-        vec![component]
+        if !self.label.is_empty() {
+            let size = self.height * LABEL_SIZE;
+
+            #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+            let width = self.label.chars().count() as f32 * size * LABEL_CHARACTER_WIDTH;
+
+            let shape = Shape::Text {
+                content: self.label.clone(),
+                size,
+                color: self.label_color,
+            };
+
+            let coordinates = ((self.width - width) / 2.0, (self.height - size) / 2.0);
+            components.push(Component::new(shape, coordinates));
+        }
+
+        components
     }
 }
 
@@ -172,3 +313,244 @@ fn attribute_cb(attribute: impl Into<Value>, cb: fn(value: Option<&mut Value>))
 
     value
 }
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::widget::Runtime;
+
+    mod try_from {
+        use super::*;
+
+        #[test]
+        fn missing_idle_color() {
+            let mut state = HashMap::new();
+            state.insert("width", 10.0.into());
+            state.insert("height", 10.0.into());
+
+            let state = WidgetState::new(widget::Kind::ButtonRectangle, state);
+
+            assert_eq!(
+                ButtonRectangle::try_from(&state).unwrap_err(),
+                widget::Error::new(
+                    widget::Kind::ButtonRectangle,
+                    "idle_color",
+                    widget::ErrorReason::Missing
+                )
+            );
+        }
+
+        #[test]
+        fn malformed_width() {
+            let mut state = HashMap::new();
+            state.insert("width", "wide".into());
+
+            let state = WidgetState::new(widget::Kind::ButtonRectangle, state);
+            let err = ButtonRectangle::try_from(&state).unwrap_err();
+
+            assert_eq!(err.attribute, "width");
+            assert!(matches!(err.reason, widget::ErrorReason::Malformed(_)));
+        }
+    }
+
+    mod label {
+        use super::*;
+
+        fn button(label: Option<&str>) -> ButtonRectangle {
+            let mut state = HashMap::new();
+            state.insert("width", 100.0.into());
+            state.insert("height", 20.0.into());
+            state.insert("idle_color", Color::default().into());
+
+            if let Some(label) = label {
+                state.insert("label", label.into());
+            }
+
+            let state = WidgetState::new(widget::Kind::ButtonRectangle, state);
+            ButtonRectangle::try_from(&state).unwrap()
+        }
+
+        #[test]
+        fn missing() {
+            let button = button(None);
+
+            assert_eq!(button.label, "");
+            assert_eq!(button.render().len(), 1);
+            assert_eq!(button.accessibility_label(), None);
+        }
+
+        #[test]
+        fn rendered_centered() {
+            let button = button(Some("Play"));
+            let components = button.render();
+
+            assert_eq!(components.len(), 2);
+            assert_eq!(
+                components[1].shape,
+                Shape::Text {
+                    content: "Play".to_owned(),
+                    size: 10.0,
+                    color: Color::new(0.0, 0.0, 0.0, 1.0),
+                }
+            );
+
+            // Four characters of (roughly) 5.5 pixels wide.
+            assert_eq!(components[1].coordinates, (39.0, 5.0));
+            assert_eq!(button.accessibility_label(), Some("Play".to_owned()));
+        }
+
+        #[test]
+        fn state_round_trip() {
+            let state = button(Some("Quit")).state();
+
+            assert_eq!(state.get_as::<String>("label"), Some("Quit".to_owned()));
+            assert_eq!(state.get_as::<String>("text"), None);
+        }
+    }
+
+    mod border {
+        use super::*;
+
+        fn button(border: Option<Border>) -> ButtonRectangle {
+            let mut state = HashMap::new();
+            state.insert("width", 10.0.into());
+            state.insert("height", 10.0.into());
+            state.insert("idle_color", Color::default().into());
+
+            if let Some(border) = border {
+                state.insert("border", border.into());
+            }
+
+            let state = WidgetState::new(widget::Kind::ButtonRectangle, state);
+            ButtonRectangle::try_from(&state).unwrap()
+        }
+
+        #[test]
+        fn rendered() {
+            let border = Border {
+                color: Color::new(0.0, 0.0, 0.0, 1.0),
+                width: 2.0,
+                glow: None,
+            };
+
+            let button = button(Some(border));
+
+            match button.render()[0].shape {
+                Shape::Rectangle { border: actual, .. } => assert_eq!(actual, Some(border)),
+                _ => panic!("expected a rectangle"),
+            }
+
+            assert_eq!(button.state().get_as("border"), Some(border));
+        }
+
+        #[test]
+        fn missing() {
+            let button = button(None);
+
+            assert_eq!(button.border, None);
+            assert_eq!(button.attribute("border"), None);
+        }
+    }
+
+    mod interact {
+        use super::*;
+
+        fn button() -> ButtonRectangle {
+            let mut state = HashMap::new();
+            state.insert("width", 10.0.into());
+            state.insert("height", 10.0.into());
+            state.insert("idle_color", Color::default().into());
+
+            let state = WidgetState::new(widget::Kind::ButtonRectangle, state);
+            ButtonRectangle::try_from(&state).unwrap()
+        }
+
+        fn keyboard(key: Key) -> Event {
+            let keys = vec![key].into_iter().collect();
+            Event::Input(event::Input::Keyboard { keys })
+        }
+
+        fn key_down(key: Key) -> Event {
+            Event::Input(event::Input::KeyDown(key))
+        }
+
+        #[test]
+        fn enter_activates_focused_button() {
+            let mut button = button();
+            button.interact(&Event::Input(event::Input::Focus), 0.01);
+
+            let events = button.interact(&key_down(Key::Enter), 0.01);
+            assert_eq!(events, vec![event::Widget::new("activated")]);
+            assert_eq!(button.state, ButtonState::Focus);
+
+            // holding down the key does not activate the button again
+            let events = button.interact(&keyboard(Key::Enter), 0.01);
+            assert!(events.is_empty());
+
+            let events = button.interact(&key_down(Key::Enter), 0.01);
+            assert_eq!(events, vec![event::Widget::new("activated")]);
+        }
+
+        #[test]
+        fn space_activates_focused_button() {
+            let mut button = button();
+            button.interact(&Event::Input(event::Input::Focus), 0.01);
+
+            let events = button.interact(&key_down(Key::Space), 0.01);
+            assert_eq!(events, vec![event::Widget::new("activated")]);
+        }
+
+        #[test]
+        fn unfocused_button() {
+            let mut button = button();
+
+            let events = button.interact(&key_down(Key::Enter), 0.01);
+            assert!(events.is_empty());
+
+            button.interact(&Event::Input(event::Input::Focus), 0.01);
+            button.interact(&Event::Input(event::Input::Blur), 0.01);
+
+            let events = button.interact(&key_down(Key::Enter), 0.01);
+            assert!(events.is_empty());
+        }
+
+        #[test]
+        fn focus_round_trip() {
+            let mut button = button();
+            button.interact(&Event::Input(event::Input::Focus), 0.01);
+
+            let button = ButtonRectangle::try_from(&button.state()).unwrap();
+            assert!(button.focus);
+        }
+
+        #[test]
+        fn press_then_release() {
+            let mouse = |press: bool, x: f32| {
+                let button = event::MouseButton::Left;
+                let input = if press {
+                    event::Input::MousePress { button, x, y: 5.0 }
+                } else {
+                    event::Input::MouseRelease { button, x, y: 5.0 }
+                };
+
+                Event::Input(input)
+            };
+
+            let mut button = button();
+            button.interact(&Event::Input(event::Input::Focus), 0.01);
+
+            button.interact(&mouse(true, 5.0), 0.01);
+            assert_eq!(button.state, ButtonState::Active);
+
+            let events = button.interact(&mouse(false, 5.0), 0.01);
+            assert!(events.is_empty());
+            assert_eq!(button.state, ButtonState::Focus);
+
+            // released after dragging the pointer outside of the button
+            button.interact(&mouse(true, 5.0), 0.01);
+            button.interact(&mouse(false, 20.0), 0.01);
+            assert_eq!(button.state, ButtonState::Idle);
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! A toggleable checkbox.
+
+use crate::{
+    event, widget, Border, Color, Component, Deserialize, Event, Serialize, Shape, Value,
+    WidgetState,
+};
+use std::{collections::HashMap, convert::TryFrom};
+
+/// The width of the border of the box, relative to the size of the checkbox.
+const BORDER_WIDTH: f32 = 0.1;
+
+/// The distance between the edge of the box and its inner fill when checked,
+/// relative to the size of the checkbox.
+const FILL_INSET: f32 = 0.25;
+
+/// A toggleable checkbox.
+///
+/// This widget renders a square box, with an inner fill when it is checked.
+/// Clicking the checkbox flips its checked state, and emits the "toggled" event
+/// with a `checked` attribute containing the new state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkbox {
+    /// The width and height of the checkbox.
+    size: f32,
+
+    /// Whether or not the checkbox is checked.
+    checked: bool,
+
+    /// The color of the box.
+    unchecked_color: Color,
+
+    /// The color of the border of the box, and of its inner fill when checked.
+    checked_color: Color,
+}
+
+impl Checkbox {
+    /// The attributes used when they aren't configured for the widget.
+    ///
+    /// The size of a checkbox has no sensible default.
+    pub(super) fn default_attributes() -> Vec<(&'static str, Value)> {
+        vec![
+            ("checked", false.into()),
+            ("unchecked_color", Color::default().into()),
+            ("checked_color", Color::new(0.0, 0.0, 0.0, 1.0).into()),
+        ]
+    }
+
+    /// The attributes the widget supports.
+    pub(super) fn attributes() -> Vec<widget::Attribute> {
+        use widget::Attribute;
+
+        vec![
+            Attribute::required::<f32>("size"),
+            Attribute::optional::<bool>("checked"),
+            Attribute::optional::<Color>("unchecked_color"),
+            Attribute::optional::<Color>("checked_color"),
+        ]
+    }
+}
+
+impl TryFrom<&WidgetState> for Checkbox {
+    type Error = widget::Error;
+
+    #[inline]
+    fn try_from(state: &WidgetState) -> Result<Self, Self::Error> {
+        let size = widget::required(state, "size")?;
+        let checked = state
+            .get("checked")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let unchecked_color = state.get_as("unchecked_color").unwrap_or_default();
+        let checked_color = state
+            .get_as("checked_color")
+            .unwrap_or_else(|| Color::new(0.0, 0.0, 0.0, 1.0));
+
+        Ok(Self {
+            size,
+            checked,
+            unchecked_color,
+            checked_color,
+        })
+    }
+}
+
+impl widget::Runtime for Checkbox {
+    #[inline]
+    fn attribute(&self, key: &str) -> Option<Value> {
+        match key {
+            "size" => Some(self.size.into()),
+            "checked" => Some(self.checked.into()),
+            "unchecked_color" => Some(self.unchecked_color.into()),
+            "checked_color" => Some(self.checked_color.into()),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn attribute_mut(&mut self, key: &str, cb: fn(value: Option<&mut Value>)) {
+        match key {
+            "checked" => {
+                let mut value = Value::from(self.checked);
+                cb(Some(&mut value));
+
+                match value.as_bool() {
+                    Some(checked) => self.checked = checked,
+                    None => log::warn!("ignoring invalid `checked` value: {}", value),
+                }
+            }
+            _ => cb(None),
+        }
+    }
+
+    #[inline]
+    fn dimensions(&self) -> (f32, f32) {
+        (self.size, self.size)
+    }
+
+    #[inline]
+    fn role(&self) -> widget::Role {
+        widget::Role::Checkbox
+    }
+
+    #[inline]
+    fn state(&self) -> WidgetState {
+        let mut state = HashMap::with_capacity(4);
+        state.insert("size", self.size.into());
+        state.insert("checked", self.checked.into());
+        state.insert("unchecked_color", self.unchecked_color.into());
+        state.insert("checked_color", self.checked_color.into());
+
+        WidgetState::new(widget::Kind::Checkbox, state)
+    }
+
+    #[inline]
+    fn interact(&mut self, event: &Event, _: f32) -> Vec<event::Widget> {
+        match event {
+            Event::Input(event::Input::MouseClick { button, .. })
+                if button == &event::MouseButton::Left =>
+            {
+                self.checked = !self.checked;
+
+                let mut event = event::Widget::new("toggled");
+                event.add_attribute("checked", self.checked);
+
+                vec![event]
+            }
+            _ => vec![],
+        }
+    }
+
+    #[inline]
+    fn render(&self) -> Vec<Component> {
+        let shape = Shape::Rectangle {
+            width: self.size,
+            height: self.size,
+            color: self.unchecked_color,
+            rotation: 0.0,
+            border: Some(Border {
+                color: self.checked_color,
+                width: self.size * BORDER_WIDTH,
+                glow: None,
+            }),
+        };
+
+        let mut components = vec![Component::new(shape, (0.0, 0.0))];
+
+        if self.checked {
+            let inset = self.size * FILL_INSET;
+            let shape = Shape::Rectangle {
+                width: self.size - inset * 2.0,
+                height: self.size - inset * 2.0,
+                color: self.checked_color,
+                rotation: 0.0,
+                border: None,
+            };
+
+            components.push(Component::new(shape, (inset, inset)));
+        }
+
+        components
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::widget::Runtime;
+
+    fn checkbox(checked: bool) -> Checkbox {
+        let mut state = HashMap::new();
+        state.insert("size", 20.0.into());
+        state.insert("checked", checked.into());
+
+        let state = WidgetState::new(widget::Kind::Checkbox, state);
+        Checkbox::try_from(&state).unwrap()
+    }
+
+    fn click(button: event::MouseButton) -> Event {
+        Event::Input(event::Input::MouseClick {
+            button,
+            x: 10.0,
+            y: 10.0,
+        })
+    }
+
+    mod interact {
+        use super::*;
+
+        #[test]
+        fn click_toggles() {
+            let mut checkbox = checkbox(false);
+
+            let mut expected = event::Widget::new("toggled");
+            expected.add_attribute("checked", true);
+
+            let events = checkbox.interact(&click(event::MouseButton::Left), 0.01);
+            assert_eq!(events, vec![expected]);
+            assert!(checkbox.checked);
+
+            let mut expected = event::Widget::new("toggled");
+            expected.add_attribute("checked", false);
+
+            let events = checkbox.interact(&click(event::MouseButton::Left), 0.01);
+            assert_eq!(events, vec![expected]);
+            assert!(!checkbox.checked);
+        }
+
+        #[test]
+        fn right_click() {
+            let mut checkbox = checkbox(false);
+
+            let events = checkbox.interact(&click(event::MouseButton::Right), 0.01);
+            assert!(events.is_empty());
+            assert!(!checkbox.checked);
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn unchecked() {
+            assert_eq!(checkbox(false).render().len(), 1);
+        }
+
+        #[test]
+        fn checked() {
+            let components = checkbox(true).render();
+
+            assert_eq!(components.len(), 2);
+            assert_eq!(components[1].coordinates, (5.0, 5.0));
+            assert_eq!(
+                components[1].shape,
+                Shape::Rectangle {
+                    width: 10.0,
+                    height: 10.0,
+                    color: Color::new(0.0, 0.0, 0.0, 1.0),
+                    rotation: 0.0,
+                    border: None,
+                }
+            );
+        }
+    }
+
+    mod state {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let checkbox = checkbox(true);
+            let state = checkbox.state();
+
+            assert_eq!(state.kind(), &widget::Kind::Checkbox);
+            assert_eq!(Checkbox::try_from(&state), Ok(checkbox));
+        }
+    }
+}
@@ -9,10 +9,25 @@ use std::{
     convert::TryFrom,
 };
 
+/// The amount by which a color component shifts per second, while its key is
+/// held down.
+const COLOR_SHIFT_PER_SECOND: f32 = 1.0;
+
+/// The speed (in pixels per second) below which a circle with inertia comes
+/// to a halt.
+const MIN_SPEED: f32 = 0.1;
+
+/// The distance a gamepad stick has to be pushed from its center, before it
+/// moves the circle.
+const STICK_DEAD_ZONE: f32 = 0.2;
+
 /// An example widget of an interactive circle.
 ///
 /// - The circle triggers the "move" event whenever the `WASD` keys are used by
-///   the player.
+///   the player. The event carries the direction to move in as an `x` and `y`
+///   vector, so that combined keys (e.g. `W` and `D`) move the circle
+///   diagonally. For backward compatibility, movement along a single axis also
+///   carries the legacy `direction` attribute (e.g. `Up`).
 ///
 /// - The `Shift` and `Ctrl` modifier keys modify the "move" event to add
 ///   details about the requested movement speed.
@@ -23,6 +38,18 @@ use std::{
 /// - The `R`, `G` and `B` keys modify the circle's color.
 ///
 /// - The `-` and `+` keys modify the circle's opacity.
+///
+/// If the `acceleration` attribute is set to a positive value, the circle has
+/// inertia: the `WASD` keys accelerate the circle instead, up to `max_speed`
+/// (in pixels per second), and `friction` slows it down again over time. The
+/// circle triggers the "move_by" event on each tick it moves.
+///
+/// The left stick of a gamepad moves the circle in the same way as the
+/// movement keys, for as long as the stick is pushed.
+///
+/// Dragging the circle with the left mouse button triggers the "drag" event
+/// whenever the pointer moves, with the distance by which to move the circle
+/// to keep it at the same position under the pointer.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct MovingCircle {
     /// The radius of the circle.
@@ -43,6 +70,26 @@ pub struct MovingCircle {
 
     /// Tracking if the circle has focus or not.
     focus: bool,
+
+    /// The acceleration (in pixels per second, per second) applied while a
+    /// movement key is held down. If set to `0.0`, the circle has no inertia.
+    acceleration: f32,
+
+    /// The maximum speed (in pixels per second) of a circle with inertia.
+    max_speed: f32,
+
+    /// The fraction of its velocity a circle with inertia loses per second.
+    friction: f32,
+
+    /// The current velocity (in pixels per second) of a circle with inertia.
+    velocity: (f32, f32),
+
+    /// The position (relative to the circle) at which the circle is held,
+    /// while it is being dragged.
+    drag_offset: Option<(f32, f32)>,
+
+    /// The last known position of the left stick of a gamepad.
+    stick: (f32, f32),
 }
 
 /// Direction of color shifting for each color.
@@ -68,9 +115,12 @@ impl Default for ShiftMode {
     }
 }
 
-/// The direction in which the widget wants to be moved by its owner, based on
-/// the incoming key events.
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// The direction in which the widget wants to be moved by its owner, for
+/// movement along a single axis.
+///
+/// This is superseded by the `x` and `y` vector of the "move" event, but kept
+/// for plugins that only handle the legacy `direction` attribute.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[allow(clippy::missing_docs_in_private_items)]
 enum Direction {
     Up,
@@ -79,6 +129,21 @@ enum Direction {
     Right,
 }
 
+impl Direction {
+    /// The direction matching the given movement vector, if it points along
+    /// a single axis.
+    fn from_vector((x, y): (f32, f32)) -> Option<Self> {
+        match () {
+            _ if x.abs() > f32::EPSILON && y.abs() > f32::EPSILON => None,
+            _ if y < 0.0 => Some(Self::Up),
+            _ if y > 0.0 => Some(Self::Down),
+            _ if x < 0.0 => Some(Self::Left),
+            _ if x > 0.0 => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
 /// The speed at which the widget wants to be moved by its owner, based on the
 /// incoming key events.
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
@@ -90,6 +155,111 @@ enum Speed {
 }
 
 impl MovingCircle {
+    /// The attributes used when they aren't configured for the widget.
+    pub(super) fn default_attributes() -> Vec<(&'static str, Value)> {
+        vec![
+            ("fill_color", Color::default().into()),
+            ("border_color", Color::default().into()),
+            ("border_width", 0.0.into()),
+            ("acceleration", 0.0.into()),
+            ("max_speed", 200.0.into()),
+            ("friction", 2.0.into()),
+        ]
+    }
+
+    /// The attributes the widget supports.
+    pub(super) fn attributes() -> Vec<widget::Attribute> {
+        use widget::Attribute;
+
+        vec![
+            Attribute::required::<f32>("radius"),
+            Attribute::optional::<Color>("fill_color"),
+            Attribute::optional::<Color>("border_color"),
+            Attribute::optional::<f32>("border_width"),
+            Attribute::optional::<ColorShift>("color_shift"),
+            Attribute::optional::<bool>("focus"),
+            Attribute::optional::<f32>("acceleration"),
+            Attribute::optional::<f32>("max_speed"),
+            Attribute::optional::<f32>("friction"),
+            Attribute::optional::<(f32, f32)>("velocity"),
+            Attribute::optional::<Option<(f32, f32)>>("drag_offset"),
+            Attribute::optional::<(f32, f32)>("stick"),
+        ]
+    }
+
+    /// Whether or not the circle moves using acceleration and velocity.
+    fn has_inertia(&self) -> bool {
+        self.acceleration > 0.0
+    }
+
+    /// Accelerate the circle in the given direction, limiting its velocity to
+    /// the maximum speed.
+    ///
+    /// The `delta` is the time (in seconds) since the last update.
+    fn accelerate(&mut self, delta: f32, (x, y): (f32, f32)) {
+        let step = self.acceleration * delta;
+        let (vx, vy) = (self.velocity.0 + x * step, self.velocity.1 + y * step);
+        let speed = vx.hypot(vy);
+
+        self.velocity = if speed > self.max_speed {
+            let scale = self.max_speed / speed;
+            (vx * scale, vy * scale)
+        } else {
+            (vx, vy)
+        };
+    }
+
+    /// Move the circle in the direction of the given movement keys.
+    ///
+    /// With inertia the circle accelerates, otherwise the "move" event is
+    /// returned.
+    fn steer(&mut self, delta: f32, keys: &HashSet<Key>) -> Option<event::Widget> {
+        if !self.has_inertia() {
+            return move_event(keys);
+        }
+
+        self.accelerate(delta, movement_direction(keys)?);
+        None
+    }
+
+    /// The movement keys matching the direction in which the left stick of a
+    /// gamepad is pushed.
+    fn stick_keys(&self) -> HashSet<Key> {
+        let (x, y) = self.stick;
+        let mut keys = HashSet::new();
+
+        if x > STICK_DEAD_ZONE {
+            keys.insert(Key::Right);
+        } else if x < -STICK_DEAD_ZONE {
+            keys.insert(Key::Left);
+        }
+
+        // A stick pushed up has a positive Y value.
+        if y > STICK_DEAD_ZONE {
+            keys.insert(Key::Up);
+        } else if y < -STICK_DEAD_ZONE {
+            keys.insert(Key::Down);
+        }
+
+        keys
+    }
+
+    /// Follow the pointer at the given (relative) position during a drag.
+    ///
+    /// Returns the "drag" event, if the pointer moved away from where it
+    /// holds the circle.
+    #[allow(clippy::float_cmp)]
+    fn follow_pointer(&self, x: f32, y: f32) -> Option<event::Widget> {
+        let (x_offset, y_offset) = self.drag_offset?;
+        let (dx, dy) = (x - x_offset, y - y_offset);
+
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+
+        Some(drag_event(dx, dy))
+    }
+
     /// Resize the circle based on the provided key.
     fn resize(&mut self, step: f32, key: Key) -> Option<event::Widget> {
         #[allow(clippy::wildcard_enum_match_arm)]
@@ -114,7 +284,9 @@ impl MovingCircle {
     }
 
     /// Shift the circle color based on the provided key.
-    fn shift_color(&mut self, mut step: f32, key: Key) -> Option<event::Widget> {
+    ///
+    /// The `delta` is the time (in seconds) since the last update.
+    fn shift_color(&mut self, delta: f32, key: Key) -> Option<event::Widget> {
         #[allow(clippy::wildcard_enum_match_arm)]
         let (shift_mode, color) = match key {
             Key::R => (&mut self.color_shift.r, &mut self.fill_color.r),
@@ -123,6 +295,8 @@ impl MovingCircle {
             _ => return None,
         };
 
+        let mut step = COLOR_SHIFT_PER_SECOND * delta;
+
         // Depending on the "up" toggle, we move up or down the color spectrum.
         if *shift_mode == ShiftMode::Down {
             step *= -1.0;
@@ -184,27 +358,42 @@ impl widget::Runtime for MovingCircle {
 
                 match value.as_f64() {
                     Some(v) => self.radius = v as f32,
-                    None => todo!("logging"),
+                    None => log::warn!("ignoring invalid `radius` value: {}", value),
                 }
             }
             "fill_color" => {
                 let mut value = serde_json::to_value(self.fill_color).ok();
                 cb(value.as_mut());
 
-                #[allow(clippy::match_wild_err_arm)]
                 match value {
                     Some(value) => match serde_json::from_value(value) {
-                        Ok(v) => self.fill_color = v,
-                        Err(_) => todo!("logging"),
+                        Ok(Color { r, g, b, a }) => {
+                            self.fill_color = Color::new_clamped(r, g, b, a)
+                        }
+                        Err(err) => log::warn!("ignoring invalid `fill_color` value: {}", err),
                     },
 
-                    None => todo!("logging"),
+                    None => log::error!("unable to serialize `fill_color` attribute"),
                 }
             }
             _ => cb(None),
         }
     }
 
+    #[inline]
+    fn bounds_shape(&self) -> Shape {
+        Shape::Circle {
+            radius: self.radius,
+            fill: self.fill_color,
+            border: None,
+        }
+    }
+
+    #[inline]
+    fn role(&self) -> widget::Role {
+        widget::Role::Graphic
+    }
+
     #[inline]
     fn dimensions(&self) -> (f32, f32) {
         let diameter = self.radius * 2.0;
@@ -212,14 +401,17 @@ impl widget::Runtime for MovingCircle {
         (diameter, diameter)
     }
 
+    /// The circle is centered at `(radius, radius)`, relative to the top-left
+    /// of its bounding box. Points in the corners of the bounding box fall
+    /// outside of the circle.
     #[inline]
     fn is_within_bounds(&self, x: f32, y: f32) -> bool {
-        (self.radius - x).hypot(self.radius - y) <= self.radius
+        self.bounds_shape().contains_point(x, y)
     }
 
     #[inline]
     fn state(&self) -> WidgetState {
-        let mut state = HashMap::with_capacity(5);
+        let mut state = HashMap::with_capacity(12);
 
         state.insert("radius", self.radius.into());
         state.insert("fill_color", self.fill_color.into());
@@ -227,21 +419,34 @@ impl widget::Runtime for MovingCircle {
         state.insert("border_width", self.border_width.into());
         state.insert("color_shift", self.color_shift.into());
         state.insert("focus", self.focus.into());
+        state.insert("acceleration", self.acceleration.into());
+        state.insert("max_speed", self.max_speed.into());
+        state.insert("friction", self.friction.into());
+        state.insert("velocity", vec![self.velocity.0, self.velocity.1].into());
+        state.insert(
+            "drag_offset",
+            self.drag_offset
+                .map_or(Value::Null, |(x, y)| vec![x, y].into()),
+        );
+        state.insert("stick", vec![self.stick.0, self.stick.1].into());
 
         WidgetState::new(widget::Kind::MovingCircle, state)
     }
 
     #[inline]
-    fn interact(&mut self, event: &Event) -> Vec<event::Widget> {
+    fn interact(&mut self, event: &Event, delta: f32) -> Vec<event::Widget> {
         let mut output = vec![];
 
         match event {
             Event::Input(event::Input::Keyboard { keys }) => {
+                // All movement keys combined move the circle in a single
+                // direction.
+                output.extend(self.steer(delta, keys));
+
                 for key in keys {
                     let event = match key {
-                        Key::W | Key::A | Key::S | Key::D => move_event(*key, keys),
                         Key::Q | Key::E => self.resize(1.0, *key),
-                        Key::R | Key::G | Key::B => self.shift_color(0.01, *key),
+                        Key::R | Key::G | Key::B => self.shift_color(delta, *key),
                         Key::Plus | Key::Minus => self.shift_alpha(0.01, *key),
                         _ => None,
                     };
@@ -253,10 +458,29 @@ impl widget::Runtime for MovingCircle {
             }
             Event::Input(event::Input::Focus) => self.focus = true,
             Event::Input(event::Input::Blur) => self.focus = false,
-            Event::Input(event::Input::MousePress { button, x, y })
+            Event::Input(event::Input::DragStart { button, x, y })
                 if button == &event::MouseButton::Left =>
             {
-                output.push(drag_event(*x - self.radius, *y - self.radius))
+                self.drag_offset = Some((*x, *y))
+            }
+            Event::Input(event::Input::DragMove { button, x, y })
+                if button == &event::MouseButton::Left =>
+            {
+                output.extend(self.follow_pointer(*x, *y))
+            }
+            Event::Input(event::Input::DragEnd { button, .. })
+                if button == &event::MouseButton::Left =>
+            {
+                self.drag_offset = None
+            }
+            Event::Input(event::Input::GamepadAxis { axis, value }) =>
+            {
+                #[allow(clippy::wildcard_enum_match_arm)]
+                match axis {
+                    event::GamepadAxis::LeftStickX => self.stick.0 = *value,
+                    event::GamepadAxis::LeftStickY => self.stick.1 = *value,
+                    _ => {}
+                }
             }
             _ => {}
         };
@@ -264,12 +488,41 @@ impl widget::Runtime for MovingCircle {
         output
     }
 
+    #[inline]
+    fn tick(&mut self, delta: f32) -> Vec<event::Widget> {
+        // Unlike keys, the stick position is only reported when it changes, so
+        // the circle keeps moving on each tick while the stick is pushed.
+        let keys = self.stick_keys();
+        let mut output: Vec<_> = self.steer(delta, &keys).into_iter().collect();
+
+        if !self.has_inertia() {
+            return output;
+        }
+
+        let (vx, vy) = self.velocity;
+        if vx.hypot(vy) < MIN_SPEED {
+            self.velocity = (0.0, 0.0);
+            return output;
+        }
+
+        // Friction removes a fraction of the velocity, proportional to the
+        // time passed.
+        let remaining = (1.0 - self.friction * delta).max(0.0);
+        self.velocity = (vx * remaining, vy * remaining);
+
+        output.push(move_by_event(vx * delta, vy * delta));
+        output
+    }
+
     #[inline]
     fn render(&self) -> Vec<Component> {
+        // A focused circle is highlighted with a border, and a soft glow of
+        // the same color around it.
         let border = if self.focus {
             Some(Border {
                 color: self.border_color,
                 width: self.border_width,
+                glow: Some((self.border_color, self.border_width)),
             })
         } else {
             None
@@ -281,44 +534,80 @@ impl widget::Runtime for MovingCircle {
             border,
         };
 
-        let component = Component {
-            shape,
-            coordinates: (0.0, 0.0),
-        };
+        let component = Component::new(shape, (0.0, 0.0));
 
         vec![component]
     }
 }
 
-/// Generate the "move" event based on the provided key and modifiers.
+/// The direction in which the pressed movement keys (either WASD or the arrow
+/// keys) move the circle, as a vector with a length of one.
+///
+/// Returns `None` if no movement keys are pressed, or if they cancel each other
+/// out (e.g. `A` and `D`).
+fn movement_direction(keys: &HashSet<Key>) -> Option<(f32, f32)> {
+    let (mut x, mut y) = (0.0_f32, 0.0_f32);
+
+    for key in keys {
+        #[allow(clippy::wildcard_enum_match_arm)]
+        match key {
+            Key::W | Key::Up => y -= 1.0,
+            Key::S | Key::Down => y += 1.0,
+            Key::A | Key::Left => x -= 1.0,
+            Key::D | Key::Right => x += 1.0,
+            _ => {}
+        }
+    }
+
+    // Pressing both the letter and the arrow key of the same direction doesn't
+    // move the circle any faster.
+    let (x, y) = (x.max(-1.0).min(1.0), y.max(-1.0).min(1.0));
+
+    let length = x.hypot(y);
+    if length < f32::EPSILON {
+        return None;
+    }
+
+    Some((x / length, y / length))
+}
+
+/// Generate the "move" event based on the provided keys, including modifiers.
 ///
 /// A widget does not control its own location on the canvas, so it has to ask
 /// its owner to change its location.
-fn move_event(key: Key, modifiers: &HashSet<Key>) -> Option<event::Widget> {
+fn move_event(keys: &HashSet<Key>) -> Option<event::Widget> {
+    let (x, y) = movement_direction(keys)?;
+
     let speed = match () {
-        _ if modifiers.contains(&Key::Shift) => Speed::Fast,
-        _ if modifiers.contains(&Key::Ctrl) => Speed::Turbo,
+        _ if keys.contains(&Key::Shift) => Speed::Fast,
+        _ if keys.contains(&Key::Ctrl) => Speed::Turbo,
         _ => Speed::Normal,
     };
 
-    #[allow(clippy::wildcard_enum_match_arm)]
-    let direction = match key {
-        Key::W => Direction::Up,
-        Key::S => Direction::Down,
-        Key::A => Direction::Left,
-        Key::D => Direction::Right,
-        _ => return None,
-    };
-
     let mut event = event::Widget::new("move");
-    event.add_attribute("direction", direction);
+    event.add_attribute("x", x);
+    event.add_attribute("y", y);
     event.add_attribute("speed", speed);
 
+    if let Some(direction) = Direction::from_vector((x, y)) {
+        event.add_attribute("direction", direction);
+    }
+
     Some(event)
 }
 
-/// Generate the "drag" event to ask the plugin to move it to a specific
-/// location.
+/// Generate the "move_by" event to ask the plugin to move it by the given
+/// distance, based on its velocity.
+fn move_by_event(x: f32, y: f32) -> event::Widget {
+    let mut event = event::Widget::new("move_by");
+    event.add_attribute("x", x);
+    event.add_attribute("y", y);
+
+    event
+}
+
+/// Generate the "drag" event to ask the plugin to move it by the given
+/// distance, to follow the pointer.
 fn drag_event(x: f32, y: f32) -> event::Widget {
     let mut event = event::Widget::new("drag");
     event.add_attribute("x", x);
@@ -328,17 +617,22 @@ fn drag_event(x: f32, y: f32) -> event::Widget {
 }
 
 impl TryFrom<&WidgetState> for MovingCircle {
-    type Error = String;
+    type Error = widget::Error;
 
     #[inline]
     fn try_from(state: &WidgetState) -> Result<Self, Self::Error> {
-        #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
-        let radius: f64 = state.get_as("radius").ok_or("missing `radius` attribute")?;
+        let radius: f64 = widget::required(state, "radius")?;
         let fill_color: Color = state.get_as("fill_color").unwrap_or_default();
         let border_color: Color = state.get_as("border_color").unwrap_or_default();
         let border_width: f64 = state.get_as("border_width").unwrap_or(0.0);
         let color_shift: ColorShift = state.get_as("color_shift").unwrap_or_default();
         let focus = state.get("focus").and_then(Value::as_bool).unwrap_or(false);
+        let acceleration: f32 = state.get_as("acceleration").unwrap_or(0.0);
+        let max_speed: f32 = state.get_as("max_speed").unwrap_or(0.0);
+        let friction: f32 = state.get_as("friction").unwrap_or(0.0);
+        let velocity: (f32, f32) = state.get_as("velocity").unwrap_or_default();
+        let drag_offset = state.get_as("drag_offset").flatten();
+        let stick: (f32, f32) = state.get_as("stick").unwrap_or_default();
 
         #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
         let (radius, border_width) = (radius as f32, border_width as f32);
@@ -350,6 +644,12 @@ impl TryFrom<&WidgetState> for MovingCircle {
             border_width,
             color_shift,
             focus,
+            acceleration,
+            max_speed,
+            friction,
+            velocity,
+            drag_offset,
+            stick,
         })
     }
 }
@@ -361,3 +661,407 @@ impl From<ColorShift> for Value {
         serde_json::to_value(color_shift).expect("valid")
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::widget::Runtime;
+
+    mod try_from {
+        use super::*;
+
+        fn circle(radius: Value) -> Result<MovingCircle, widget::Error> {
+            let mut state = HashMap::new();
+            state.insert("radius", radius);
+
+            MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state))
+        }
+
+        #[test]
+        fn valid() {
+            assert_eq!(circle(10.0.into()).unwrap().radius, 10.0);
+        }
+
+        #[test]
+        fn missing_radius() {
+            let state = WidgetState::new(widget::Kind::MovingCircle, HashMap::<String, _>::new());
+            let err = MovingCircle::try_from(&state).unwrap_err();
+
+            assert_eq!(err.widget_kind, widget::Kind::MovingCircle);
+            assert_eq!(err.attribute, "radius");
+            assert_eq!(err.reason, widget::ErrorReason::Missing);
+        }
+
+        #[test]
+        fn malformed_radius() {
+            let err = circle("large".into()).unwrap_err();
+
+            assert_eq!(err.attribute, "radius");
+            assert!(matches!(err.reason, widget::ErrorReason::Malformed(_)));
+            assert_eq!(
+                err.to_string(),
+                "malformed `radius` attribute of MovingCircle widget: \
+                 invalid type: string \"large\", expected f64"
+            );
+        }
+    }
+
+    mod is_within_bounds {
+        use super::*;
+
+        fn circle() -> MovingCircle {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+
+            MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state)).unwrap()
+        }
+
+        #[test]
+        fn center() {
+            assert!(circle().is_within_bounds(10.0, 10.0));
+        }
+
+        #[test]
+        fn edge() {
+            let circle = circle();
+
+            assert!(circle.is_within_bounds(0.0, 10.0));
+            assert!(circle.is_within_bounds(20.0, 10.0));
+            assert!(circle.is_within_bounds(10.0, 0.0));
+            assert!(circle.is_within_bounds(10.0, 20.0));
+        }
+
+        #[test]
+        fn corner() {
+            let circle = circle();
+
+            assert!(!circle.is_within_bounds(0.0, 0.0));
+            assert!(!circle.is_within_bounds(20.0, 0.0));
+            assert!(!circle.is_within_bounds(0.0, 20.0));
+            assert!(!circle.is_within_bounds(20.0, 20.0));
+            assert!(!circle.is_within_bounds(2.0, 2.0));
+        }
+    }
+
+    mod interact {
+        use super::*;
+
+        #[test]
+        fn color_shift_is_frame_rate_independent() {
+            let shift = |ticks: usize, delta: f32| {
+                let mut state = HashMap::new();
+                state.insert("radius", 10.0.into());
+                state.insert("fill_color", Color::new(0.0, 0.0, 0.0, 1.0).into());
+
+                let mut circle =
+                    MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state))
+                        .unwrap();
+
+                let keys = vec![Key::R].into_iter().collect();
+                let event = Event::Input(event::Input::Keyboard { keys });
+
+                for _ in 0..ticks {
+                    let _ = circle.interact(&event, delta);
+                }
+
+                circle.fill_color.r
+            };
+
+            // Half a second at 100, 50 and 20 updates per second.
+            let a = shift(50, 0.01);
+            let b = shift(25, 0.02);
+            let c = shift(10, 0.05);
+
+            assert!((a - 0.5).abs() < 0.0001);
+            assert!((a - b).abs() < 0.0001);
+            assert!((a - c).abs() < 0.0001);
+        }
+
+        #[test]
+        fn movement_keys_accelerate_with_inertia() {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+            state.insert("acceleration", 100.0.into());
+            state.insert("max_speed", 15.0.into());
+
+            let mut circle =
+                MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state))
+                    .unwrap();
+
+            let keys = vec![Key::D].into_iter().collect();
+            let event = Event::Input(event::Input::Keyboard { keys });
+
+            assert!(circle.interact(&event, 0.1).is_empty());
+            assert_eq!(circle.velocity, (10.0, 0.0));
+
+            let _ = circle.interact(&event, 0.1);
+            assert_eq!(circle.velocity, (15.0, 0.0));
+        }
+    }
+
+    mod drag {
+        use super::*;
+
+        fn drag(input: fn(event::MouseButton, f32, f32) -> event::Input, x: f32, y: f32) -> Event {
+            Event::Input(input(event::MouseButton::Left, x, y))
+        }
+
+        #[test]
+        fn follows_pointer() {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+
+            let mut circle =
+                MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state))
+                    .unwrap();
+
+            let start = |button, x, y| event::Input::DragStart { button, x, y };
+            let moved = |button, x, y| event::Input::DragMove { button, x, y };
+            let end = |button, x, y| event::Input::DragEnd { button, x, y };
+
+            // The circle is held near its top-left, and doesn't jump to center
+            // itself under the pointer.
+            assert!(circle.interact(&drag(start, 5.0, 6.0), 0.01).is_empty());
+
+            let events = circle.interact(&drag(moved, 15.0, 4.0), 0.01);
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].name(), "drag");
+            assert_eq!(events[0].attribute("x"), Some(&Value::from(10.0)));
+            assert_eq!(events[0].attribute("y"), Some(&Value::from(-2.0)));
+
+            // The drag offset is stored in the widget state, to be retained
+            // between updates.
+            let mut circle = MovingCircle::try_from(&circle.state()).unwrap();
+            assert!(circle.interact(&drag(moved, 5.0, 6.0), 0.01).is_empty());
+
+            circle.interact(&drag(end, 5.0, 6.0), 0.01);
+            assert_eq!(circle.drag_offset, None);
+            assert!(circle.interact(&drag(moved, 50.0, 6.0), 0.01).is_empty());
+        }
+    }
+
+    mod arrow_keys {
+        use super::*;
+
+        fn moves(key: Key, modifier: Option<Key>) -> Vec<event::Widget> {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+
+            let mut circle =
+                MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state))
+                    .unwrap();
+
+            let keys = vec![Some(key), modifier].into_iter().flatten().collect();
+            circle.interact(&Event::Input(event::Input::Keyboard { keys }), 0.01)
+        }
+
+        #[test]
+        fn same_as_wasd() {
+            let pairs = [
+                (Key::Up, Key::W),
+                (Key::Down, Key::S),
+                (Key::Left, Key::A),
+                (Key::Right, Key::D),
+            ];
+
+            for &(arrow, letter) in &pairs {
+                assert_eq!(moves(arrow, None), moves(letter, None));
+                assert_eq!(
+                    moves(arrow, Some(Key::Shift)),
+                    moves(letter, Some(Key::Shift))
+                );
+                assert_eq!(moves(arrow, None).len(), 1);
+            }
+        }
+    }
+
+    mod key_combo {
+        use super::*;
+
+        fn circle(acceleration: f32) -> MovingCircle {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+            state.insert("acceleration", acceleration.into());
+            state.insert("max_speed", 100.0.into());
+
+            MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state)).unwrap()
+        }
+
+        fn keyboard(keys: Vec<Key>) -> Event {
+            let keys = keys.into_iter().collect();
+            Event::Input(event::Input::Keyboard { keys })
+        }
+
+        fn vector(event: &event::Widget) -> (f64, f64) {
+            let x = event.attribute("x").and_then(Value::as_f64).unwrap();
+            let y = event.attribute("y").and_then(Value::as_f64).unwrap();
+
+            (x, y)
+        }
+
+        #[test]
+        fn cardinal() {
+            let cases = vec![
+                (Key::W, (0.0, -1.0), "Up"),
+                (Key::Down, (0.0, 1.0), "Down"),
+                (Key::A, (-1.0, 0.0), "Left"),
+                (Key::Right, (1.0, 0.0), "Right"),
+            ];
+
+            for (key, expected, direction) in cases {
+                let events = circle(0.0).interact(&keyboard(vec![key]), 0.01);
+
+                assert_eq!(events.len(), 1);
+                assert_eq!(vector(&events[0]), expected);
+                assert_eq!(
+                    events[0].attribute("direction"),
+                    Some(&Value::from(direction))
+                );
+            }
+        }
+
+        #[test]
+        fn diagonal() {
+            let events = circle(0.0).interact(&keyboard(vec![Key::W, Key::D]), 0.01);
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].name(), "move");
+            assert_eq!(events[0].attribute("speed"), Some(&Value::from("Normal")));
+
+            // Moving diagonally is as fast as moving along a single axis.
+            let (x, y) = vector(&events[0]);
+            assert!(x > 0.0 && y < 0.0);
+            assert!((x + y).abs() < 0.0001);
+            assert!((x.hypot(y) - 1.0).abs() < 0.0001);
+
+            // The legacy direction can't describe diagonal movement.
+            assert!(events[0].attribute("direction").is_none());
+        }
+
+        #[test]
+        fn opposite_keys_cancel_out() {
+            let events = circle(0.0).interact(&keyboard(vec![Key::A, Key::D, Key::W]), 0.01);
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(vector(&events[0]), (0.0, -1.0));
+
+            let events = circle(0.0).interact(&keyboard(vec![Key::A, Key::D]), 0.01);
+            assert!(events.is_empty());
+        }
+
+        #[test]
+        fn letter_and_arrow_key() {
+            let events = circle(0.0).interact(&keyboard(vec![Key::D, Key::Right]), 0.01);
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(vector(&events[0]), (1.0, 0.0));
+        }
+
+        #[test]
+        fn diagonal_with_inertia() {
+            let mut circle = circle(100.0);
+            let _ = circle.interact(&keyboard(vec![Key::S, Key::A]), 0.1);
+
+            let (vx, vy) = circle.velocity;
+            assert!(vx < 0.0 && vy > 0.0);
+            assert!((vx.hypot(vy) - 10.0).abs() < 0.0001);
+        }
+    }
+
+    mod tick {
+        use super::*;
+
+        fn circle(velocity: (f32, f32), friction: f32) -> MovingCircle {
+            let mut state = HashMap::new();
+            state.insert("radius", 10.0.into());
+            state.insert("acceleration", 100.0.into());
+            state.insert("friction", friction.into());
+            state.insert("velocity", vec![velocity.0, velocity.1].into());
+
+            MovingCircle::try_from(&WidgetState::new(widget::Kind::MovingCircle, state)).unwrap()
+        }
+
+        #[test]
+        fn without_inertia() {
+            let mut circle = circle((10.0, 0.0), 1.0);
+            circle.acceleration = 0.0;
+
+            assert!(circle.tick(0.1).is_empty());
+        }
+
+        #[test]
+        fn moves_by_velocity() {
+            let mut circle = circle((10.0, -20.0), 0.0);
+            let events = circle.tick(0.5);
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].name(), "move_by");
+            assert_eq!(events[0].attribute("x"), Some(&Value::from(5.0)));
+            assert_eq!(events[0].attribute("y"), Some(&Value::from(-10.0)));
+            assert_eq!(circle.velocity, (10.0, -20.0));
+        }
+
+        #[test]
+        fn stick_moves_circle() {
+            let mut circle = circle((0.0, 0.0), 0.0);
+            circle.acceleration = 0.0;
+
+            let stick = |axis, value| Event::Input(event::Input::GamepadAxis { axis, value });
+
+            circle.interact(&stick(event::GamepadAxis::LeftStickX, 0.8), 0.1);
+            circle.interact(&stick(event::GamepadAxis::LeftStickY, 0.1), 0.1);
+
+            // The circle keeps moving while the stick is pushed, ignoring the
+            // Y axis within the dead zone.
+            for _ in 0..2 {
+                let events = circle.tick(0.1);
+
+                assert_eq!(events.len(), 1);
+                assert_eq!(events[0].name(), "move");
+                assert_eq!(events[0].attribute("x"), Some(&Value::from(1.0)));
+                assert_eq!(events[0].attribute("y"), Some(&Value::from(0.0)));
+            }
+
+            circle.interact(&stick(event::GamepadAxis::LeftStickX, 0.0), 0.1);
+            assert!(circle.tick(0.1).is_empty());
+        }
+
+        #[test]
+        fn stick_accelerates_with_inertia() {
+            let mut circle = circle((0.0, 0.0), 0.0);
+            circle.max_speed = 100.0;
+
+            let stick = Event::Input(event::Input::GamepadAxis {
+                axis: event::GamepadAxis::LeftStickY,
+                value: 1.0,
+            });
+
+            circle.interact(&stick, 0.1);
+            let _ = circle.tick(0.1);
+
+            assert_eq!(circle.velocity, (0.0, -10.0));
+        }
+
+        #[test]
+        fn velocity_decays_under_friction() {
+            let mut circle = circle((100.0, 0.0), 2.0);
+
+            let mut previous = circle.velocity.0;
+            for _ in 0..10 {
+                let _ = circle.tick(0.1);
+
+                assert!(circle.velocity.0 < previous);
+                previous = circle.velocity.0;
+            }
+
+            // Eventually the circle comes to a halt.
+            for _ in 0..100 {
+                let _ = circle.tick(0.1);
+            }
+
+            assert_eq!(circle.velocity, (0.0, 0.0));
+            assert!(circle.tick(0.1).is_empty());
+        }
+    }
+}
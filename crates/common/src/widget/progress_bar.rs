@@ -0,0 +1,213 @@
+//! A horizontal progress bar.
+
+use crate::{widget, Color, Component, Deserialize, Serialize, Shape, Value, WidgetState};
+use std::{collections::HashMap, convert::TryFrom};
+
+/// A horizontal progress bar.
+///
+/// This widget renders a background spanning the width of the bar, and a fill
+/// from the left edge, proportional to its progress. The progress ranges from
+/// `0.0` (empty) to `1.0` (full), values outside of that range are rendered as
+/// an empty or full bar.
+///
+/// The progress bar is non-interactive, its progress is set by the plugin that
+/// owns it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressBar {
+    /// The width of the progress bar.
+    width: f32,
+
+    /// The height of the progress bar.
+    height: f32,
+
+    /// The progress, from `0.0` to `1.0`.
+    progress: f32,
+
+    /// The color of the (empty) bar.
+    background_color: Color,
+
+    /// The color of the progress fill.
+    fill_color: Color,
+}
+
+impl ProgressBar {
+    /// The attributes used when they aren't configured for the widget.
+    ///
+    /// The dimensions of a progress bar have no sensible default.
+    pub(super) fn default_attributes() -> Vec<(&'static str, Value)> {
+        vec![
+            ("progress", 0.0.into()),
+            ("background_color", Color::from_rgb(128, 128, 128).into()),
+            ("fill_color", Color::default().into()),
+        ]
+    }
+
+    /// The attributes the widget supports.
+    pub(super) fn attributes() -> Vec<widget::Attribute> {
+        use widget::Attribute;
+
+        vec![
+            Attribute::required::<f32>("width"),
+            Attribute::required::<f32>("height"),
+            Attribute::optional::<f32>("progress"),
+            Attribute::optional::<Color>("background_color"),
+            Attribute::optional::<Color>("fill_color"),
+        ]
+    }
+
+    /// The progress, clamped to the `0.0` to `1.0` range.
+    fn clamped_progress(&self) -> f32 {
+        self.progress.max(0.0).min(1.0)
+    }
+}
+
+impl TryFrom<&WidgetState> for ProgressBar {
+    type Error = widget::Error;
+
+    #[inline]
+    fn try_from(state: &WidgetState) -> Result<Self, Self::Error> {
+        let width = widget::required(state, "width")?;
+        let height = widget::required(state, "height")?;
+        let progress = state.get_as("progress").unwrap_or(0.0);
+        let background_color = state
+            .get_as("background_color")
+            .unwrap_or_else(|| Color::from_rgb(128, 128, 128));
+        let fill_color = state.get_as("fill_color").unwrap_or_default();
+
+        Ok(Self {
+            width,
+            height,
+            progress,
+            background_color,
+            fill_color,
+        })
+    }
+}
+
+impl widget::Runtime for ProgressBar {
+    #[inline]
+    fn attribute(&self, key: &str) -> Option<Value> {
+        match key {
+            "width" => Some(self.width.into()),
+            "height" => Some(self.height.into()),
+            "progress" => Some(self.progress.into()),
+            "background_color" => Some(self.background_color.into()),
+            "fill_color" => Some(self.fill_color.into()),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+    fn attribute_mut(&mut self, key: &str, cb: fn(value: Option<&mut Value>)) {
+        match key {
+            "progress" => {
+                let mut value = Value::from(self.progress);
+                cb(Some(&mut value));
+
+                match value.as_f64() {
+                    Some(progress) => self.progress = progress as f32,
+                    None => log::warn!("ignoring invalid `progress` value: {}", value),
+                }
+            }
+            _ => cb(None),
+        }
+    }
+
+    #[inline]
+    fn dimensions(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    #[inline]
+    fn accessibility_label(&self) -> Option<String> {
+        Some(format!("{:.0}%", self.clamped_progress() * 100.0))
+    }
+
+    #[inline]
+    fn role(&self) -> widget::Role {
+        widget::Role::ProgressBar
+    }
+
+    #[inline]
+    fn state(&self) -> WidgetState {
+        let mut state = HashMap::with_capacity(5);
+        state.insert("width", self.width.into());
+        state.insert("height", self.height.into());
+        state.insert("progress", self.progress.into());
+        state.insert("background_color", self.background_color.into());
+        state.insert("fill_color", self.fill_color.into());
+
+        WidgetState::new(widget::Kind::ProgressBar, state)
+    }
+
+    #[inline]
+    fn render(&self) -> Vec<Component> {
+        let background = Shape::Rectangle {
+            width: self.width,
+            height: self.height,
+            color: self.background_color,
+            rotation: 0.0,
+            border: None,
+        };
+
+        let fill = Shape::Rectangle {
+            width: self.width * self.clamped_progress(),
+            height: self.height,
+            color: self.fill_color,
+            rotation: 0.0,
+            border: None,
+        };
+
+        vec![
+            Component::new(background, (0.0, 0.0)),
+            Component::new(fill, (0.0, 0.0)),
+        ]
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::widget::Runtime;
+
+    fn progress_bar(progress: f32) -> ProgressBar {
+        let mut state = HashMap::new();
+        state.insert("width", 200.0.into());
+        state.insert("height", 10.0.into());
+        state.insert("progress", progress.into());
+
+        let state = WidgetState::new(widget::Kind::ProgressBar, state);
+        ProgressBar::try_from(&state).unwrap()
+    }
+
+    fn fill_width(progress_bar: &ProgressBar) -> f32 {
+        match progress_bar.render()[1].shape {
+            Shape::Rectangle { width, .. } => width,
+            _ => panic!("expected a rectangle"),
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn fill() {
+            let progress_bar = progress_bar(0.25);
+
+            assert_eq!(progress_bar.render().len(), 2);
+            assert_eq!(fill_width(&progress_bar), 50.0);
+            assert_eq!(progress_bar.accessibility_label(), Some("25%".to_owned()));
+        }
+
+        #[test]
+        fn clamped() {
+            assert_eq!(fill_width(&progress_bar(1.5)), 200.0);
+            assert_eq!(fill_width(&progress_bar(-0.5)), 0.0);
+
+            // The configured progress itself is kept as-is.
+            assert_eq!(progress_bar(1.5).attribute("progress"), Some(1.5.into()));
+        }
+    }
+}
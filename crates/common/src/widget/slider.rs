@@ -0,0 +1,403 @@
+//! A horizontal value slider.
+
+use crate::{
+    event, widget, Color, Component, Deserialize, Event, Serialize, Shape, Value, WidgetState,
+};
+use std::{collections::HashMap, convert::TryFrom};
+
+/// The height of the track, relative to the height of the slider.
+const TRACK_HEIGHT: f32 = 0.25;
+
+/// A horizontal slider to select a value from a range.
+///
+/// This widget renders a track spanning the width of the slider, and a
+/// circular handle with a diameter equal to the height of the slider. The
+/// handle moves between the two ends of the track as the value goes from `min`
+/// to `max`.
+///
+/// Clicking on the slider, or dragging its handle, emits the "changed" event
+/// with a `value` attribute containing the new value. The event is only
+/// emitted if the value actually changed.
+///
+/// The value is always clamped to the configured `min` and `max` range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Slider {
+    /// The width of the slider.
+    width: f32,
+
+    /// The height of the slider.
+    height: f32,
+
+    /// The value of the slider when the handle is at the left end of the
+    /// track.
+    min: f32,
+
+    /// The value of the slider when the handle is at the right end of the
+    /// track.
+    max: f32,
+
+    /// The current value of the slider.
+    value: f32,
+
+    /// The color of the track.
+    track_color: Color,
+
+    /// The color of the handle.
+    handle_color: Color,
+}
+
+impl Slider {
+    /// The attributes used when they aren't configured for the widget.
+    ///
+    /// The dimensions of a slider have no sensible default.
+    pub(super) fn default_attributes() -> Vec<(&'static str, Value)> {
+        vec![
+            ("min", 0.0.into()),
+            ("max", 1.0.into()),
+            ("value", 0.0.into()),
+            ("track_color", Color::from_rgb(128, 128, 128).into()),
+            ("handle_color", Color::default().into()),
+        ]
+    }
+
+    /// The attributes the widget supports.
+    pub(super) fn attributes() -> Vec<widget::Attribute> {
+        use widget::Attribute;
+
+        vec![
+            Attribute::required::<f32>("width"),
+            Attribute::required::<f32>("height"),
+            Attribute::optional::<f32>("min"),
+            Attribute::optional::<f32>("max"),
+            Attribute::optional::<f32>("value"),
+            Attribute::optional::<Color>("track_color"),
+            Attribute::optional::<Color>("handle_color"),
+        ]
+    }
+
+    /// The radius of the handle.
+    fn handle_radius(&self) -> f32 {
+        self.height / 2.0
+    }
+
+    /// The distance the center of the handle travels from one end of the
+    /// track to the other.
+    fn travel(&self) -> f32 {
+        (self.width - self.height).max(0.0)
+    }
+
+    /// Clamp the given value to the configured range.
+    ///
+    /// A range with a `min` larger than its `max` only allows the `min` value.
+    fn clamp(&self, value: f32) -> f32 {
+        value.min(self.max).max(self.min)
+    }
+
+    /// Move the handle to the given (relative) horizontal pointer position.
+    ///
+    /// Returns the "changed" event, if the value of the slider changed.
+    #[allow(clippy::float_cmp)]
+    fn move_handle(&mut self, x: f32) -> Option<event::Widget> {
+        let travel = self.travel();
+        let ratio = if travel > 0.0 {
+            ((x - self.handle_radius()) / travel).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+
+        let value = self.clamp(self.min + ratio * (self.max - self.min));
+
+        if value == self.value {
+            return None;
+        }
+
+        self.value = value;
+
+        let mut event = event::Widget::new("changed");
+        event.add_attribute("value", value);
+
+        Some(event)
+    }
+}
+
+impl TryFrom<&WidgetState> for Slider {
+    type Error = widget::Error;
+
+    #[inline]
+    fn try_from(state: &WidgetState) -> Result<Self, Self::Error> {
+        let width = widget::required(state, "width")?;
+        let height = widget::required(state, "height")?;
+        let min = state.get_as("min").unwrap_or(0.0);
+        let max = state.get_as("max").unwrap_or(1.0);
+        let value = state.get_as("value").unwrap_or(min);
+        let track_color = state
+            .get_as("track_color")
+            .unwrap_or_else(|| Color::from_rgb(128, 128, 128));
+        let handle_color = state.get_as("handle_color").unwrap_or_default();
+
+        let mut slider = Self {
+            width,
+            height,
+            min,
+            max,
+            value,
+            track_color,
+            handle_color,
+        };
+
+        slider.value = slider.clamp(value);
+
+        Ok(slider)
+    }
+}
+
+impl widget::Runtime for Slider {
+    #[inline]
+    fn attribute(&self, key: &str) -> Option<Value> {
+        match key {
+            "width" => Some(self.width.into()),
+            "height" => Some(self.height.into()),
+            "min" => Some(self.min.into()),
+            "max" => Some(self.max.into()),
+            "value" => Some(self.value.into()),
+            "track_color" => Some(self.track_color.into()),
+            "handle_color" => Some(self.handle_color.into()),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+    fn attribute_mut(&mut self, key: &str, cb: fn(value: Option<&mut Value>)) {
+        let attribute = match key {
+            "min" => &mut self.min,
+            "max" => &mut self.max,
+            "value" => &mut self.value,
+            _ => return cb(None),
+        };
+
+        let mut value = Value::from(*attribute);
+        cb(Some(&mut value));
+
+        match value.as_f64() {
+            Some(value) => *attribute = value as f32,
+            None => log::warn!("ignoring invalid `{}` value: {}", key, value),
+        }
+
+        // Changing the range can move the value out of it.
+        self.value = self.clamp(self.value);
+    }
+
+    #[inline]
+    fn dimensions(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    #[inline]
+    fn accessibility_label(&self) -> Option<String> {
+        Some(self.value.to_string())
+    }
+
+    #[inline]
+    fn role(&self) -> widget::Role {
+        widget::Role::Slider
+    }
+
+    #[inline]
+    fn state(&self) -> WidgetState {
+        let mut state = HashMap::with_capacity(7);
+        state.insert("width", self.width.into());
+        state.insert("height", self.height.into());
+        state.insert("min", self.min.into());
+        state.insert("max", self.max.into());
+        state.insert("value", self.value.into());
+        state.insert("track_color", self.track_color.into());
+        state.insert("handle_color", self.handle_color.into());
+
+        WidgetState::new(widget::Kind::Slider, state)
+    }
+
+    #[inline]
+    fn interact(&mut self, event: &Event, _: f32) -> Vec<event::Widget> {
+        #[allow(clippy::wildcard_enum_match_arm)]
+        let event = match event {
+            // A click moves the handle to the pointer, during a drag the
+            // handle follows the pointer.
+            Event::Input(event::Input::MouseClick { button, x, .. })
+            | Event::Input(event::Input::DragStart { button, x, .. })
+            | Event::Input(event::Input::DragMove { button, x, .. })
+                if button == &event::MouseButton::Left =>
+            {
+                self.move_handle(*x)
+            }
+            _ => None,
+        };
+
+        event.into_iter().collect()
+    }
+
+    #[inline]
+    fn render(&self) -> Vec<Component> {
+        let track_height = self.height * TRACK_HEIGHT;
+        let track = Shape::Rectangle {
+            width: self.width,
+            height: track_height,
+            color: self.track_color,
+            rotation: 0.0,
+            border: None,
+        };
+
+        let handle = Shape::Circle {
+            radius: self.handle_radius(),
+            fill: self.handle_color,
+            border: None,
+        };
+
+        let range = self.max - self.min;
+        let ratio = if range > 0.0 {
+            (self.value - self.min) / range
+        } else {
+            0.0
+        };
+
+        vec![
+            Component::new(track, (0.0, (self.height - track_height) / 2.0)),
+            Component::new(handle, (ratio * self.travel(), 0.0)),
+        ]
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::widget::Runtime;
+
+    fn slider(min: f32, max: f32, value: f32) -> Slider {
+        let mut state = HashMap::new();
+        state.insert("width", 110.0.into());
+        state.insert("height", 10.0.into());
+        state.insert("min", min.into());
+        state.insert("max", max.into());
+        state.insert("value", value.into());
+
+        let state = WidgetState::new(widget::Kind::Slider, state);
+        Slider::try_from(&state).unwrap()
+    }
+
+    fn drag_move(x: f32) -> Event {
+        Event::Input(event::Input::DragMove {
+            button: event::MouseButton::Left,
+            x,
+            y: 5.0,
+        })
+    }
+
+    mod try_from {
+        use super::*;
+
+        #[test]
+        fn missing_dimensions() {
+            let state = WidgetState::new(widget::Kind::Slider, HashMap::new());
+
+            assert_eq!(
+                Slider::try_from(&state),
+                Err(widget::Error::new(
+                    widget::Kind::Slider,
+                    "width",
+                    widget::ErrorReason::Missing
+                ))
+            );
+        }
+
+        #[test]
+        fn clamped_value() {
+            assert_eq!(slider(0.0, 10.0, 20.0).value, 10.0);
+            assert_eq!(slider(0.0, 10.0, -5.0).value, 0.0);
+            assert_eq!(slider(0.0, 10.0, 5.0).value, 5.0);
+        }
+    }
+
+    mod interact {
+        use super::*;
+
+        #[test]
+        fn drag() {
+            let mut slider = slider(0.0, 10.0, 0.0);
+
+            // The handle center travels from x=5 to x=105.
+            let events = slider.interact(&drag_move(55.0), 0.01);
+            let mut expected = event::Widget::new("changed");
+            expected.add_attribute("value", 5.0);
+
+            assert_eq!(events, vec![expected]);
+            assert_eq!(slider.state().get_as::<f32>("value"), Some(5.0));
+        }
+
+        #[test]
+        fn drag_beyond_track() {
+            let mut slider = slider(0.0, 10.0, 5.0);
+
+            slider.interact(&drag_move(200.0), 0.01);
+            assert_eq!(slider.value, 10.0);
+
+            slider.interact(&drag_move(0.0), 0.01);
+            assert_eq!(slider.value, 0.0);
+        }
+
+        #[test]
+        fn press_ignored() {
+            let mut slider = slider(0.0, 10.0, 0.0);
+            let press = Event::Input(event::Input::MousePress {
+                button: event::MouseButton::Left,
+                x: 55.0,
+                y: 5.0,
+            });
+
+            assert!(slider.interact(&press, 0.01).is_empty());
+            assert_eq!(slider.value, 0.0);
+        }
+
+        #[test]
+        fn unchanged() {
+            let mut slider = slider(0.0, 10.0, 10.0);
+
+            assert!(slider.interact(&drag_move(105.0), 0.01).is_empty());
+        }
+    }
+
+    mod attribute_mut {
+        use super::*;
+
+        #[test]
+        fn range_clamps_value() {
+            let mut slider = slider(0.0, 10.0, 8.0);
+            slider.attribute_mut("max", |v| *v.unwrap() = 4.0.into());
+
+            assert_eq!(slider.attribute("max"), Some(4.0.into()));
+            assert_eq!(slider.value, 4.0);
+        }
+
+        #[test]
+        fn invalid_value_ignored() {
+            let mut slider = slider(0.0, 10.0, 8.0);
+            slider.attribute_mut("value", |v| *v.unwrap() = "high".into());
+
+            assert_eq!(slider.value, 8.0);
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn handle_position() {
+            let components = slider(0.0, 10.0, 2.5).render();
+
+            assert_eq!(components.len(), 2);
+            assert_eq!(components[0].coordinates, (0.0, 3.75));
+            assert_eq!(components[1].coordinates, (25.0, 0.0));
+            assert!(matches!(components[1].shape, Shape::Circle { radius, .. } if radius == 5.0));
+        }
+    }
+}
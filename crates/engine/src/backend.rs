@@ -0,0 +1,55 @@
+//! The game engine backends the engine can run on.
+//!
+//! A single backend is compiled into the engine, selected through the
+//! `backend-*` features. Each backend implements the [`Backend`] trait, and
+//! only contains the parts specific to the game engine it abstracts over. The
+//! plumbing shared by all backends lives in the `renderer` and `updater`
+//! modules.
+
+#[cfg(all(feature = "backend-coffee", not(feature = "backend-ggez")))]
+mod coffee;
+#[cfg(all(feature = "backend-ggez", not(feature = "backend-coffee")))]
+mod ggez;
+#[cfg(all(
+    feature = "backend-null",
+    not(feature = "backend-coffee"),
+    not(feature = "backend-ggez")
+))]
+mod null;
+mod renderer;
+mod updater;
+
+#[cfg(all(feature = "backend-coffee", not(feature = "backend-ggez")))]
+pub use self::coffee::{Coffee as Selected, BUILDER};
+#[cfg(all(feature = "backend-ggez", not(feature = "backend-coffee")))]
+pub use self::ggez::Ggez as Selected;
+#[cfg(all(
+    feature = "backend-null",
+    not(feature = "backend-coffee"),
+    not(feature = "backend-ggez")
+))]
+pub use self::null::Null as Selected;
+
+use crate::{config, Engine, Error};
+use std::fmt;
+
+/// A game engine backend.
+///
+/// The backend is in charge of running the engine: it receives player input,
+/// decides when the game state is updated, and draws the game to the screen.
+pub trait Backend {
+    /// Handles rendering frames to the screen.
+    type Renderer: From<config::Renderer> + fmt::Debug;
+
+    /// Handles updating the game state.
+    type Updater: From<config::Updater> + fmt::Debug;
+
+    /// Run the engine to completion or until an error occurs.
+    fn run(engine: Engine) -> Result<(), Error>;
+}
+
+/// The renderer of the selected backend.
+pub type Renderer = <Selected as Backend>::Renderer;
+
+/// The updater of the selected backend.
+pub type Updater = <Selected as Backend>::Updater;
@@ -1,9 +1,24 @@
 //! The "coffee" game engine backend.
 
+mod input;
 mod renderer;
 mod run;
 mod updater;
 
-pub use renderer::Renderer;
-pub use run::{run, BUILDER};
-pub use updater::Updater;
+pub use run::BUILDER;
+
+use super::Backend;
+use crate::{Engine, Error};
+
+/// The "coffee" game engine backend.
+#[derive(Debug)]
+pub struct Coffee;
+
+impl Backend for Coffee {
+    type Renderer = super::renderer::Renderer<renderer::State>;
+    type Updater = super::updater::Updater<updater::State>;
+
+    fn run(engine: Engine) -> Result<(), Error> {
+        run::run(engine)
+    }
+}
@@ -0,0 +1,176 @@
+//! The input tracked by the coffee backend.
+
+use coffee::input::{self, keyboard::Keyboard, mouse::Mouse, KeyboardAndMouse};
+use common::event;
+
+#[cfg(feature = "gamepad")]
+use coffee::input::gamepad::{self, Axis, Button};
+
+/// The keyboard and mouse input, and (if enabled) gamepad input, received
+/// since the last interaction.
+pub struct Input {
+    /// The state of the keyboard and mouse.
+    keyboard_and_mouse: KeyboardAndMouse,
+
+    /// The gamepad events, converted to input events of the engine.
+    #[cfg(feature = "gamepad")]
+    gamepad_events: Vec<event::Input>,
+}
+
+impl Input {
+    /// Get the state of the keyboard.
+    pub fn keyboard(&self) -> &Keyboard {
+        self.keyboard_and_mouse.keyboard()
+    }
+
+    /// Get the state of the mouse.
+    pub fn mouse(&self) -> &Mouse {
+        self.keyboard_and_mouse.mouse()
+    }
+
+    /// Get the gamepad events, in the order in which they occurred.
+    ///
+    /// Without the "gamepad" feature, there are never any gamepad events.
+    pub fn gamepad_events(&self) -> &[event::Input] {
+        #[cfg(feature = "gamepad")]
+        let events = self.gamepad_events.as_slice();
+
+        #[cfg(not(feature = "gamepad"))]
+        let events = &[];
+
+        events
+    }
+}
+
+impl input::Input for Input {
+    fn new() -> Self {
+        Self {
+            keyboard_and_mouse: KeyboardAndMouse::new(),
+
+            #[cfg(feature = "gamepad")]
+            gamepad_events: vec![],
+        }
+    }
+
+    fn update(&mut self, event: input::Event) {
+        match event {
+            #[cfg(feature = "gamepad")]
+            input::Event::Gamepad { event, .. } => {
+                self.gamepad_events.extend(convert_gamepad_event(event))
+            }
+            event => self.keyboard_and_mouse.update(event),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.keyboard_and_mouse.clear();
+
+        #[cfg(feature = "gamepad")]
+        self.gamepad_events.clear();
+    }
+}
+
+/// Convert a gamepad event to an input event supported by the engine.
+///
+/// Returns `None` for events the engine doesn't handle, such as a gamepad
+/// connecting.
+#[cfg(feature = "gamepad")]
+#[allow(clippy::wildcard_enum_match_arm)]
+fn convert_gamepad_event(gamepad_event: gamepad::Event) -> Option<event::Input> {
+    let input = match gamepad_event {
+        gamepad::Event::ButtonPressed(button) => event::Input::GamepadButton {
+            button: convert_gamepad_button(button),
+            pressed: true,
+        },
+        gamepad::Event::ButtonReleased(button) => event::Input::GamepadButton {
+            button: convert_gamepad_button(button),
+            pressed: false,
+        },
+        gamepad::Event::AxisChanged(axis, value) => event::Input::GamepadAxis {
+            axis: convert_gamepad_axis(axis),
+            value,
+        },
+        _ => return None,
+    };
+
+    Some(input)
+}
+
+/// Convert a gamepad button to a gamepad button supported by the engine.
+#[cfg(feature = "gamepad")]
+#[allow(clippy::wildcard_enum_match_arm)]
+const fn convert_gamepad_button(button: Button) -> event::GamepadButton {
+    match button {
+        Button::South => event::GamepadButton::South,
+        Button::East => event::GamepadButton::East,
+        Button::North => event::GamepadButton::North,
+        Button::West => event::GamepadButton::West,
+        Button::LeftTrigger => event::GamepadButton::LeftTrigger,
+        Button::RightTrigger => event::GamepadButton::RightTrigger,
+        Button::DPadUp => event::GamepadButton::DPadUp,
+        Button::DPadDown => event::GamepadButton::DPadDown,
+        Button::DPadLeft => event::GamepadButton::DPadLeft,
+        Button::DPadRight => event::GamepadButton::DPadRight,
+        Button::Select => event::GamepadButton::Select,
+        Button::Start => event::GamepadButton::Start,
+        _ => event::GamepadButton::Other,
+    }
+}
+
+/// Convert a gamepad axis to a gamepad axis supported by the engine.
+#[cfg(feature = "gamepad")]
+#[allow(clippy::wildcard_enum_match_arm)]
+const fn convert_gamepad_axis(axis: Axis) -> event::GamepadAxis {
+    match axis {
+        Axis::LeftStickX => event::GamepadAxis::LeftStickX,
+        Axis::LeftStickY => event::GamepadAxis::LeftStickY,
+        Axis::RightStickX => event::GamepadAxis::RightStickX,
+        Axis::RightStickY => event::GamepadAxis::RightStickY,
+        _ => event::GamepadAxis::Other,
+    }
+}
+
+#[cfg(all(test, feature = "gamepad"))]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod convert_gamepad_event {
+        use super::*;
+
+        #[test]
+        fn buttons() {
+            assert_eq!(
+                convert_gamepad_event(gamepad::Event::ButtonPressed(Button::South)),
+                Some(event::Input::GamepadButton {
+                    button: event::GamepadButton::South,
+                    pressed: true,
+                })
+            );
+
+            assert_eq!(
+                convert_gamepad_event(gamepad::Event::ButtonReleased(Button::Mode)),
+                Some(event::Input::GamepadButton {
+                    button: event::GamepadButton::Other,
+                    pressed: false,
+                })
+            );
+        }
+
+        #[test]
+        fn axes() {
+            assert_eq!(
+                convert_gamepad_event(gamepad::Event::AxisChanged(Axis::LeftStickY, -0.5)),
+                Some(event::Input::GamepadAxis {
+                    axis: event::GamepadAxis::LeftStickY,
+                    value: -0.5,
+                })
+            );
+        }
+
+        #[test]
+        fn connection() {
+            assert_eq!(convert_gamepad_event(gamepad::Event::Connected), None);
+        }
+    }
+}
@@ -1,78 +1,270 @@
 //! The renderer implementation for the coffee backend.
 
-use crate::{config, widget};
-use coffee::graphics::{self, Frame, Mesh, Point};
-use common::{Color, Component, GameState, Shape};
-use std::time::Instant;
-
-/// Handles rendering frames to the screen.
-#[derive(Debug)]
-pub struct Renderer {
-    /// The configuration of the renderer.
-    config: config::Renderer,
-
-    /// A cache of the timestamp the last step finished.
-    ///
-    /// This is used to adhere to any configured FPS limits.
-    last_step_timestamp: Instant,
+use crate::{backend::renderer::Renderer, config, widget};
+use coffee::graphics::{self, Font, Frame, Gpu, Mesh, Point, Target, Transformation};
+use common::{Border, Canvas, Color, Component, GameState, Shape, Viewport};
+use std::{borrow::Cow, fmt, mem, time::Instant};
+
+/// The state of the renderer specific to the coffee backend.
+#[derive(Default)]
+pub struct State {
+    /// Whether or not the window is currently in fullscreen mode.
+    pub(crate) is_fullscreen: bool,
+
+    /// The font used to render text, loaded once the GPU is available.
+    font: Option<Font>,
+
+    /// The offscreen canvas frames are captured in, created on the first
+    /// capture.
+    offscreen: Option<graphics::Canvas>,
+}
 
-    /// A cache based off the FPS configuration.
-    ///
-    /// This is used to adhere to any configured FPS limits.
-    minimum_nanoseconds_between_renders: u64,
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("is_fullscreen", &self.is_fullscreen)
+            .field("font", &self.font.as_ref().map(|_| "coffee::Font"))
+            .field(
+                "offscreen",
+                &self.offscreen.as_ref().map(|_| "coffee::Canvas"),
+            )
+            .finish()
+    }
 }
 
-impl Renderer {
-    /// Render the state of the game to the screen.
-    pub fn run(&mut self, frame: &mut Frame<'_>, state: &GameState) {
+impl Renderer<State> {
+    /// Load the font used to render text onto the GPU.
+    ///
+    /// Until the font is loaded, text is not rendered. Coffee has no font of
+    /// its own, so text is never rendered if there is no font to load.
+    pub fn load_font(&mut self, gpu: &mut Gpu) -> coffee::Result<()> {
+        let source = match self.font_source() {
+            Some(source) => source,
+            None => {
+                log::warn!("no font found, text is not rendered");
+                return Ok(());
+            }
+        };
+
+        // Coffee borrows the font for as long as the game runs, so a font read
+        // from a file is leaked. It is only loaded once.
+        let bytes: &'static [u8] = match source {
+            Cow::Borrowed(bytes) => bytes,
+            Cow::Owned(bytes) => Box::leak(bytes.into_boxed_slice()),
+        };
+
+        self.backend.font = Some(Font::from_bytes(gpu, bytes)?);
+
+        Ok(())
+    }
+
+    /// Render the state of the game to the screen, within the given viewport.
+    ///
+    /// If a focus ring is configured, it is drawn around the focused widget.
+    pub fn run(
+        &mut self,
+        frame: &mut Frame<'_>,
+        state: &GameState,
+        canvas: Canvas,
+        viewport: Viewport,
+        focus_ring: Option<config::FocusRing>,
+    ) {
         // We're allowed to render. Record the timestamp for future render
         // decisions.
-        self.last_step_timestamp = Instant::now();
+        self.frame_rendered(Instant::now());
 
-        self.render_game_state(frame, state)
-    }
+        self.render_game_state(&mut frame.as_target(), state, canvas, viewport, focus_ring);
 
-    /// Should the renderer render to the screen, based on the max FPS settings?
-    pub fn should_run(&self) -> bool {
-        if self.minimum_nanoseconds_between_renders == 0 {
-            return true;
+        if self.take_capture_request() {
+            // The window is sized in whole physical pixels.
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::as_conversions
+            )]
+            let size = (frame.width() as u16, frame.height() as u16);
+
+            self.capture_frame(frame.gpu(), size, state, canvas, viewport, focus_ring);
         }
+    }
 
-        let last_step_duration = self.last_step_timestamp.elapsed();
+    /// Render the state of the game to an offscreen canvas of the given size,
+    /// and pass its pixels to the frame capture callback.
+    ///
+    /// Coffee can't read back the pixels of the window itself, so the frame is
+    /// drawn a second time. The offscreen canvas is kept for later captures,
+    /// unless the size of the window changes.
+    fn capture_frame(
+        &mut self,
+        gpu: &mut Gpu,
+        (width, height): (u16, u16),
+        state: &GameState,
+        canvas: Canvas,
+        viewport: Viewport,
+        focus_ring: Option<config::FocusRing>,
+    ) {
+        let mut offscreen = match self.backend.offscreen.take() {
+            Some(offscreen) if offscreen.width() == width && offscreen.height() == height => {
+                offscreen
+            }
+            _ => match graphics::Canvas::new(gpu, width, height) {
+                Ok(offscreen) => offscreen,
+                Err(err) => {
+                    log::error!("unable to capture frame: {}", err);
+                    return;
+                }
+            },
+        };
 
-        #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
-        let last_step_nanoseconds = last_step_duration.as_nanos() as u64;
+        self.render_game_state(
+            &mut offscreen.as_target(gpu),
+            state,
+            canvas,
+            viewport,
+            focus_ring,
+        );
+
+        let pixels = offscreen.read_pixels(gpu).to_rgba().into_raw();
+        self.backend.offscreen = Some(offscreen);
+
+        self.frame_captured(crate::Frame {
+            width,
+            height,
+            pixels,
+        });
+    }
 
-        last_step_nanoseconds >= self.minimum_nanoseconds_between_renders
+    /// Should the renderer render to the screen, based on the max FPS settings?
+    pub fn should_run(&self) -> bool {
+        self.should_render_at(Instant::now())
     }
 
     /// Render the state of the game to the screen.
-    fn render_game_state(&self, frame: &mut Frame<'_>, state: &GameState) {
-        frame.clear(graphics::Color {
-            r: 0.1,
-            g: 0.2,
-            b: 0.3,
+    fn render_game_state(
+        &mut self,
+        target: &mut Target<'_>,
+        state: &GameState,
+        canvas: Canvas,
+        viewport: Viewport,
+        focus_ring: Option<config::FocusRing>,
+    ) {
+        // Anything outside of the viewport is rendered black.
+        target.clear(graphics::Color {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
             a: 1.0,
         });
 
-        for widget_with_position in state.widgets() {
+        self.render_background(target, canvas, viewport);
+
+        // Widgets are drawn at their own coordinates, and transformed to fit
+        // the viewport.
+        let dpi = canvas.density();
+        let offset = graphics::Vector::new(viewport.x * dpi, viewport.y * dpi);
+        let transformation =
+            Transformation::translate(offset) * Transformation::scale(viewport.scale);
+
+        self.render_widgets(
+            &mut target.transform(transformation),
+            state,
+            canvas,
+            focus_ring,
+        );
+        Self::render_letterbox(target, canvas, viewport);
+    }
+
+    /// Render all visible widgets, including the focus ring around the focused
+    /// widget, and the bounds of all widgets, if configured.
+    fn render_widgets(
+        &mut self,
+        target: &mut Target<'_>,
+        state: &GameState,
+        canvas: Canvas,
+        focus_ring: Option<config::FocusRing>,
+    ) {
+        // The cache is moved out while rendering, as rendering a component
+        // needs mutable access to the renderer.
+        let mut cache = mem::take(&mut self.components);
+
+        for (plugin, name, widget_with_position) in state.named_widgets_in_render_order() {
             if !widget_with_position.is_visible() {
                 continue;
             }
 
-            // TODO: remove clone
-            let state = widget_with_position.state().clone().into();
             let coordinates = widget_with_position.coordinates();
 
-            for component in widget::components(&state) {
-                self.render_component(frame, &component, coordinates);
+            for component in cache.components(plugin, name, widget_with_position) {
+                self.render_component(target, canvas, component, coordinates);
+            }
+
+            let focus_ring = focus_ring.filter(|_| widget_with_position.focussed());
+            if focus_ring.is_none() && !self.config.debug_bounds {
+                continue;
+            }
+
+            let state = widget_with_position.state().clone().into();
+
+            if let Some(ring) = focus_ring {
+                for component in widget::focus_ring(&state, ring) {
+                    self.render_component(target, canvas, &component, coordinates);
+                }
+            }
+
+            if self.config.debug_bounds {
+                for component in widget::debug_bounds(&state) {
+                    self.render_component(target, canvas, &component, coordinates);
+                }
             }
         }
+
+        cache.finish_frame();
+        self.components = cache;
+    }
+
+    /// Render the game background within the viewport.
+    fn render_background(&self, target: &mut Target<'_>, canvas: Canvas, viewport: Viewport) {
+        let mut mesh = Mesh::new();
+        mesh.fill(
+            graphics::Shape::Rectangle(physical_rectangle(
+                canvas,
+                (viewport.x, viewport.y, viewport.width, viewport.height),
+            )),
+            into_color(self.config.background_color),
+        );
+        mesh.draw(target);
+    }
+
+    /// Render black bars over the area of the canvas outside of the viewport.
+    ///
+    /// The bars are drawn on top of the widgets, clipping them to the
+    /// viewport.
+    fn render_letterbox(target: &mut Target<'_>, canvas: Canvas, viewport: Viewport) {
+        let (width, height) = canvas.dimensions_f32();
+        let bars = viewport.letterbox_bars(width, height);
+        if bars.is_empty() {
+            return;
+        }
+
+        let mut mesh = Mesh::new();
+        for bar in bars {
+            mesh.fill(
+                graphics::Shape::Rectangle(physical_rectangle(canvas, bar)),
+                into_color(Color::new(0.0, 0.0, 0.0, 1.0)),
+            );
+        }
+        mesh.draw(target);
     }
 
     /// Render a single component to the screen.
-    fn render_component(&self, frame: &mut Frame<'_>, component: &Component, (x, y): (f32, f32)) {
-        let dpi = if self.config.hidpi_mode { 2.0 } else { 1.0 };
+    fn render_component(
+        &mut self,
+        target: &mut Target<'_>,
+        canvas: Canvas,
+        component: &Component,
+        (x, y): (f32, f32),
+    ) {
+        let dpi = canvas.density();
 
         let (x_rel, y_rel) = component.coordinates;
 
@@ -82,82 +274,280 @@ impl Renderer {
         x += x_rel * dpi;
         y += y_rel * dpi;
 
+        // The points of the shape are rotated around the origin of the
+        // component, before they are positioned on the screen.
+        let to_point = |&point: &(f32, f32)| {
+            let (x_point, y_point) = component.rotate(point);
+            Point::new(x + x_point * dpi, y + y_point * dpi)
+        };
+
         let mesh = match component.shape {
             Shape::Circle {
                 radius,
                 fill,
                 border,
             } => {
+                let center = to_point(&(radius, radius));
                 let radius = radius * dpi;
 
-                let shape = graphics::Shape::Circle {
-                    center: Point::new(x + radius, y + radius),
-                    radius,
-                };
+                let shape = graphics::Shape::Circle { center, radius };
 
                 let mut mesh = Mesh::new();
-                mesh.fill(shape, into_color(fill));
+                stroke_glow(&mut mesh, component, border, dpi, || {
+                    graphics::Shape::Circle { center, radius }
+                });
+                mesh.fill(shape, into_color(component.tinted(fill)));
 
                 if let Some(border) = border {
                     // Make sure the border falls inside the circle's radius.
                     let border_radius = radius - border.width / dpi;
 
                     let shape = graphics::Shape::Circle {
-                        center: Point::new(x + radius, y + radius),
+                        center,
                         radius: border_radius,
                     };
 
-                    mesh.stroke(shape, into_color(border.color), border.width);
+                    mesh.stroke(
+                        shape,
+                        into_color(component.tinted(border.color)),
+                        border.width,
+                    );
                 }
 
                 mesh
             }
 
-            Shape::Rectangle {
-                width,
-                height,
+            Shape::Ring {
+                outer_radius,
                 color,
+                ..
+            } => {
+                let center = to_point(&(outer_radius, outer_radius));
+
+                // The ring is drawn as a single stroke, leaving its center
+                // empty.
+                let mut mesh = Mesh::new();
+                if let Some((radius, width)) = component.shape.ring_stroke() {
+                    mesh.stroke(
+                        graphics::Shape::Circle {
+                            center,
+                            radius: radius * dpi,
+                        },
+                        into_color(component.tinted(color)),
+                        width * dpi,
+                    );
+                }
+
+                mesh
+            }
+
+            Shape::Rectangle { color, border, .. } => {
+                // The corners take the rotation of the rectangle into account.
+                let points: Vec<_> = component
+                    .shape
+                    .corners()
+                    .iter()
+                    .flatten()
+                    .map(to_point)
+                    .collect();
+
+                let mut mesh = Mesh::new();
+                stroke_glow(&mut mesh, component, border, dpi, || {
+                    graphics::Shape::Polyline {
+                        points: closed(&points),
+                    }
+                });
+
+                let shape = graphics::Shape::Polyline { points };
+                mesh.fill(shape, into_color(component.tinted(color)));
+
+                if let Some(border) = border {
+                    // The border corners are inset, to make sure the border
+                    // falls inside the rectangle. The outline needs to return
+                    // to the first corner to close it.
+                    let corners = component.shape.border_corners();
+                    let mut points: Vec<_> = corners.iter().flatten().map(to_point).collect();
+                    let first = points.first().copied();
+                    points.extend(first);
+
+                    mesh.stroke(
+                        graphics::Shape::Polyline { points },
+                        into_color(component.tinted(border.color)),
+                        border.width * dpi,
+                    );
+                }
+
+                mesh
+            }
+
+            Shape::Triangle {
+                a,
+                b,
+                c,
+                fill,
+                border,
             } => {
-                let width = width * dpi;
-                let height = height * dpi;
-
-                let rect = graphics::Rectangle {
-                    x,
-                    y,
-                    width,
-                    height,
+                let mut points: Vec<_> = [a, b, c].iter().map(to_point).collect();
+
+                let shape = graphics::Shape::Polyline {
+                    points: points.clone(),
                 };
 
-                let shape = graphics::Shape::Rectangle(rect);
+                let mut mesh = Mesh::new();
+                stroke_glow(&mut mesh, component, border, dpi, || {
+                    graphics::Shape::Polyline {
+                        points: closed(&points),
+                    }
+                });
+                mesh.fill(shape, into_color(component.tinted(fill)));
+
+                if let Some(border) = border {
+                    // The border is centered on the edges of the triangle, and
+                    // needs to return to the first point to close the outline.
+                    let first = points.first().copied();
+                    points.extend(first);
+
+                    mesh.stroke(
+                        graphics::Shape::Polyline { points },
+                        into_color(component.tinted(border.color)),
+                        border.width,
+                    );
+                }
+
+                mesh
+            }
+
+            Shape::Line {
+                from,
+                to,
+                width,
+                color,
+            } => {
+                let points = vec![to_point(&from), to_point(&to)];
+
+                let mut mesh = Mesh::new();
+                mesh.stroke(
+                    graphics::Shape::Polyline { points },
+                    into_color(component.tinted(color)),
+                    width * dpi,
+                );
+                mesh
+            }
+
+            Shape::Polygon {
+                ref points,
+                fill,
+                border,
+            } => {
+                if points.len() < 3 {
+                    log::warn!("skipping polygon with {} point(s)", points.len());
+
+                    return;
+                }
+
+                let mut points: Vec<_> = points.iter().map(to_point).collect();
+
+                let shape = graphics::Shape::Polyline {
+                    points: points.clone(),
+                };
 
                 let mut mesh = Mesh::new();
-                mesh.fill(shape, into_color(color));
+                stroke_glow(&mut mesh, component, border, dpi, || {
+                    graphics::Shape::Polyline {
+                        points: closed(&points),
+                    }
+                });
+                mesh.fill(shape, into_color(component.tinted(fill)));
+
+                if let Some(border) = border {
+                    // The border is centered on the edges of the polygon, and
+                    // needs to return to the first point to close the outline.
+                    let first = points.first().copied();
+                    points.extend(first);
+
+                    mesh.stroke(
+                        graphics::Shape::Polyline { points },
+                        into_color(component.tinted(border.color)),
+                        border.width,
+                    );
+                }
+
                 mesh
             }
+
+            Shape::Text {
+                ref content,
+                size,
+                color,
+            } => {
+                // Text starts at the origin of the component, which stays in
+                // place. The text itself is not rotated.
+                if let Some(font) = &mut self.backend.font {
+                    font.add(graphics::Text {
+                        content,
+                        position: Point::new(x, y),
+                        size: canvas.scaled_font_size(size),
+                        color: into_color(component.tinted(color)),
+                        ..graphics::Text::default()
+                    });
+
+                    font.draw(target);
+                }
+
+                return;
+            }
         };
 
-        mesh.draw(&mut frame.as_target());
+        mesh.draw(target);
+    }
+}
+
+/// Convert a rectangle `(x, y, width, height)` on the canvas to a rectangle in
+/// physical pixels.
+fn physical_rectangle(
+    canvas: Canvas,
+    (x, y, width, height): (f32, f32, f32, f32),
+) -> graphics::Rectangle<f32> {
+    let dpi = canvas.density();
+
+    graphics::Rectangle {
+        x: x * dpi,
+        y: y * dpi,
+        width: width * dpi,
+        height: height * dpi,
     }
 }
 
+/// Stroke the glow of a border (if any) along the outline of a shape.
+///
+/// The glow is stroked before the shape is filled, see
+/// [`Border::glow_strokes`].
+fn stroke_glow(
+    mesh: &mut Mesh,
+    component: &Component,
+    border: Option<Border>,
+    dpi: f32,
+    outline: impl Fn() -> graphics::Shape,
+) {
+    for stroke in border.iter().flat_map(Border::glow_strokes) {
+        mesh.stroke(
+            outline(),
+            into_color(component.tinted(stroke.color)),
+            stroke.width * dpi,
+        );
+    }
+}
+
+/// Close an outline by returning to its first point.
+fn closed(points: &[Point]) -> Vec<Point> {
+    points.iter().chain(points.first()).copied().collect()
+}
+
 /// Convert our color struct to Coffee's one.
+///
+/// Coffee blends using straight (non-premultiplied) alpha, so the color is
+/// passed as-is.
 const fn into_color(color: Color) -> graphics::Color {
     let Color { r, g, b, a } = color;
     graphics::Color { r, g, b, a }
 }
-
-impl From<config::Renderer> for Renderer {
-    fn from(config: config::Renderer) -> Self {
-        let minimum_nanoseconds_between_renders = match config.max_frames_per_second {
-            #[allow(clippy::integer_division, clippy::integer_arithmetic)]
-            Some(fps) => 1_000_000_000 / u64::from(fps),
-            None => 0,
-        };
-
-        Self {
-            config,
-            last_step_timestamp: Instant::now(),
-            minimum_nanoseconds_between_renders,
-        }
-    }
-}
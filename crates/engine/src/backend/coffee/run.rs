@@ -2,16 +2,17 @@
 //!
 //! Only this module explicitly depends on `coffee` types, in theory.
 
-use crate::{error, Builder, Engine, Error};
+use super::input;
+use crate::{Builder, Engine, Error};
 use coffee::{
-    graphics::{Frame, Window, WindowSettings},
-    input::{keyboard::KeyCode, mouse::Button, KeyboardAndMouse},
+    graphics::{CursorIcon, Frame, Window, WindowSettings},
+    input::{keyboard::KeyCode, mouse::Button},
     load::Task,
     Game, Timer,
 };
 use common::{event, Event, Key};
 use once_cell::sync::OnceCell;
-use std::{collections::HashSet, convert::TryInto};
+use std::collections::HashSet;
 
 /// A horrible hack to make Coffee work with our current initialization set-up.
 pub static mut BUILDER: OnceCell<Builder> = OnceCell::new();
@@ -23,19 +24,12 @@ pub static mut BUILDER: OnceCell<Builder> = OnceCell::new();
 /// read more about why this is.
 pub fn run(_: Engine) -> Result<(), Error> {
     let config = unsafe { BUILDER.get_unchecked() };
-    let (width, height) = config.canvas.dimensions();
-
-    let width = (width.saturating_mul(2))
-        .try_into()
-        .map_err(|_| error::Builder::WindowSize(width))?;
-
-    let height = (height.saturating_mul(2))
-        .try_into()
-        .map_err(|_| error::Builder::WindowSize(height))?;
 
+    // Coffee sizes the window in physical pixels, see the documentation of
+    // `render_component()`.
     let window = WindowSettings {
         title: "Vienna: work in progress".to_owned(),
-        size: (width, height),
+        size: config.canvas().physical_dimensions(),
         resizable: false,
         fullscreen: false,
         maximized: false,
@@ -48,53 +42,96 @@ pub fn run(_: Engine) -> Result<(), Error> {
 impl Game for Engine {
     const TICKS_PER_SECOND: u16 = 100;
 
-    type Input = KeyboardAndMouse;
+    type Input = input::Input;
     type LoadingScreen = (); // No loading screen
 
     fn load(_window: &Window) -> Task<Self> {
-        let builder = match unsafe { BUILDER.get_mut() } {
-            Some(builder) => builder,
-            None => todo!("logging"),
+        // Coffee gives us no way to surface our own errors while loading, so
+        // any failure is logged, and the engine starts without plugins.
+        let engine = match unsafe { BUILDER.get_mut() } {
+            Some(builder) => match builder.build_inner() {
+                Ok(engine) => engine,
+                Err(err) => {
+                    log::error!("unable to build engine: {}", err);
+                    Self::default()
+                }
+            },
+            None => {
+                log::error!("missing engine builder");
+                Self::default()
+            }
         };
 
-        #[allow(clippy::match_wild_err_arm)]
-        let engine = match builder.build_inner() {
-            Ok(engine) => engine,
-            Err(_) => todo!("logging"),
-        };
+        // The font is loaded once, and cached by the renderer.
+        Task::using_gpu(move |gpu| {
+            let mut engine = engine;
+            engine.renderer.load_font(gpu)?;
 
-        Task::succeed(|| engine)
+            Ok(engine)
+        })
     }
 
-    fn interact(&mut self, input: &mut Self::Input, _: &mut Window) {
+    fn interact(&mut self, input: &mut Self::Input, window: &mut Window) {
+        // Apply any fullscreen changes requested by plugins.
+        if self.renderer.backend.is_fullscreen != self.config.fullscreen {
+            window.toggle_fullscreen();
+            self.renderer.backend.is_fullscreen = self.config.fullscreen;
+        }
+
+        // The window is sized in physical pixels, see the documentation of
+        // `render_component()`.
+        let dpi = self.config.canvas.density();
+        self.config
+            .resize_window(window.width() / dpi, window.height() / dpi);
+
         let mut events = vec![];
 
+        // The game might be letterboxed, so pointer coordinates are converted
+        // to be relative to the viewport.
+        let viewport = self.config.viewport();
+
         // Handle cursor input if needed.
         if input.mouse().is_cursor_within_window() {
             // mouse position
             let position = input.mouse().cursor_position();
 
-            // divided by two, because of Coffee's issue with high-DPI (see
-            // documentation for `render_component()`).
-            let (x, y) = (position.x / 2.0, position.y / 2.0);
+            // divided by the pixel density, because of Coffee's issue with
+            // high-DPI (see documentation for `render_component()`).
+            let dpi = self.config.canvas.density();
+            let (x, y) = viewport.to_viewport_coordinates(position.x / dpi, position.y / dpi);
 
             let event = Event::Input(event::Input::Pointer(x, y));
             events.push(event);
 
             for button in &[Button::Left, Button::Middle, Button::Right] {
+                // A release is sent before the click it is part of, so that
+                // widgets know a drag ended before the click is handled.
+                let pressed = input.mouse().is_button_pressed(*button);
+                if !pressed
+                    && self
+                        .updater
+                        .backend
+                        .pressed_buttons
+                        .remove(&convert_button(button))
+                {
+                    let button = convert_button(button);
+                    let event = Event::Input(event::Input::MouseRelease { button, x, y });
+
+                    events.push(event);
+                }
+
                 for point in input.mouse().button_clicks(*button) {
                     let button = convert_button(button);
-                    let event = Event::Input(event::Input::MouseClick {
-                        button,
-                        x: point.x / 2.0,
-                        y: point.y / 2.0,
-                    });
+                    let (x, y) = viewport.to_viewport_coordinates(point.x / dpi, point.y / dpi);
+                    let event = Event::Input(event::Input::MouseClick { button, x, y });
 
                     events.push(event);
                 }
 
-                if input.mouse().is_button_pressed(*button) {
+                if pressed {
                     let button = convert_button(button);
+                    self.updater.backend.pressed_buttons.insert(button.clone());
+
                     let event = Event::Input(event::Input::MousePress { button, x, y });
 
                     events.push(event);
@@ -102,68 +139,82 @@ impl Game for Engine {
             }
         }
 
-        if !input.keyboard().pressed_keys().is_empty() {
-            let mut keys = HashSet::new();
-            for pressed_key in input.keyboard().pressed_keys() {
-                let key = match pressed_key {
-                    // letter keys
-                    KeyCode::A => Key::A,
-                    KeyCode::B => Key::B,
-                    KeyCode::D => Key::D,
-                    KeyCode::E => Key::E,
-                    KeyCode::G => Key::G,
-                    KeyCode::Q => Key::Q,
-                    KeyCode::R => Key::R,
-                    KeyCode::S => Key::S,
-                    KeyCode::W => Key::W,
-
-                    // other keys
-                    KeyCode::Equals if input.keyboard().is_key_pressed(KeyCode::LShift) => {
-                        Key::Plus
-                    }
-                    KeyCode::Minus => Key::Minus,
-
-                    // modifier keys
-                    KeyCode::LShift | KeyCode::RShift => Key::Shift,
-                    KeyCode::LControl | KeyCode::RControl => Key::Ctrl,
-
-                    // Quit engine.
-                    KeyCode::Escape => {
-                        self.updater.is_finished = true;
-                        return;
-                    }
-
-                    // All other keys are ignored for now.
-                    _ => break,
-                };
-
-                keys.insert(key);
+        // Toggle the game simulation pause.
+        if input.keyboard().was_key_released(KeyCode::Pause) {
+            self.updater.is_paused = !self.updater.is_paused;
+        }
+
+        let mut keys = HashSet::new();
+        let shift = input.keyboard().is_key_pressed(KeyCode::LShift);
+
+        for pressed_key in input.keyboard().pressed_keys() {
+            // Quit engine.
+            if *pressed_key == KeyCode::Escape {
+                self.updater.is_finished = true;
+                return;
             }
 
-            let event = Event::Input(event::Input::Keyboard { keys });
-            events.push(event)
+            let key = match convert_key(*pressed_key, shift) {
+                Some(key) => key,
+
+                // All other keys are ignored for now.
+                None => break,
+            };
+
+            keys.insert(key);
+        }
+
+        // Key presses and releases are only reported once, while held keys
+        // are reported for as long as they are pressed.
+        let transitions = event::key_transitions(&self.updater.backend.pressed_keys, &keys);
+        events.extend(transitions.into_iter().map(Event::Input));
+
+        if !keys.is_empty() {
+            let keys = keys.clone();
+            events.push(Event::Input(event::Input::Keyboard { keys }));
         }
 
+        self.updater.backend.pressed_keys = keys;
+
+        // Gamepad input is passed on as-is.
+        let gamepad_events = input.gamepad_events().iter().cloned();
+        events.extend(gamepad_events.map(Event::Input));
+
         for event in events {
-            if !self.updater.active_events.contains(&event) {
-                self.updater.active_events.push(event);
+            if !self.updater.backend.active_events.contains(&event) {
+                self.updater.backend.active_events.push(event);
             }
         }
     }
 
     fn update(&mut self, _: &Window) {
-        let canvas = self.config.canvas;
+        // Plugins only know about the part of the canvas the game is rendered
+        // in.
+        let canvas = self.config.viewport().into();
         let handler = self.plugin_handler.as_mut();
 
-        let result = self.updater.run(&mut self.game_state, canvas, handler);
-
-        if result.is_err() {
-            todo!("logging")
+        match self.updater.run(&mut self.game_state, canvas, handler) {
+            Ok(commands) => self.process_commands(commands),
+            Err(err) => log::error!("unable to update game state: {}", err),
         }
     }
 
     fn draw(&mut self, frame: &mut Frame<'_>, _timer: &Timer) {
-        self.renderer.run(frame, &self.game_state)
+        self.renderer.run(
+            frame,
+            &self.game_state,
+            self.config.window,
+            self.config.viewport(),
+            self.config.focus_ring,
+        )
+    }
+
+    fn cursor_icon(&self) -> CursorIcon {
+        if self.config.cursor_hidden {
+            CursorIcon::Hidden
+        } else {
+            CursorIcon::Default
+        }
     }
 
     fn should_draw(&self) -> bool {
@@ -175,6 +226,90 @@ impl Game for Engine {
     }
 }
 
+/// Convert a key code to a key supported by the engine.
+///
+/// Returns `None` if the key is not supported.
+#[allow(clippy::wildcard_enum_match_arm)]
+const fn convert_key(code: KeyCode, shift: bool) -> Option<Key> {
+    let key = match code {
+        // letter keys
+        KeyCode::A => Key::A,
+        KeyCode::B => Key::B,
+        KeyCode::C => Key::C,
+        KeyCode::D => Key::D,
+        KeyCode::E => Key::E,
+        KeyCode::F => Key::F,
+        KeyCode::G => Key::G,
+        KeyCode::H => Key::H,
+        KeyCode::I => Key::I,
+        KeyCode::J => Key::J,
+        KeyCode::K => Key::K,
+        KeyCode::L => Key::L,
+        KeyCode::M => Key::M,
+        KeyCode::N => Key::N,
+        KeyCode::O => Key::O,
+        KeyCode::P => Key::P,
+        KeyCode::Q => Key::Q,
+        KeyCode::R => Key::R,
+        KeyCode::S => Key::S,
+        KeyCode::T => Key::T,
+        KeyCode::U => Key::U,
+        KeyCode::V => Key::V,
+        KeyCode::W => Key::W,
+        KeyCode::X => Key::X,
+        KeyCode::Y => Key::Y,
+        KeyCode::Z => Key::Z,
+
+        // digit keys
+        KeyCode::Key0 => Key::Digit0,
+        KeyCode::Key1 => Key::Digit1,
+        KeyCode::Key2 => Key::Digit2,
+        KeyCode::Key3 => Key::Digit3,
+        KeyCode::Key4 => Key::Digit4,
+        KeyCode::Key5 => Key::Digit5,
+        KeyCode::Key6 => Key::Digit6,
+        KeyCode::Key7 => Key::Digit7,
+        KeyCode::Key8 => Key::Digit8,
+        KeyCode::Key9 => Key::Digit9,
+
+        // other keys
+        KeyCode::Equals if shift => Key::Plus,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::Minus => Key::Minus,
+        KeyCode::Return => Key::Enter,
+        KeyCode::Space => Key::Space,
+        KeyCode::Tab => Key::Tab,
+
+        // arrow keys
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+
+        // numpad keys
+        KeyCode::Numpad0 => Key::Digit0,
+        KeyCode::Numpad1 => Key::Digit1,
+        KeyCode::Numpad2 => Key::Digit2,
+        KeyCode::Numpad3 => Key::Digit3,
+        KeyCode::Numpad4 => Key::Digit4,
+        KeyCode::Numpad5 => Key::Digit5,
+        KeyCode::Numpad6 => Key::Digit6,
+        KeyCode::Numpad7 => Key::Digit7,
+        KeyCode::Numpad8 => Key::Digit8,
+        KeyCode::Numpad9 => Key::Digit9,
+        KeyCode::Add => Key::Plus,
+        KeyCode::Subtract => Key::Minus,
+
+        // modifier keys
+        KeyCode::LShift | KeyCode::RShift => Key::Shift,
+        KeyCode::LControl | KeyCode::RControl => Key::Ctrl,
+
+        _ => return None,
+    };
+
+    Some(key)
+}
+
 fn convert_button(button: &coffee::input::mouse::Button) -> event::MouseButton {
     match button {
         Button::Left => event::MouseButton::Left,
@@ -183,3 +318,55 @@ fn convert_button(button: &coffee::input::mouse::Button) -> event::MouseButton {
         _ => event::MouseButton::Other,
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod convert_key {
+        use super::*;
+
+        #[test]
+        fn plus_and_minus() {
+            assert_eq!(convert_key(KeyCode::Equals, true), Some(Key::Plus));
+            assert_eq!(convert_key(KeyCode::Equals, false), None);
+            assert_eq!(convert_key(KeyCode::Minus, false), Some(Key::Minus));
+        }
+
+        #[test]
+        fn arrows() {
+            assert_eq!(convert_key(KeyCode::Up, false), Some(Key::Up));
+            assert_eq!(convert_key(KeyCode::Down, false), Some(Key::Down));
+            assert_eq!(convert_key(KeyCode::Left, false), Some(Key::Left));
+            assert_eq!(convert_key(KeyCode::Right, false), Some(Key::Right));
+        }
+
+        #[test]
+        fn letters() {
+            assert_eq!(convert_key(KeyCode::A, false), Some(Key::A));
+            assert_eq!(convert_key(KeyCode::M, false), Some(Key::M));
+            assert_eq!(convert_key(KeyCode::Z, false), Some(Key::Z));
+        }
+
+        #[test]
+        fn digits() {
+            assert_eq!(convert_key(KeyCode::Key0, false), Some(Key::Digit0));
+            assert_eq!(convert_key(KeyCode::Key9, false), Some(Key::Digit9));
+        }
+
+        #[test]
+        fn escape_and_tab() {
+            assert_eq!(convert_key(KeyCode::Escape, false), Some(Key::Escape));
+            assert_eq!(convert_key(KeyCode::Tab, false), Some(Key::Tab));
+        }
+
+        #[test]
+        fn numpad() {
+            assert_eq!(convert_key(KeyCode::Add, false), Some(Key::Plus));
+            assert_eq!(convert_key(KeyCode::Subtract, false), Some(Key::Minus));
+            assert_eq!(convert_key(KeyCode::Numpad0, false), Some(Key::Digit0));
+            assert_eq!(convert_key(KeyCode::Numpad9, false), Some(Key::Digit9));
+        }
+    }
+}
@@ -1,52 +1,209 @@
 //! The updater implementation for the coffee backend.
 
-use crate::{config, error, plugin::Handler, widget};
-use common::{Canvas, Event, GameState};
-
-/// Handles updating the game state.
-#[derive(Debug)]
-pub struct Updater {
-    /// The configuration of the updater.
-    pub(crate) config: config::Updater,
+use crate::{backend::updater::Updater, error, plugin::Handler};
+use common::{event, Canvas, Command, Event, GameState, Key};
+use std::{collections::HashSet, mem};
 
+/// The state of the updater specific to the coffee backend.
+#[derive(Debug, Default)]
+pub struct State {
     /// A list of events that are currently active. This list is updated when
     /// new player input is received.
     ///
     /// When the updater runs, it drains all existing events.
     pub(crate) active_events: Vec<Event>,
 
-    /// Returns true if the game should be closed.
-    pub(crate) is_finished: bool,
+    /// The keys pressed during the previous input, used to detect which keys
+    /// were pressed or released since.
+    pub(crate) pressed_keys: HashSet<Key>,
+
+    /// The mouse buttons pressed during the previous input, used to detect
+    /// which buttons were released since.
+    pub(crate) pressed_buttons: HashSet<event::MouseButton>,
 }
 
-impl Updater {
+impl Updater<State> {
     /// Update the game state.
+    ///
+    /// Returns the commands issued by the plugins for the engine to process.
     pub fn run(
         &mut self,
         state: &mut GameState,
         canvas: Canvas,
         plugin_handler: &mut dyn Handler,
-    ) -> Result<(), error::Updater> {
-        let mut widget_events = vec![];
-        let input_events = &self.active_events;
-
-        for (name, widget) in state.widgets_mut() {
-            widget_events.append(&mut widget::update(name, widget, input_events))
+    ) -> Result<Vec<Command>, error::Updater> {
+        // Input received while paused is discarded, to prevent a flood of
+        // events once the game resumes.
+        if self.is_paused {
+            self.backend.active_events.clear();
+            return Ok(vec![]);
         }
 
-        plugin_handler.run_plugins(state, canvas, &widget_events)?;
-
-        self.active_events.clear();
-        Ok(())
+        let events = mem::take(&mut self.backend.active_events);
+        self.update_game_state(state, canvas, events, plugin_handler)
     }
 }
 
-impl From<config::Updater> for Updater {
-    fn from(config: config::Updater) -> Self {
-        Self {
-            config,
-            active_events: vec![],
-            is_finished: false,
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::{
+        config,
+        recorder::{Mode, Recorder},
+    };
+    use common::{serde_json, widget, Key, PluginState, Value};
+    use std::{collections::HashMap, path::Path, time::Duration};
+    use tempfile::NamedTempFile;
+
+    mod run {
+        use super::*;
+
+        #[test]
+        fn paused() {
+            let canvas = Canvas::default();
+            let mut state = GameState::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+            handler.register_plugin(&mut state, Path::new("")).unwrap();
+
+            let mut updater = Updater::<State>::from(config::Updater {
+                paused: true,
+                ..config::Updater::default()
+            });
+
+            updater.run(&mut state, canvas, &mut handler).unwrap();
+            assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 0);
+
+            updater.is_paused = false;
+            updater.run(&mut state, canvas, &mut handler).unwrap();
+            assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 1);
+        }
+
+        #[test]
+        fn time() {
+            let canvas = Canvas::default();
+            let mut state = GameState::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+            handler.register_plugin(&mut state, Path::new("")).unwrap();
+
+            let mut updater = Updater::<State>::from(config::Updater::default());
+            updater.run(&mut state, canvas, &mut handler).unwrap();
+            updater.run(&mut state, canvas, &mut handler).unwrap();
+
+            let time = handler.as_mock().unwrap().plugins[0].time;
+            assert_eq!(time.delta(), Duration::from_millis(10));
+            assert_eq!(time.elapsed(), Duration::from_millis(10));
+        }
+
+        #[test]
+        fn event_order() {
+            let circle = |name, z_index| {
+                widget::Builder::new(name, widget::Kind::MovingCircle)
+                    .attribute("radius", 10.0)
+                    .z_index(z_index)
+                    .build()
+            };
+
+            let widgets: HashMap<_, _> = vec![circle("top", 1), circle("bottom", 0)]
+                .into_iter()
+                .collect();
+
+            let canvas = Canvas::default();
+            let mut state = GameState::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+            handler.register_plugin(&mut state, Path::new("")).unwrap();
+            handler.plugins[0].name = "test".to_owned();
+            state.register_plugin_state(
+                "test",
+                PluginState::new(HashMap::<String, Value>::new(), widgets),
+            );
+
+            let input = Event::Input(event::Input::Keyboard {
+                keys: vec![Key::Q].into_iter().collect(),
+            });
+
+            let mut updater = Updater::<State>::from(config::Updater::default());
+            updater.backend.active_events = vec![input.clone()];
+            updater.run(&mut state, canvas, &mut handler).unwrap();
+
+            let events = &handler.as_mock().unwrap().plugins[0].events;
+            let names: Vec<_> = events
+                .iter()
+                .map(|event| match event {
+                    Event::Input(_) => "input",
+                    Event::Widget { name, .. } => name.as_str(),
+                })
+                .collect();
+
+            // Input events come first, followed by the widget events in
+            // z-order.
+            assert_eq!(names, vec!["input", "bottom", "top"]);
+            assert_eq!(events[0], input);
+        }
+
+        #[test]
+        fn record_and_playback() {
+            let file = NamedTempFile::new().unwrap();
+            let path = file.path().to_owned();
+
+            let game_state = || {
+                let (name, widget) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                    .attribute("radius", 10.0)
+                    .build();
+
+                let mut widgets = HashMap::new();
+                widgets.insert(name, widget);
+
+                let mut state = GameState::default();
+                let plugin = PluginState::new(HashMap::<String, Value>::new(), widgets);
+                state.register_plugin_state("test", plugin);
+                state
+            };
+
+            let keys = |keys: Vec<Key>| {
+                let keys = keys.into_iter().collect();
+                Event::Input(event::Input::Keyboard { keys })
+            };
+
+            let session = vec![
+                vec![keys(vec![Key::E])],
+                vec![],
+                vec![keys(vec![Key::E, Key::R]), keys(vec![Key::Q])],
+                vec![keys(vec![Key::E])],
+            ];
+
+            let canvas = Canvas::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+
+            // record
+            let mut recorded_state = game_state();
+            let mut updater = Updater::<State>::from(config::Updater::default());
+            updater.recorder = Some(Recorder::new(Mode::Record(path.clone())).unwrap());
+
+            for events in session.clone() {
+                updater.backend.active_events = events;
+                updater
+                    .run(&mut recorded_state, canvas, &mut handler)
+                    .unwrap();
+            }
+
+            // playback
+            let mut replayed_state = game_state();
+            let mut updater = Updater::<State>::from(config::Updater::default());
+            updater.recorder = Some(Recorder::new(Mode::Playback(path)).unwrap());
+
+            for _ in &session {
+                // Input from the backend is ignored during playback.
+                updater.backend.active_events = vec![keys(vec![Key::Q])];
+                updater
+                    .run(&mut replayed_state, canvas, &mut handler)
+                    .unwrap();
+            }
+
+            let state = |state: &GameState| serde_json::to_value(state.get("test")).unwrap();
+
+            assert_eq!(state(&recorded_state), state(&replayed_state));
+            assert_ne!(state(&recorded_state), state(&game_state()));
         }
     }
 }
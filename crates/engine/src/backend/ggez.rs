@@ -4,6 +4,18 @@ mod renderer;
 mod run;
 mod updater;
 
-pub use renderer::Renderer;
-pub use run::run;
-pub use updater::Updater;
+use super::Backend;
+use crate::{Engine, Error};
+
+/// The "ggez" game engine backend.
+#[derive(Debug)]
+pub struct Ggez;
+
+impl Backend for Ggez {
+    type Renderer = super::renderer::Renderer<renderer::State>;
+    type Updater = super::updater::Updater<updater::State>;
+
+    fn run(engine: Engine) -> Result<(), Error> {
+        run::run(engine)
+    }
+}
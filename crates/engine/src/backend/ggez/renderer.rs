@@ -1,142 +1,440 @@
 //! The renderer implementation for the ggez backend.
 
-use crate::{config, widget};
-use common::{Color, Component, GameState, Shape};
+use crate::{backend::renderer::Renderer, config, widget, Frame};
+use common::{Border, Color, Component, GameState, Shape, Viewport};
 use ggez::{graphics, nalgebra, Context, GameResult};
 use std::time::Instant;
 
-/// Handles rendering frames to the screen.
-#[derive(Debug)]
-pub struct Renderer {
-    /// The configuration of the renderer.
-    pub(crate) config: config::Renderer,
-
-    /// A cache of the timestamp the last step finished.
-    ///
-    /// This is used to adhere to any configured FPS limits.
-    last_step_timestamp: Instant,
-
-    /// A cache based off the FPS configuration.
-    ///
-    /// This is used to adhere to any configured FPS limits.
-    minimum_nanoseconds_between_renders: u64,
+/// The state of the renderer specific to the ggez backend.
+#[derive(Debug, Default)]
+pub struct State {
+    /// The font used to render text, loaded on the first render.
+    font: Option<graphics::Font>,
 }
 
-impl Renderer {
+impl Renderer<State> {
     /// Render the state of the game to the screen.
     pub fn run(
         &mut self,
         ctx: &mut Context,
         state: &GameState,
+        viewport: Viewport,
+        focus_ring: Option<config::FocusRing>,
         _step_progress: f64,
     ) -> GameResult<()> {
         // Check if we are exceeding the configured max FPS
-        if !self.should_render() {
+        let now = Instant::now();
+        if !self.should_render_at(now) {
             return Ok(());
         }
 
         // We're allowed to render. Record the timestamp for future render
         // decisions.
-        self.last_step_timestamp = Instant::now();
+        self.frame_rendered(now);
 
-        render_game_state(ctx, state)
-    }
+        let font = self.font(ctx)?;
+        render_game_state(
+            ctx,
+            &self.config,
+            &mut self.components,
+            state,
+            viewport,
+            focus_ring,
+            font,
+        )?;
+
+        if self.take_capture_request() {
+            let image = graphics::screenshot(ctx)?;
 
-    /// Should the renderer render to the screen, based on the max FPS settings?
-    fn should_render(&self) -> bool {
-        if self.minimum_nanoseconds_between_renders == 0 {
-            return true;
+            self.frame_captured(Frame {
+                width: image.width(),
+                height: image.height(),
+                pixels: image.to_rgba8(ctx)?,
+            });
         }
 
-        let last_step_duration = self.last_step_timestamp.elapsed();
+        graphics::present(ctx)
+    }
 
-        #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
-        let last_step_nanoseconds = last_step_duration.as_nanos() as u64;
+    /// Get the font used to render text, loading it if it isn't cached yet.
+    ///
+    /// Without any font to load, the font built into ggez is used.
+    fn font(&mut self, ctx: &mut Context) -> GameResult<graphics::Font> {
+        if let Some(font) = self.backend.font {
+            return Ok(font);
+        }
 
-        last_step_nanoseconds >= self.minimum_nanoseconds_between_renders
+        let font = match self.font_source() {
+            Some(source) => graphics::Font::new_glyph_font_bytes(ctx, &source)?,
+            None => graphics::Font::default(),
+        };
+        self.backend.font = Some(font);
+
+        Ok(font)
     }
 }
 
 /// Render the state of the game to the screen.
-fn render_game_state(ctx: &mut Context, state: &GameState) -> GameResult<()> {
-    graphics::clear(ctx, [0.1, 0.2, 0.3, 1.0].into());
+fn render_game_state(
+    ctx: &mut Context,
+    config: &config::Renderer,
+    cache: &mut widget::ComponentCache,
+    state: &GameState,
+    viewport: Viewport,
+    focus_ring: Option<config::FocusRing>,
+    font: graphics::Font,
+) -> GameResult<()> {
+    // Anything outside of the viewport is rendered black.
+    graphics::clear(ctx, graphics::BLACK);
+
+    let background = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        graphics::Rect {
+            x: viewport.x,
+            y: viewport.y,
+            w: viewport.width,
+            h: viewport.height,
+        },
+        into_color(config.background_color),
+    )?;
 
-    for widget_with_position in state.widgets() {
+    graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+
+    for (plugin, name, widget_with_position) in state.named_widgets_in_render_order() {
         if !widget_with_position.is_visible() {
             continue;
         }
 
-        // TODO: remove clone
-        let widget = widget_with_position.widget().clone().into();
+        // Widgets are drawn at their own coordinates, and transformed to fit
+        // the viewport when drawn, see `render_component()`.
         let coordinates = widget_with_position.coordinates();
 
-        for component in widget::components(&widget) {
-            render_component(ctx, &component, coordinates);
+        for component in cache.components(plugin, name, widget_with_position) {
+            render_component(ctx, viewport, component, coordinates, font);
+        }
+
+        let focus_ring = focus_ring.filter(|_| widget_with_position.focussed());
+        if focus_ring.is_none() && !config.debug_bounds {
+            continue;
+        }
+
+        let widget = widget_with_position.state().clone().into();
+
+        if let Some(ring) = focus_ring {
+            for component in widget::focus_ring(&widget, ring) {
+                render_component(ctx, viewport, &component, coordinates, font);
+            }
+        }
+
+        if config.debug_bounds {
+            for component in widget::debug_bounds(&widget) {
+                render_component(ctx, viewport, &component, coordinates, font);
+            }
         }
     }
 
-    graphics::present(ctx)
+    cache.finish_frame();
+
+    // Widgets are clipped to the viewport, by drawing the letterbox on top.
+    let (width, height) = {
+        let screen = graphics::screen_coordinates(ctx);
+        (screen.w, screen.h)
+    };
+
+    for (x, y, w, h) in viewport.letterbox_bars(width, height) {
+        let bar = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect { x, y, w, h },
+            graphics::BLACK,
+        )?;
+
+        graphics::draw(ctx, &bar, graphics::DrawParam::default())?;
+    }
+
+    Ok(())
 }
 
 /// Render a single component to the screen.
-fn render_component(ctx: &mut Context, component: &Component, (mut x, mut y): (f32, f32)) {
+///
+/// The component is drawn at the given coordinates relative to the viewport,
+/// and scaled to fit the viewport.
+fn render_component(
+    ctx: &mut Context,
+    viewport: Viewport,
+    component: &Component,
+    (mut x, mut y): (f32, f32),
+    font: graphics::Font,
+) {
     let (x_rel, y_rel) = component.coordinates;
 
     x += x_rel;
     y += y_rel;
 
+    // The points of the shape are rotated around the origin of the component,
+    // before they are positioned on the screen.
+    let to_point = |&point: &(f32, f32)| {
+        let (x_point, y_point) = component.rotate(point);
+        nalgebra::Point2::new(x + x_point, y + y_point)
+    };
+
     let drawable = match component.shape {
-        Shape::Circle { radius, color } => graphics::Mesh::new_circle(
-            ctx,
-            graphics::DrawMode::fill(),
-            nalgebra::Point2::new(x, y),
-            radius.max(1.0),
-            2.0,
-            into_color(color),
-        ),
-        Shape::Rectangle {
+        Shape::Circle {
+            radius,
+            fill,
+            border,
+        } => {
+            let center = to_point(&(radius, radius));
+
+            let mut builder = graphics::MeshBuilder::new();
+            let result = stroke_glow(&mut builder, component, border, |builder, mode, color| {
+                builder.circle(mode, center, radius.max(1.0), 2.0, color);
+                Ok(())
+            });
+
+            builder.circle(
+                graphics::DrawMode::fill(),
+                center,
+                radius.max(1.0),
+                2.0,
+                into_color(component.tinted(fill)),
+            );
+
+            // Make sure the border falls inside the circle's radius.
+            if let Some(border) = border {
+                builder.circle(
+                    graphics::DrawMode::stroke(border.width),
+                    center,
+                    (radius - border.width / 2.0).max(1.0),
+                    2.0,
+                    into_color(component.tinted(border.color)),
+                );
+            }
+
+            result.and_then(|_| builder.build(ctx))
+        }
+        Shape::Ring {
+            outer_radius,
+            color,
+            ..
+        } => {
+            // The ring is drawn as a single stroke, leaving its center empty.
+            let (radius, width) = component.shape.ring_stroke().unwrap_or_default();
+
+            graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::stroke(width),
+                to_point(&(outer_radius, outer_radius)),
+                radius.max(1.0),
+                2.0,
+                into_color(component.tinted(color)),
+            )
+        }
+        Shape::Rectangle { color, border, .. } => {
+            // The corners take the rotation of the rectangle into account.
+            let points: Vec<_> = component
+                .shape
+                .corners()
+                .iter()
+                .flatten()
+                .map(to_point)
+                .collect();
+
+            let mut builder = graphics::MeshBuilder::new();
+            let mut result =
+                stroke_glow(&mut builder, component, border, |builder, mode, color| {
+                    builder.polygon(mode, &points, color).map(|_| ())
+                })
+                .and_then(|_| {
+                    builder
+                        .polygon(
+                            graphics::DrawMode::fill(),
+                            &points,
+                            into_color(component.tinted(color)),
+                        )
+                        .map(|_| ())
+                });
+
+            // The border corners are inset, to make sure the border falls
+            // inside the rectangle.
+            if let Some(border) = border {
+                let corners = component.shape.border_corners();
+                let points: Vec<_> = corners.iter().flatten().map(to_point).collect();
+
+                result = result.and_then(|_| {
+                    builder
+                        .polygon(
+                            graphics::DrawMode::stroke(border.width),
+                            &points,
+                            into_color(component.tinted(border.color)),
+                        )
+                        .map(|_| ())
+                });
+            }
+
+            result.and_then(|_| builder.build(ctx))
+        }
+        Shape::Triangle {
+            a,
+            b,
+            c,
+            fill,
+            border,
+        } => {
+            let points: Vec<_> = [a, b, c].iter().map(to_point).collect();
+
+            let mut builder = graphics::MeshBuilder::new();
+            let mut result =
+                stroke_glow(&mut builder, component, border, |builder, mode, color| {
+                    builder.polygon(mode, &points, color).map(|_| ())
+                })
+                .and_then(|_| {
+                    builder
+                        .polygon(
+                            graphics::DrawMode::fill(),
+                            &points,
+                            into_color(component.tinted(fill)),
+                        )
+                        .map(|_| ())
+                });
+
+            // The border is centered on the edges of the triangle.
+            if let Some(border) = border {
+                result = result.and_then(|_| {
+                    builder
+                        .polygon(
+                            graphics::DrawMode::stroke(border.width),
+                            &points,
+                            into_color(component.tinted(border.color)),
+                        )
+                        .map(|_| ())
+                });
+            }
+
+            result.and_then(|_| builder.build(ctx))
+        }
+        Shape::Line {
+            from,
+            to,
             width,
-            height,
             color,
-        } => graphics::Mesh::new_rectangle(
+        } => graphics::Mesh::new_line(
             ctx,
-            graphics::DrawMode::fill(),
-            graphics::Rect {
-                x,
-                y,
-                w: width,
-                h: height,
-            },
-            into_color(color),
+            &[to_point(&from), to_point(&to)],
+            width,
+            into_color(component.tinted(color)),
         ),
+        Shape::Polygon {
+            ref points,
+            fill,
+            border,
+        } => {
+            if points.len() < 3 {
+                log::warn!("skipping polygon with {} point(s)", points.len());
+
+                return;
+            }
+
+            let points: Vec<_> = points.iter().map(to_point).collect();
+
+            let mut builder = graphics::MeshBuilder::new();
+            let mut result =
+                stroke_glow(&mut builder, component, border, |builder, mode, color| {
+                    builder.polygon(mode, &points, color).map(|_| ())
+                })
+                .and_then(|_| {
+                    builder
+                        .polygon(
+                            graphics::DrawMode::fill(),
+                            &points,
+                            into_color(component.tinted(fill)),
+                        )
+                        .map(|_| ())
+                });
+
+            // The border is centered on the edges of the polygon.
+            if let Some(border) = border {
+                result = result.and_then(|_| {
+                    builder
+                        .polygon(
+                            graphics::DrawMode::stroke(border.width),
+                            &points,
+                            into_color(component.tinted(border.color)),
+                        )
+                        .map(|_| ())
+                });
+            }
+
+            result.and_then(|_| builder.build(ctx))
+        }
+        Shape::Text {
+            ref content,
+            size,
+            color,
+        } => {
+            // Text starts at the origin of the component, which stays in
+            // place. The text itself is not rotated.
+            let text = graphics::Text::new(graphics::TextFragment {
+                text: content.clone(),
+                color: Some(into_color(component.tinted(color))),
+                font: Some(font),
+                scale: Some(graphics::Scale::uniform(size)),
+            });
+
+            let (x, y) = viewport.to_canvas_coordinates(x, y);
+            let param = graphics::DrawParam::default()
+                .dest(nalgebra::Point2::new(x, y))
+                .scale(nalgebra::Vector2::new(viewport.scale, viewport.scale));
+
+            if let Err(err) = graphics::draw(ctx, &text, param) {
+                log::error!("unable to draw text: {}", err);
+            }
+
+            return;
+        }
     };
 
-    let result = drawable
-        .and_then(|drawable| graphics::draw(ctx, &drawable, graphics::DrawParam::default()));
+    let param = graphics::DrawParam::default()
+        .dest(nalgebra::Point2::new(viewport.x, viewport.y))
+        .scale(nalgebra::Vector2::new(viewport.scale, viewport.scale));
 
-    if result.is_err() {
-        todo!("logging")
+    let result = drawable.and_then(|drawable| graphics::draw(ctx, &drawable, param));
+
+    if let Err(err) = result {
+        log::error!("unable to draw shape: {}", err);
     }
 }
 
+/// Stroke the glow of a border (if any) along the outline of a shape.
+///
+/// The outline is added to the mesh by the given closure, using the draw mode
+/// and color of each stroke.
+///
+/// The glow is stroked before the shape is filled, see
+/// [`Border::glow_strokes`].
+fn stroke_glow(
+    builder: &mut graphics::MeshBuilder,
+    component: &Component,
+    border: Option<Border>,
+    outline: impl Fn(&mut graphics::MeshBuilder, graphics::DrawMode, graphics::Color) -> GameResult<()>,
+) -> GameResult<()> {
+    for stroke in border.iter().flat_map(Border::glow_strokes) {
+        outline(
+            builder,
+            graphics::DrawMode::stroke(stroke.width),
+            into_color(component.tinted(stroke.color)),
+        )?;
+    }
+
+    Ok(())
+}
+
 /// convert our color into a ggez color.
+///
+/// The default ggez blend mode uses straight (non-premultiplied) alpha, so the
+/// color is passed as-is.
 const fn into_color(color: Color) -> graphics::Color {
     let Color { r, g, b, a } = color;
     graphics::Color { r, g, b, a }
 }
-
-impl From<config::Renderer> for Renderer {
-    fn from(config: config::Renderer) -> Self {
-        let minimum_nanoseconds_between_renders = match config.max_frames_per_second {
-            #[allow(clippy::integer_division, clippy::integer_arithmetic)]
-            Some(fps) => 1_000_000_000 / u64::from(fps),
-            None => 0,
-        };
-
-        Self {
-            config,
-            last_step_timestamp: Instant::now(),
-            minimum_nanoseconds_between_renders,
-        }
-    }
-}
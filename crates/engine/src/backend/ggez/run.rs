@@ -10,11 +10,21 @@ use crate::{error, Engine, Error};
 use common::{event, Event, Key};
 use ggez::{
     conf::{FullscreenType, ModuleConf, NumSamples, WindowMode, WindowSetup},
-    event::EventHandler,
-    input::keyboard::{self, KeyCode, KeyMods},
+    event::{EventHandler, MouseButton},
+    graphics,
+    input::{
+        keyboard::{self, KeyCode, KeyMods},
+        mouse,
+    },
     Context, ContextBuilder, GameResult,
 };
-use std::{collections::HashSet, path::Path};
+use std::{collections::HashSet, mem, path::Path};
+
+#[cfg(feature = "gamepad")]
+use ggez::{
+    event::{Axis, Button},
+    input::gamepad::GamepadId,
+};
 
 /// Run the ggez backend.
 #[allow(clippy::cast_precision_loss)]
@@ -22,16 +32,16 @@ pub fn run(mut engine: Engine) -> Result<(), Error> {
     let window_setup = WindowSetup {
         title: "Vienna: work in progress".to_owned(),
         samples: NumSamples::Zero,
-        vsync: true,
+        vsync: engine.renderer.config.vsync,
         icon: "".to_owned(),
         srgb: true,
     };
 
-    let (width, height) = engine.config.canvas.dimensions();
+    let (width, height) = engine.config.canvas.dimensions_f32();
 
     let window_mode = WindowMode {
-        width: f32::from(width),
-        height: f32::from(height),
+        width,
+        height,
         maximized: false,
         fullscreen_type: FullscreenType::Windowed,
         borderless: false,
@@ -43,7 +53,7 @@ pub fn run(mut engine: Engine) -> Result<(), Error> {
     };
 
     let modules = ModuleConf {
-        gamepad: false,
+        gamepad: cfg!(feature = "gamepad"),
         audio: true,
     };
 
@@ -55,11 +65,8 @@ pub fn run(mut engine: Engine) -> Result<(), Error> {
         .add_resource_path(Path::new("./resources"))
         .build();
 
-    #[allow(clippy::match_wild_err_arm)]
-    let (mut ctx, mut event_loop) = match result {
-        Ok(result) => result,
-        Err(_) => todo!("logging"),
-    };
+    let (mut ctx, mut event_loop) = result?;
+    mouse::set_cursor_hidden(&mut ctx, engine.config.cursor_hidden);
 
     ggez::event::run(&mut ctx, &mut event_loop, &mut engine).map_err(Into::into)
 }
@@ -72,17 +79,70 @@ impl EventHandler for Engine {
                 // letter keys
                 KeyCode::A => Key::A,
                 KeyCode::B => Key::B,
+                KeyCode::C => Key::C,
                 KeyCode::D => Key::D,
                 KeyCode::E => Key::E,
+                KeyCode::F => Key::F,
                 KeyCode::G => Key::G,
+                KeyCode::H => Key::H,
+                KeyCode::I => Key::I,
+                KeyCode::J => Key::J,
+                KeyCode::K => Key::K,
+                KeyCode::L => Key::L,
+                KeyCode::M => Key::M,
+                KeyCode::N => Key::N,
+                KeyCode::O => Key::O,
+                KeyCode::P => Key::P,
                 KeyCode::Q => Key::Q,
                 KeyCode::R => Key::R,
                 KeyCode::S => Key::S,
+                KeyCode::T => Key::T,
+                KeyCode::U => Key::U,
+                KeyCode::V => Key::V,
                 KeyCode::W => Key::W,
+                KeyCode::X => Key::X,
+                KeyCode::Y => Key::Y,
+                KeyCode::Z => Key::Z,
+
+                // digit keys
+                KeyCode::Key0 => Key::Digit0,
+                KeyCode::Key1 => Key::Digit1,
+                KeyCode::Key2 => Key::Digit2,
+                KeyCode::Key3 => Key::Digit3,
+                KeyCode::Key4 => Key::Digit4,
+                KeyCode::Key5 => Key::Digit5,
+                KeyCode::Key6 => Key::Digit6,
+                KeyCode::Key7 => Key::Digit7,
+                KeyCode::Key8 => Key::Digit8,
+                KeyCode::Key9 => Key::Digit9,
 
                 // other keys
                 KeyCode::Equals if keyboard::is_mod_active(ctx, KeyMods::SHIFT) => Key::Plus,
+                KeyCode::Escape => Key::Escape,
                 KeyCode::Minus => Key::Minus,
+                KeyCode::Return => Key::Enter,
+                KeyCode::Space => Key::Space,
+                KeyCode::Tab => Key::Tab,
+
+                // arrow keys
+                KeyCode::Up => Key::Up,
+                KeyCode::Down => Key::Down,
+                KeyCode::Left => Key::Left,
+                KeyCode::Right => Key::Right,
+
+                // numpad keys
+                KeyCode::Numpad0 => Key::Digit0,
+                KeyCode::Numpad1 => Key::Digit1,
+                KeyCode::Numpad2 => Key::Digit2,
+                KeyCode::Numpad3 => Key::Digit3,
+                KeyCode::Numpad4 => Key::Digit4,
+                KeyCode::Numpad5 => Key::Digit5,
+                KeyCode::Numpad6 => Key::Digit6,
+                KeyCode::Numpad7 => Key::Digit7,
+                KeyCode::Numpad8 => Key::Digit8,
+                KeyCode::Numpad9 => Key::Digit9,
+                KeyCode::Add => Key::Plus,
+                KeyCode::Subtract => Key::Minus,
 
                 // modifier keys
                 KeyCode::LShift | KeyCode::RShift => Key::Shift,
@@ -95,14 +155,23 @@ impl EventHandler for Engine {
             keys.insert(key);
         }
 
-        let mut events = vec![];
+        let mut events = mem::take(&mut self.updater.backend.pending_events);
+        let transitions = event::key_transitions(&self.updater.backend.pressed_keys, &keys);
+        events.extend(transitions.into_iter().map(Event::Input));
+
         if !keys.is_empty() {
+            let keys = keys.clone();
             events.push(Event::Input(event::Input::Keyboard { keys }));
         }
 
-        let canvas = self.config.canvas;
+        self.updater.backend.pressed_keys = keys;
+
+        // Plugins only know about the part of the canvas the game is rendered
+        // in.
+        let canvas = self.config.viewport().into();
         let handler = self.plugin_handler.as_mut();
-        self.updater
+        let commands = self
+            .updater
             .run(&mut self.game_state, canvas, &events, handler)
             .map_err(|err| match err {
                 // this is the only native error type supported by ggez
@@ -113,14 +182,148 @@ impl EventHandler for Engine {
                 error::Updater::PluginRuntime(err) => {
                     ggez::GameError::RenderError(format!("{:#}", anyhow::Error::new(err)))
                 }
-            })
+                error::Updater::Recorder(err) => {
+                    ggez::GameError::RenderError(format!("{:#}", anyhow::Error::new(err)))
+                }
+            })?;
+
+        let fullscreen = self.config.fullscreen;
+        let cursor_hidden = self.config.cursor_hidden;
+        self.process_commands(commands);
+
+        // Apply any cursor visibility changes requested by plugins.
+        if cursor_hidden != self.config.cursor_hidden {
+            mouse::set_cursor_hidden(ctx, self.config.cursor_hidden);
+        }
+
+        // Apply any fullscreen changes requested by plugins.
+        if fullscreen != self.config.fullscreen {
+            let fullscreen_type = if self.config.fullscreen {
+                FullscreenType::True
+            } else {
+                FullscreenType::Windowed
+            };
+
+            graphics::set_fullscreen(ctx, fullscreen_type)?;
+        }
+
+        if self.updater.is_finished {
+            ggez::event::quit(ctx);
+        }
+
+        Ok(())
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        // Plugins only know about the part of the canvas the game is rendered
+        // in.
+        let (x, y) = self.config.viewport().to_viewport_coordinates(x, y);
+        let button = convert_button(button);
+        let event = Event::Input(event::Input::MouseRelease { button, x, y });
+
+        self.updater.backend.pending_events.push(event);
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, button: Button, _id: GamepadId) {
+        let button = convert_gamepad_button(button);
+        let event = Event::Input(event::Input::GamepadButton {
+            button,
+            pressed: true,
+        });
+
+        self.updater.backend.pending_events.push(event);
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, button: Button, _id: GamepadId) {
+        let button = convert_gamepad_button(button);
+        let event = Event::Input(event::Input::GamepadButton {
+            button,
+            pressed: false,
+        });
+
+        self.updater.backend.pending_events.push(event);
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        let axis = convert_gamepad_axis(axis);
+        let event = Event::Input(event::Input::GamepadAxis { axis, value });
+
+        self.updater.backend.pending_events.push(event);
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods) {
+        // Toggle the game simulation pause.
+        if keycode == KeyCode::Pause {
+            self.updater.is_paused = !self.updater.is_paused;
+        }
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        // The renderer scales the game to fit the window itself, so drawing
+        // happens in window pixels.
+        let coordinates = graphics::Rect::new(0.0, 0.0, width, height);
+        if let Err(err) = graphics::set_screen_coordinates(ctx, coordinates) {
+            log::error!("unable to resize the drawing area: {}", err);
+        }
+
+        self.config.resize_window(width, height);
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let progress = self.updater.step_progress;
+        let progress = self.updater.backend.step_progress;
 
         // TODO: For now the renderer is not engine-agnostic, but will be once
         //       plugins are in charge of drawing to the screen.
-        self.renderer.run(ctx, &self.game_state, progress)
+        let viewport = self.config.viewport();
+        let focus_ring = self.config.focus_ring;
+        self.renderer
+            .run(ctx, &self.game_state, viewport, focus_ring, progress)
+    }
+}
+
+/// Convert a mouse button to a mouse button supported by the engine.
+fn convert_button(button: MouseButton) -> event::MouseButton {
+    match button {
+        MouseButton::Left => event::MouseButton::Left,
+        MouseButton::Middle => event::MouseButton::Middle,
+        MouseButton::Right => event::MouseButton::Right,
+        MouseButton::Other(_) => event::MouseButton::Other,
+    }
+}
+
+/// Convert a gamepad button to a gamepad button supported by the engine.
+#[cfg(feature = "gamepad")]
+#[allow(clippy::wildcard_enum_match_arm)]
+const fn convert_gamepad_button(button: Button) -> event::GamepadButton {
+    match button {
+        Button::South => event::GamepadButton::South,
+        Button::East => event::GamepadButton::East,
+        Button::North => event::GamepadButton::North,
+        Button::West => event::GamepadButton::West,
+        Button::LeftTrigger => event::GamepadButton::LeftTrigger,
+        Button::RightTrigger => event::GamepadButton::RightTrigger,
+        Button::DPadUp => event::GamepadButton::DPadUp,
+        Button::DPadDown => event::GamepadButton::DPadDown,
+        Button::DPadLeft => event::GamepadButton::DPadLeft,
+        Button::DPadRight => event::GamepadButton::DPadRight,
+        Button::Select => event::GamepadButton::Select,
+        Button::Start => event::GamepadButton::Start,
+        _ => event::GamepadButton::Other,
+    }
+}
+
+/// Convert a gamepad axis to a gamepad axis supported by the engine.
+#[cfg(feature = "gamepad")]
+#[allow(clippy::wildcard_enum_match_arm)]
+const fn convert_gamepad_axis(axis: Axis) -> event::GamepadAxis {
+    match axis {
+        Axis::LeftStickX => event::GamepadAxis::LeftStickX,
+        Axis::LeftStickY => event::GamepadAxis::LeftStickY,
+        Axis::RightStickX => event::GamepadAxis::RightStickX,
+        Axis::RightStickY => event::GamepadAxis::RightStickY,
+        _ => event::GamepadAxis::Other,
     }
 }
@@ -1,24 +1,12 @@
 //! The updater implementation for the ggez backend.
 
-use crate::{config, error, plugin::Handler, widget};
-use common::{Canvas, Event, GameState};
-use std::time::Instant;
+use crate::{backend::updater::Updater, error, plugin::Handler};
+use common::{Canvas, Command, Event, GameState, Key};
+use std::{collections::HashSet, time::Instant};
 
-/// Handles updating the game state.
+/// The state of the updater specific to the ggez backend.
 #[derive(Debug)]
-pub struct Updater {
-    /// The configuration of the updater.
-    pub(crate) config: config::Updater,
-
-    /// `update_interval` is the minimum amount of time (in nanoseconds) that
-    /// needs to pass before we trigger a game state update.
-    update_interval: u64,
-
-    /// `total_time` is the total accumulation of passed time (in nanoseconds).
-    /// This is a monotonically increasing value. The value is passed to the
-    /// update handler of the game, which can use this when needed.
-    total_time: u64,
-
+pub struct State {
     /// `last_step_timestamp` is the timestamp at the end of the last game step.
     last_step_timestamp: Instant,
 
@@ -30,9 +18,28 @@ pub struct Updater {
     /// the renderer know how far along the updater is towards providing the
     /// next update.
     pub(super) step_progress: f64,
+
+    /// The keys pressed during the previous input, used to detect which keys
+    /// were pressed or released since.
+    pub(crate) pressed_keys: HashSet<Key>,
+
+    /// Input events received through event callbacks since the last update.
+    pub(crate) pending_events: Vec<Event>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            last_step_timestamp: Instant::now(),
+            accumulated_time: 0,
+            step_progress: 0.0,
+            pressed_keys: HashSet::new(),
+            pending_events: vec![],
+        }
+    }
 }
 
-impl Updater {
+impl Updater<State> {
     /// Update the game state.
     #[allow(
         clippy::cast_possible_truncation,
@@ -46,68 +53,57 @@ impl Updater {
         canvas: Canvas,
         events: &[Event],
         plugin_handler: &mut dyn Handler,
-    ) -> Result<(), error::Updater> {
-        let last_step_duration = self.last_step_timestamp.elapsed();
-        self.accumulated_time += last_step_duration.as_nanos() as u64;
-        self.last_step_timestamp = Instant::now();
+    ) -> Result<Vec<Command>, error::Updater> {
+        // `update_interval` is the minimum amount of time (in nanoseconds) that
+        // needs to pass before we trigger a game state update.
+        let update_interval = self.config.update_interval();
+
+        let last_step_duration = self.backend.last_step_timestamp.elapsed();
+        self.backend.accumulated_time += last_step_duration.as_nanos() as u64;
+        self.backend.last_step_timestamp = Instant::now();
+
+        // While paused, no time is accumulated, so that the game doesn't try
+        // to catch up on all missed updates once it resumes.
+        if self.is_paused {
+            self.backend.accumulated_time = 0;
+            return Ok(vec![]);
+        }
+
+        let mut commands = vec![];
+        let mut updates = 0;
 
         // We check if there's enough time accumulated to actually
         // update a single game update. The required available time
         // depends on the configured updates per second.
-        while self.accumulated_time >= self.update_interval {
-            update_game_state(state, canvas, events, plugin_handler)?;
-
-            self.accumulated_time -= self.update_interval;
-            self.total_time += self.update_interval;
+        while self.backend.accumulated_time >= update_interval {
+            // Stop catching up once the maximum number of updates for this
+            // frame is reached, discarding the remaining time. Otherwise a
+            // slow update causes even more updates in the next frame, until
+            // the game grinds to a halt.
+            if updates >= self.config.max_updates_per_frame {
+                log::warn!(
+                    "updater fell behind, skipping {} updates",
+                    self.backend.accumulated_time / update_interval
+                );
+
+                self.backend.accumulated_time %= update_interval;
+                break;
+            }
+
+            updates += 1;
+
+            let events = events.to_vec();
+            commands.append(&mut self.update_game_state(state, canvas, events, plugin_handler)?);
+
+            self.backend.accumulated_time -= update_interval;
         }
 
         // The remaining accumulated time is used as a range between 0 and 1 to
         // let the renderer know how far along the updater is towards providing
         // the next update.
-        self.step_progress = self.accumulated_time as f64 / self.update_interval as f64;
+        self.backend.step_progress = self.backend.accumulated_time as f64 / update_interval as f64;
 
-        Ok(())
-    }
-}
-
-/// Run the relevant code to update the state of the game.
-///
-/// This includes updating the widgets and running all plugins.
-fn update_game_state(
-    state: &mut GameState,
-    canvas: Canvas,
-    input_events: &[Event],
-    plugin_handler: &mut dyn Handler,
-) -> Result<(), error::Updater> {
-    let mut widget_events = vec![];
-
-    for (name, widget) in state.widgets_mut() {
-        widget_events.append(&mut widget::update(name, widget, input_events))
-    }
-
-    // TODO: A plugin should only see events from the widgets that belong to it.
-    plugin_handler
-        .run_plugins(state, canvas, &widget_events)
-        .map_err(Into::into)
-}
-
-impl From<config::Updater> for Updater {
-    #[allow(
-        clippy::as_conversions,
-        clippy::integer_arithmetic,
-        clippy::integer_division
-    )]
-    fn from(config: config::Updater) -> Self {
-        let update_interval = 1_000_000_000 / config.updates_per_second;
-
-        Self {
-            config,
-            update_interval,
-            total_time: 0,
-            last_step_timestamp: Instant::now(),
-            accumulated_time: 0,
-            step_progress: 0.0,
-        }
+        Ok(commands)
     }
 }
 
@@ -115,18 +111,45 @@ impl From<config::Updater> for Updater {
 #[allow(clippy::restriction)]
 mod tests {
     use super::*;
-    use std::path::Path;
+    use crate::config;
+    use std::{path::Path, time::Duration};
 
     #[test]
-    fn test_update_game_state() {
+    fn catch_up_cap() {
         let canvas = Canvas::default();
         let mut state = GameState::default();
         let mut handler = crate::plugin::mock::Manager::default();
         handler.register_plugin(&mut state, Path::new("")).unwrap();
 
-        update_game_state(&mut state, canvas, &[], &mut handler).unwrap();
-        update_game_state(&mut state, canvas, &[], &mut handler).unwrap();
+        let mut updater = Updater::<State>::from(config::Updater {
+            max_updates_per_frame: 5,
+            ..config::Updater::default()
+        });
+
+        // Simulate a frame that stalled for ten seconds.
+        updater.backend.last_step_timestamp = Instant::now() - Duration::from_secs(10);
+        updater.run(&mut state, canvas, &[], &mut handler).unwrap();
+
+        assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 5);
+        assert!(updater.backend.accumulated_time < updater.config.update_interval());
+    }
+
+    #[test]
+    fn time() {
+        let canvas = Canvas::default();
+        let mut state = GameState::default();
+        let mut handler = crate::plugin::mock::Manager::default();
+        handler.register_plugin(&mut state, Path::new("")).unwrap();
+
+        let mut updater = Updater::<State>::from(config::Updater::default());
+
+        // Three updates worth of time, at the default of 100 updates per
+        // second.
+        updater.backend.last_step_timestamp = Instant::now() - Duration::from_millis(30);
+        updater.run(&mut state, canvas, &[], &mut handler).unwrap();
 
-        assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 2);
+        let time = handler.as_mock().unwrap().plugins[0].time;
+        assert_eq!(time.delta(), Duration::from_millis(10));
+        assert_eq!(time.elapsed(), Duration::from_millis(20));
     }
 }
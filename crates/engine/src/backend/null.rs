@@ -0,0 +1,32 @@
+//! The "null" backend, which runs the engine without a window.
+//!
+//! Nothing is drawn, and no input is received from the player. Instead, the
+//! game state is updated on every call to [`Engine::tick`], with the input
+//! events passed to it. This allows running the engine (and its plugins)
+//! deterministically, e.g. in tests or on CI.
+//!
+//! The state of the game can still be rendered to pixels using
+//! [`Engine::capture_frame`], which rasterizes the game state on the CPU, as
+//! there is no window to take a screenshot of.
+//!
+//! [`Engine::tick`]: crate::Engine::tick
+//! [`Engine::capture_frame`]: crate::Engine::capture_frame
+
+mod run;
+mod updater;
+
+use super::Backend;
+use crate::{Engine, Error};
+
+/// The "null" backend.
+#[derive(Debug)]
+pub struct Null;
+
+impl Backend for Null {
+    type Renderer = super::renderer::Renderer<()>;
+    type Updater = super::updater::Updater<()>;
+
+    fn run(engine: Engine) -> Result<(), Error> {
+        run::run(engine)
+    }
+}
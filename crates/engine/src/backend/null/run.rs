@@ -0,0 +1,166 @@
+//! Running the engine without a window.
+
+use crate::{Engine, Error};
+use common::Event;
+
+/// Run the null backend.
+///
+/// Without a window there is no player input, so the game state is updated
+/// without any input events, until a plugin quits the game.
+pub fn run(mut engine: Engine) -> Result<(), Error> {
+    while !engine.updater.is_finished {
+        engine.tick(&[])?;
+    }
+
+    Ok(())
+}
+
+impl Engine {
+    /// Update the game state once, with the given input events, and process
+    /// the commands issued by the plugins.
+    ///
+    /// Every tick advances the game time by a single update interval,
+    /// regardless of how much (wall-clock) time passed since the last tick.
+    ///
+    /// If a plugin requested a frame to be captured, the updated game state is
+    /// rasterized using [`Engine::capture_frame`], and passed to the frame
+    /// capture callback.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the plugins failed to run.
+    pub fn tick(&mut self, events: &[Event]) -> Result<(), Error> {
+        // Plugins only know about the part of the canvas the game is rendered
+        // in.
+        let canvas = self.config.viewport().into();
+        let handler = self.plugin_handler.as_mut();
+        let commands = self
+            .updater
+            .run(&mut self.game_state, canvas, events, handler)?;
+
+        self.process_commands(commands);
+
+        if self.renderer.take_capture_request() {
+            let frame = self.capture_frame();
+            self.renderer.frame_captured(frame);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::frame;
+    use crate::plugin::mock;
+    use common::{widget, Canvas, Color, Command, PluginState, Value};
+    use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
+
+    fn engine(commands: Vec<Command>) -> Engine {
+        let mut manager = mock::Manager::default();
+        manager.plugins.push(mock::Plugin {
+            commands,
+            ..mock::Plugin::default()
+        });
+
+        Engine {
+            plugin_handler: Box::new(manager),
+            ..Engine::default()
+        }
+    }
+
+    mod tick {
+        use super::*;
+
+        #[test]
+        fn runs_plugins() {
+            let mut engine = engine(vec![]);
+            engine.tick(&[]).unwrap();
+            engine.tick(&[]).unwrap();
+
+            let plugin = &engine.plugin_handler.as_mock().unwrap().plugins[0];
+            assert_eq!(plugin.runs, 2);
+            assert_eq!(plugin.time.elapsed(), Duration::from_millis(10));
+        }
+
+        #[test]
+        fn processes_commands() {
+            let mut engine = engine(vec![Command::Fullscreen(true)]);
+            engine.tick(&[]).unwrap();
+
+            assert!(engine.config.fullscreen);
+        }
+    }
+
+    mod run {
+        use super::*;
+
+        #[test]
+        fn until_finished() {
+            let engine = engine(vec![Command::Quit]);
+            assert!(run(engine).is_ok());
+        }
+    }
+
+    mod capture_frame {
+        use super::*;
+
+        /// The null backend has no window to take a screenshot of, so this
+        /// checks the frame rasterized on the CPU, not a backend renderer.
+        #[test]
+        fn requested_by_plugin() {
+            let red = Color::from_rgb(255, 0, 0);
+            let frames = Rc::new(RefCell::new(vec![]));
+            let captured = Rc::clone(&frames);
+
+            let mut engine = Engine {
+                config: Canvas::new(100, 50).into(),
+                ..engine(vec![Command::CaptureFrame])
+            };
+            engine.renderer.on_frame_captured = Some(frame::Callback::new(move |frame| {
+                captured.borrow_mut().push(frame)
+            }));
+
+            let widgets: HashMap<_, _> =
+                vec![
+                    widget::Builder::new("button", widget::Kind::ButtonRectangle)
+                        .attribute("width", 20.0)
+                        .attribute("height", 10.0)
+                        .attribute("idle_color", red)
+                        .position(10.0, 10.0)
+                        .build(),
+                ]
+                .into_iter()
+                .collect();
+
+            let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+            engine.game_state.register_plugin_state("test", state);
+            engine.tick(&[]).unwrap();
+
+            let frames = frames.borrow();
+            assert_eq!(frames.len(), 1);
+
+            let frame = &frames[0];
+            assert_eq!((frame.width, frame.height), (100, 50));
+            assert_eq!(frame.pixels.len(), 100 * 50 * 4);
+            assert_eq!(frame.pixel(15, 15), Some(red));
+            assert_ne!(frame.pixel(5, 5), Some(red));
+        }
+
+        #[test]
+        fn not_requested() {
+            let frames = Rc::new(RefCell::new(vec![]));
+            let captured = Rc::clone(&frames);
+
+            let mut engine = engine(vec![]);
+            engine.renderer.on_frame_captured = Some(frame::Callback::new(move |frame| {
+                captured.borrow_mut().push(frame)
+            }));
+            engine.tick(&[]).unwrap();
+
+            assert!(frames.borrow().is_empty());
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! The updater implementation for the null backend.
+
+use crate::{backend::updater::Updater, error, plugin::Handler};
+use common::{Canvas, Command, Event, GameState};
+
+impl Updater<()> {
+    /// Update the game state once, with the given input events.
+    ///
+    /// Time doesn't pass by itself without a window, instead every update
+    /// advances the game time by exactly one update interval.
+    ///
+    /// Returns the commands issued by the plugins for the engine to process.
+    pub fn run(
+        &mut self,
+        state: &mut GameState,
+        canvas: Canvas,
+        events: &[Event],
+        plugin_handler: &mut dyn Handler,
+    ) -> Result<Vec<Command>, error::Updater> {
+        if self.is_paused {
+            return Ok(vec![]);
+        }
+
+        self.update_game_state(state, canvas, events.to_vec(), plugin_handler)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use std::path::Path;
+
+    mod run {
+        use super::*;
+
+        #[test]
+        fn paused() {
+            let canvas = Canvas::default();
+            let mut state = GameState::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+            handler.register_plugin(&mut state, Path::new("")).unwrap();
+
+            let mut updater = Updater::<()>::from(config::Updater {
+                paused: true,
+                ..config::Updater::default()
+            });
+
+            updater.run(&mut state, canvas, &[], &mut handler).unwrap();
+            assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 0);
+            assert_eq!(updater.tick, 0);
+
+            updater.is_paused = false;
+            updater.run(&mut state, canvas, &[], &mut handler).unwrap();
+            assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 1);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! The renderer plumbing shared by all backends.
+
+use crate::{config, frame, widget, Frame};
+#[cfg(any(feature = "backend-coffee", feature = "backend-ggez"))]
+use std::{borrow::Cow, fs};
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
+
+/// The font embedded in the engine, used to render text if no font is
+/// configured.
+#[cfg(all(
+    feature = "embedded-font",
+    any(feature = "backend-coffee", feature = "backend-ggez")
+))]
+const EMBEDDED_FONT: Option<&[u8]> = Some(include_bytes!("../../assets/fonts/DejaVuSans.ttf"));
+
+/// Without the `embedded-font` feature, no font is embedded in the engine.
+#[cfg(all(
+    not(feature = "embedded-font"),
+    any(feature = "backend-coffee", feature = "backend-ggez")
+))]
+const EMBEDDED_FONT: Option<&[u8]> = None;
+
+/// Fonts commonly installed on the supported platforms, used to render text if
+/// no font is configured or embedded.
+#[cfg(any(feature = "backend-coffee", feature = "backend-ggez"))]
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+/// Handles rendering frames to the screen.
+///
+/// The renderer keeps track of when to render the next frame, and caches the
+/// components of the rendered widgets. Anything specific to a backend, such as
+/// loaded fonts, is stored in `backend`.
+#[derive(Debug)]
+pub struct Renderer<B> {
+    /// The configuration of the renderer.
+    pub(crate) config: config::Renderer,
+
+    /// A cache of the timestamp the last step finished.
+    ///
+    /// This is used to adhere to any configured FPS limits.
+    last_step_timestamp: Instant,
+
+    /// A cache based off the FPS configuration.
+    ///
+    /// This is used to adhere to any configured FPS limits.
+    minimum_nanoseconds_between_renders: u64,
+
+    /// The components of the widgets rendered in previous frames.
+    pub(super) components: widget::ComponentCache,
+
+    /// The state of the renderer specific to the backend.
+    pub(super) backend: B,
+
+    /// Whether or not a plugin requested the next frame to be captured.
+    pub(crate) capture_requested: bool,
+
+    /// The callback receiving the captured frames.
+    pub(crate) on_frame_captured: Option<frame::Callback>,
+}
+
+impl<B> Renderer<B> {
+    /// Should the renderer render to the screen at the given moment, based on
+    /// the max FPS settings?
+    pub(super) fn should_render_at(&self, now: Instant) -> bool {
+        if self.minimum_nanoseconds_between_renders == 0 {
+            return true;
+        }
+
+        let last_step_duration = now.saturating_duration_since(self.last_step_timestamp);
+
+        #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
+        let last_step_nanoseconds = last_step_duration.as_nanos() as u64;
+
+        last_step_nanoseconds >= self.minimum_nanoseconds_between_renders
+    }
+
+    /// Record that a frame was rendered at the given moment.
+    ///
+    /// The next frame is scheduled a fixed interval after the previous one,
+    /// rather than after `now`, so that the time spent waiting past each
+    /// deadline doesn't add up and lower the frame rate below the cap. If the
+    /// renderer fell behind by more than a frame, it starts over from `now`.
+    pub(super) fn frame_rendered(&mut self, now: Instant) {
+        let interval = Duration::from_nanos(self.minimum_nanoseconds_between_renders);
+        let elapsed = now.saturating_duration_since(self.last_step_timestamp);
+
+        self.last_step_timestamp = if interval > Duration::from_secs(0) && elapsed < interval * 2 {
+            self.last_step_timestamp + interval
+        } else {
+            now
+        };
+    }
+
+    /// Check whether the frame being rendered should be captured, clearing
+    /// the request.
+    ///
+    /// Requests are ignored if there is no callback to pass the frame to.
+    pub(crate) fn take_capture_request(&mut self) -> bool {
+        mem::take(&mut self.capture_requested) && self.on_frame_captured.is_some()
+    }
+
+    /// Pass a captured frame to the callback.
+    pub(crate) fn frame_captured(&mut self, frame: Frame) {
+        if let Some(callback) = &mut self.on_frame_captured {
+            callback.call(frame);
+        }
+    }
+
+    /// Get the font used to render text.
+    ///
+    /// This is the configured font, if it can be read. Otherwise it is the
+    /// embedded font (with the `embedded-font` feature), or the first font
+    /// found on the system. Returns `None` if there is no font at all.
+    #[cfg(any(feature = "backend-coffee", feature = "backend-ggez"))]
+    pub(super) fn font_source(&self) -> Option<Cow<'static, [u8]>> {
+        if let Some(path) = &self.config.font_path {
+            match fs::read(path) {
+                Ok(font) => return Some(Cow::Owned(font)),
+                Err(err) => log::error!("unable to read font `{}`: {}", path.display(), err),
+            }
+        }
+
+        EMBEDDED_FONT.map(Cow::Borrowed).or_else(|| {
+            SYSTEM_FONTS
+                .iter()
+                .find_map(|path| fs::read(path).ok())
+                .map(Cow::Owned)
+        })
+    }
+}
+
+impl<B: Default> From<config::Renderer> for Renderer<B> {
+    fn from(config: config::Renderer) -> Self {
+        let minimum_nanoseconds_between_renders = config.minimum_nanoseconds_between_renders();
+
+        Self {
+            config,
+            last_step_timestamp: Instant::now(),
+            minimum_nanoseconds_between_renders,
+            components: widget::ComponentCache::default(),
+            backend: B::default(),
+            capture_requested: false,
+            on_frame_captured: None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    fn renderer(max_frames_per_second: Option<u16>) -> Renderer<()> {
+        Renderer::from(config::Renderer {
+            max_frames_per_second,
+            ..config::Renderer::default()
+        })
+    }
+
+    mod should_render {
+        use super::*;
+
+        #[test]
+        fn capped() {
+            let renderer = renderer(Some(50));
+            let start = renderer.last_step_timestamp;
+
+            assert!(!renderer.should_render_at(start + Duration::from_millis(19)));
+            assert!(renderer.should_render_at(start + Duration::from_millis(20)));
+        }
+
+        #[test]
+        fn uncapped() {
+            for fps in &[None, Some(0)] {
+                let renderer = renderer(*fps);
+
+                assert!(renderer.should_render_at(renderer.last_step_timestamp));
+            }
+        }
+    }
+
+    mod frame_rendered {
+        use super::*;
+
+        #[test]
+        fn fixed_interval() {
+            let mut renderer = renderer(Some(50));
+            let start = renderer.last_step_timestamp;
+
+            // A frame rendered late doesn't push back the next frame.
+            renderer.frame_rendered(start + Duration::from_millis(25));
+            assert!(renderer.should_render_at(start + Duration::from_millis(40)));
+        }
+
+        #[test]
+        fn fallen_behind() {
+            let mut renderer = renderer(Some(50));
+            let start = renderer.last_step_timestamp;
+
+            renderer.frame_rendered(start + Duration::from_millis(100));
+            assert!(!renderer.should_render_at(start + Duration::from_millis(110)));
+            assert!(renderer.should_render_at(start + Duration::from_millis(120)));
+        }
+    }
+
+    #[cfg(any(feature = "backend-coffee", feature = "backend-ggez"))]
+    mod font_source {
+        use super::*;
+        use std::io::Write;
+
+        #[test]
+        fn configured() {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(b"font").unwrap();
+
+            let renderer: Renderer<()> = Renderer::from(config::Renderer {
+                font_path: Some(file.path().to_owned()),
+                ..config::Renderer::default()
+            });
+
+            assert_eq!(renderer.font_source().as_deref(), Some(&b"font"[..]));
+        }
+
+        #[test]
+        #[cfg(feature = "embedded-font")]
+        fn unreadable() {
+            let renderer: Renderer<()> = Renderer::from(config::Renderer {
+                font_path: Some("missing.ttf".into()),
+                ..config::Renderer::default()
+            });
+
+            assert_eq!(renderer.font_source(), EMBEDDED_FONT.map(Cow::Borrowed));
+        }
+    }
+
+    mod take_capture_request {
+        use super::*;
+
+        #[test]
+        fn requested() {
+            let mut renderer = renderer(None);
+            renderer.on_frame_captured = Some(frame::Callback::new(|_| {}));
+            renderer.capture_requested = true;
+
+            assert!(renderer.take_capture_request());
+            assert!(!renderer.take_capture_request());
+        }
+
+        #[test]
+        fn without_callback() {
+            let mut renderer = renderer(None);
+            renderer.capture_requested = true;
+
+            assert!(!renderer.take_capture_request());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! The updater plumbing shared by all backends.
+
+use crate::{click::Clicks, config, error, plugin::Handler, recorder::Recorder, widget};
+use common::{event, Canvas, Command, Event, GameState, Time};
+
+/// Handles updating the game state.
+///
+/// The updater runs the widgets and plugins once for every update of the game
+/// state. When to update, and which input events to update with, is decided
+/// by the backend, which stores any state it needs for that in `backend`.
+#[derive(Debug)]
+pub struct Updater<B> {
+    /// The configuration of the updater.
+    pub(crate) config: config::Updater,
+
+    /// Returns true if the game should be closed.
+    pub(crate) is_finished: bool,
+
+    /// Returns true if the game state should not be updated.
+    pub(crate) is_paused: bool,
+
+    /// The number of times the game state was updated.
+    pub(crate) tick: u64,
+
+    /// An optional recorder to record the input events, or to play back
+    /// previously recorded events.
+    pub(crate) recorder: Option<Recorder>,
+
+    /// The last clicks, to detect double-clicks.
+    clicks: Clicks,
+
+    /// The state of the updater specific to the backend.
+    pub(crate) backend: B,
+}
+
+impl<B> Updater<B> {
+    /// Update the game state once, with the given input events.
+    ///
+    /// This includes updating the widgets and running all plugins. Every
+    /// update advances the game time by exactly one update interval.
+    ///
+    /// Returns the commands issued by the plugins for the engine to process.
+    pub(super) fn update_game_state(
+        &mut self,
+        state: &mut GameState,
+        canvas: Canvas,
+        mut events: Vec<Event>,
+        plugin_handler: &mut dyn Handler,
+    ) -> Result<Vec<Command>, error::Updater> {
+        event::coalesce_pointer_moves(&mut events);
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.tap(self.tick, &mut events)?;
+        }
+
+        // Double-clicks are detected after recording, so that a recording
+        // only contains the clicks themselves.
+        let interval = self.config.update_interval();
+        let now = self.tick.saturating_mul(interval);
+        self.clicks.detect(&mut events, now);
+
+        let events = widget::update_all(
+            state,
+            &events,
+            self.config.delta(),
+            self.config.drag_threshold,
+        );
+
+        // Each plugin only gets to see the events of its own widgets, see
+        // `GameState::plugin_events`.
+        let time = Time::new(now, interval);
+        let commands = plugin_handler.run_plugins(state, canvas, time, &events)?;
+
+        self.tick = self.tick.saturating_add(1);
+
+        Ok(commands)
+    }
+}
+
+impl<B: Default> From<config::Updater> for Updater<B> {
+    fn from(config: config::Updater) -> Self {
+        Self {
+            is_finished: false,
+            is_paused: config.paused,
+            tick: 0,
+            recorder: None,
+            clicks: Clicks::new(config.double_click_interval, config.double_click_radius),
+            backend: B::default(),
+            config,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use std::{path::Path, time::Duration};
+
+    mod update_game_state {
+        use super::*;
+
+        #[test]
+        fn runs_plugins() {
+            let canvas = Canvas::default();
+            let mut state = GameState::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+            handler.register_plugin(&mut state, Path::new("")).unwrap();
+
+            let mut updater = Updater::<()>::from(config::Updater::default());
+            updater
+                .update_game_state(&mut state, canvas, vec![], &mut handler)
+                .unwrap();
+            updater
+                .update_game_state(&mut state, canvas, vec![], &mut handler)
+                .unwrap();
+
+            assert_eq!(handler.as_mock().unwrap().plugins[0].runs, 2);
+            assert_eq!(updater.tick, 2);
+        }
+
+        #[test]
+        fn time() {
+            let canvas = Canvas::default();
+            let mut state = GameState::default();
+            let mut handler = crate::plugin::mock::Manager::default();
+            handler.register_plugin(&mut state, Path::new("")).unwrap();
+
+            let mut updater = Updater::<()>::from(config::Updater::default());
+            for _ in 0..3 {
+                updater
+                    .update_game_state(&mut state, canvas, vec![], &mut handler)
+                    .unwrap();
+            }
+
+            let time = handler.as_mock().unwrap().plugins[0].time;
+            assert_eq!(time.delta(), Duration::from_millis(10));
+            assert_eq!(time.elapsed(), Duration::from_millis(20));
+        }
+    }
+}
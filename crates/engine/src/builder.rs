@@ -3,11 +3,13 @@
 use crate::{
     config,
     error::Builder as Error,
+    frame,
     plugin::{wasm, Handler},
-    Engine,
+    recorder::{Mode as RecorderMode, Recorder},
+    Engine, Frame,
 };
-use common::{Canvas, GameState};
-use std::{mem, path::PathBuf};
+use common::{Canvas, Color, GameState};
+use std::{mem, path::PathBuf, time::Duration};
 
 /// Convenient way to create an [`Engine`].
 ///
@@ -27,12 +29,65 @@ pub struct Builder {
     /// A list of paths in which to search for wasm plugins.
     plugin_paths: Vec<PathBuf>,
 
+    /// The maximum directory depth to search for plugins in each plugin path.
+    plugin_scan_depth: Option<usize>,
+
     /// The state of a game (e.g. a saved game state)
     game_state: GameState,
 
+    /// The path to a saved game state to load when building the engine.
+    saved_game_state: Option<PathBuf>,
+
+    /// The path to which the game state is saved when a plugin requests it.
+    save_path: Option<PathBuf>,
+
     /// The maximum number of frames per second to run the game at.
     maximum_fps: Option<u16>,
 
+    /// Whether or not the game starts paused.
+    paused: bool,
+
+    /// Whether or not the mouse cursor starts hidden.
+    cursor_hidden: bool,
+
+    /// The aspect ratio in which to render the game.
+    target_aspect: Option<f32>,
+
+    /// The outline to draw around the focused widget.
+    focus_ring: Option<config::FocusRing>,
+
+    /// Whether or not to draw the bounds of every widget.
+    debug_bounds: bool,
+
+    /// The path to the font used to render text.
+    font_path: Option<PathBuf>,
+
+    /// The callback receiving the frames captured when a plugin requests it.
+    on_frame_captured: Option<frame::Callback>,
+
+    /// The distance the pointer has to move while pressed to start a drag.
+    drag_threshold: Option<f32>,
+
+    /// The maximum interval and distance between the clicks of a
+    /// double-click.
+    double_click: Option<(Duration, f32)>,
+
+    /// The maximum number of updates to run to catch up on a single frame.
+    max_updates_per_frame: Option<u32>,
+
+    /// The number of times reading a plugin is retried after a transient I/O
+    /// error.
+    plugin_read_retries: Option<u8>,
+
+    /// The maximum (wall-clock) time a single plugin run is allowed to take.
+    plugin_time_budget: Option<Duration>,
+
+    /// The number of previous runs of which events are passed to plugins.
+    event_history: usize,
+
+    /// Whether to record input events to, or play them back from a file.
+    recording: Option<RecorderMode>,
+
     // These are exported so that the `coffee` backend's `run` function has access
     // to the values when creating a new window.
     /// Details about the canvas of the game.
@@ -50,7 +105,8 @@ impl Builder {
     ///
     /// How it works:
     ///
-    /// - The entire directory tree of the path is searched for plugins.
+    /// - The entire directory tree of the path is searched for plugins, unless
+    ///   limited using [`Builder::with_plugin_scan_depth`].
     /// - A plugin is any file that has the "wasm" extension.
     /// - Duplicate file names are ignored (even for different paths).
     pub fn with_plugin_path(mut self, path: impl Into<PathBuf>) -> Self {
@@ -58,6 +114,90 @@ impl Builder {
         self
     }
 
+    /// Limit how deep the directory tree of each plugin path is searched for
+    /// plugins.
+    ///
+    /// A depth of 1 only considers the files directly inside the plugin path,
+    /// which avoids scanning (e.g. vendored) subdirectories when all plugins
+    /// live in a single flat directory.
+    ///
+    /// Defaults to no limit.
+    pub const fn with_plugin_scan_depth(mut self, depth: usize) -> Self {
+        self.plugin_scan_depth = Some(depth);
+        self
+    }
+
+    /// Set the number of times loading a plugin is retried, if reading it
+    /// from disk fails with a transient I/O error.
+    ///
+    /// Each retry waits twice as long as the previous one, starting at 10
+    /// milliseconds. This improves robustness when loading plugins from slow
+    /// or network filesystems.
+    ///
+    /// Defaults to 3 retries. Set to 0 to disable retrying.
+    pub const fn with_plugin_read_retries(mut self, retries: u8) -> Self {
+        self.plugin_read_retries = Some(retries);
+        self
+    }
+
+    /// Set the maximum (wall-clock) time a single run of a plugin is allowed to
+    /// take.
+    ///
+    /// A plugin exceeding its budget is interrupted, and paused, after which
+    /// the engine returns an error. This guards against plugins that never
+    /// finish running, e.g. due to an infinite loop.
+    ///
+    /// Defaults to no time budget.
+    pub const fn with_plugin_time_budget(mut self, budget: Duration) -> Self {
+        self.plugin_time_budget = Some(budget);
+        self
+    }
+
+    /// Keep the events of the given number of previous runs, and pass them to
+    /// plugins as the event history.
+    ///
+    /// Plugins can use this to detect sequences of input spanning multiple
+    /// updates (e.g. combos in a fighting game).
+    ///
+    /// Defaults to no history.
+    pub const fn with_event_history(mut self, runs: usize) -> Self {
+        self.event_history = runs;
+        self
+    }
+
+    /// Set the distance (in pixels) the pointer has to move from where a mouse
+    /// button was pressed, before the press turns into a drag.
+    ///
+    /// This prevents tiny pointer movements during a click from registering
+    /// as a drag.
+    ///
+    /// Defaults to 4 pixels.
+    pub const fn with_drag_threshold(mut self, threshold: f32) -> Self {
+        self.drag_threshold = Some(threshold);
+        self
+    }
+
+    /// Set the maximum time and distance (in pixels) between two clicks of the
+    /// same mouse button, for them to count as a double-click.
+    ///
+    /// Defaults to 500 milliseconds and 4 pixels.
+    pub const fn with_double_click(mut self, interval: Duration, radius: f32) -> Self {
+        self.double_click = Some((interval, radius));
+        self
+    }
+
+    /// Set the maximum number of game state updates to run to catch up on a
+    /// single (slow) frame.
+    ///
+    /// If the game falls further behind, the remaining time is discarded, and
+    /// the game slows down instead of trying to catch up indefinitely.
+    ///
+    /// Defaults to 10 updates.
+    pub const fn with_max_updates_per_frame(mut self, updates: u32) -> Self {
+        self.max_updates_per_frame = Some(updates);
+        self
+    }
+
     /// Use an existing game state.
     ///
     /// This can be used to resume an active game session.
@@ -67,12 +207,86 @@ impl Builder {
         self
     }
 
+    /// Resume the game state saved to the file at the given path.
+    ///
+    /// The file is loaded when the engine is built, replacing any game state
+    /// set using [`with_game_state()`].
+    ///
+    /// [`with_game_state()`]: Builder::with_game_state
+    pub fn with_saved_game_state(mut self, path: impl Into<PathBuf>) -> Self {
+        self.saved_game_state = Some(path.into());
+        self
+    }
+
+    /// Save the game state to the file at the given path whenever a plugin
+    /// requests the game to be saved.
+    ///
+    /// Without a save path, such requests are ignored. The saved game state
+    /// can be resumed using [`with_saved_game_state()`].
+    ///
+    /// [`with_saved_game_state()`]: Builder::with_saved_game_state
+    pub fn with_save_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.save_path = Some(path.into());
+        self
+    }
+
     /// Configure the width and height of the window.
     pub const fn with_window_dimensions(mut self, width: u16, height: u16) -> Self {
         self.canvas = Canvas::new(width, height);
         self
     }
 
+    /// Render the game in the given aspect ratio (width / height).
+    ///
+    /// If the aspect ratio differs from that of the window, the game is
+    /// centered in the window, with black bars filling the remaining space.
+    pub const fn with_target_aspect(mut self, aspect_ratio: f32) -> Self {
+        self.target_aspect = Some(aspect_ratio);
+        self
+    }
+
+    /// Draw an outline of the given color and width (in pixels) around the
+    /// bounds of the focused widget.
+    ///
+    /// This gives a visible indication of which widget receives input. By
+    /// default, no focus ring is drawn.
+    pub const fn with_focus_ring(mut self, color: Color, width: f32) -> Self {
+        self.focus_ring = Some(config::FocusRing { color, width });
+        self
+    }
+
+    /// Draw a thin outline around the bounds of every widget, on top of the
+    /// widget itself.
+    ///
+    /// This helps diagnosing layout and hit-testing issues. By default, no
+    /// bounds are drawn.
+    pub const fn with_debug_bounds(mut self) -> Self {
+        self.debug_bounds = true;
+        self
+    }
+
+    /// Render text using the TrueType font at the given path.
+    ///
+    /// The font is loaded when the window opens. If it can't be read, or no
+    /// font is configured, the font embedded with the `embedded-font` feature
+    /// is used, or else a font installed on the system.
+    pub fn with_font(mut self, path: impl Into<PathBuf>) -> Self {
+        self.font_path = Some(path.into());
+        self
+    }
+
+    /// Pass the next frame drawn to the given callback, whenever a plugin
+    /// requests a frame to be captured.
+    ///
+    /// The windowed backends take a screenshot of the frame, the null backend
+    /// rasterizes the game state on the CPU instead (see
+    /// [`Engine::capture_frame`]). Without a callback, such requests are
+    /// ignored.
+    pub fn on_frame_captured(mut self, callback: impl FnMut(Frame) + 'static) -> Self {
+        self.on_frame_captured = Some(frame::Callback::new(callback));
+        self
+    }
+
     /// Limit the frames per seconds to be equal or less than the refresh rate
     /// of the monitor.
     ///
@@ -84,13 +298,19 @@ impl Builder {
 
     /// The maximum frames the engine should render per second.
     ///
-    /// Set this to reduce the system resources used by the engine.
+    /// Set this to reduce the system resources used by the engine. A value of
+    /// `0` disables the cap.
+    ///
+    /// The frame rate is independent of the rate at which the game state is
+    /// updated, frames rendered in between two updates draw the same state.
     ///
     /// # Vertical synchronization
     ///
-    /// If [`with_vsync()`] is provided, the frames per second will be
+    /// If [`Builder::with_vsync`] is provided, the frames per second will be
     /// capped at the refresh rate of the active monitor, unless the maximum
-    /// frames per second is set at a lower value.
+    /// frames per second is set at a lower value. A cap that is not a divisor
+    /// of the refresh rate results in uneven frame times, as a frame can only
+    /// be presented on a refresh of the monitor.
     ///
     /// Defaults to unlimited frames per second.
     pub fn with_maximum_fps(mut self, fps: u16) -> Self {
@@ -106,17 +326,62 @@ impl Builder {
     /// one.
     ///
     /// This allows running the game (for example) on Macs with retina support.
+    /// Fonts are scaled by the same factor, see [`Canvas::scaled_font_size`].
     pub const fn with_hidpi_mode(mut self) -> Self {
         self.hidpi_mode = true;
         self
     }
 
+    /// Start the engine with the game simulation paused.
+    ///
+    /// While paused, widgets and plugins are not updated, but the last state of
+    /// the game is still rendered to the screen. The pause can be toggled at
+    /// runtime using the `Pause` key.
+    pub const fn with_paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// Start the engine with the mouse cursor hidden while it is over the
+    /// window (e.g. for fullscreen games that draw their own cursor).
+    ///
+    /// Plugins can show or hide the cursor at runtime, see
+    /// [`Command::CursorHidden`](common::Command::CursorHidden).
+    pub const fn with_cursor_hidden(mut self) -> Self {
+        self.cursor_hidden = true;
+        self
+    }
+
+    /// Record all input events to the file at the given path.
+    ///
+    /// The recording can be replayed using [`with_event_playback()`].
+    ///
+    /// [`with_event_playback()`]: Builder::with_event_playback
+    pub fn with_event_recording(mut self, path: impl Into<PathBuf>) -> Self {
+        self.recording = Some(RecorderMode::Record(path.into()));
+        self
+    }
+
+    /// Play back the input events recorded in the file at the given path.
+    ///
+    /// While playing back, all input events from the player are ignored. This
+    /// replaces any recording set using [`with_event_recording()`].
+    ///
+    /// [`with_event_recording()`]: Builder::with_event_recording
+    pub fn with_event_playback(mut self, path: impl Into<PathBuf>) -> Self {
+        self.recording = Some(RecorderMode::Playback(path.into()));
+        self
+    }
+
     /// Build the final [`Engine`].
     ///
     /// # Errors
     ///
     /// Returns an error if anything is misconfigured.
-    #[cfg(all(feature = "backend-ggez", not(feature = "backend-coffee")))]
+    #[cfg(all(
+        any(feature = "backend-ggez", feature = "backend-null"),
+        not(feature = "backend-coffee")
+    ))]
     pub fn build(mut self) -> Result<Engine, Error> {
         self.build_inner()
     }
@@ -155,37 +420,95 @@ impl Builder {
         use crate::backend::BUILDER;
 
         if unsafe { BUILDER.set(self) }.is_err() {
-            todo!("logging")
+            return Err(Error::AlreadyBuilt);
         }
 
         Ok(Engine::default())
     }
 
+    /// The canvas the game is rendered on, with a pixel density matching the
+    /// configured "high DPI" mode.
+    pub(crate) fn canvas(&self) -> Canvas {
+        let density = if self.hidpi_mode { 2.0 } else { 1.0 };
+
+        self.canvas.with_density(density)
+    }
+
     /// Actual logic to build the engine.
     ///
     /// This is split from the regular `build()` method because that method
     /// are implemented differently based on the enabled backend.
     pub(super) fn build_inner(&mut self) -> Result<Engine, Error> {
-        let mut game_state = mem::take(&mut self.game_state);
+        let mut game_state = match self.saved_game_state.take() {
+            Some(path) => GameState::load_from_path(&path)
+                .map_err(|err| Error::saved_game_state(&path, &err))?,
+            None => mem::take(&mut self.game_state),
+        };
         let mut plugin_handler = Box::new(wasm::Manager::default());
 
+        if let Some(retries) = self.plugin_read_retries {
+            plugin_handler.read_retries = retries;
+        }
+
+        plugin_handler.time_budget = self.plugin_time_budget;
+        plugin_handler.history_depth = self.event_history;
+
         for path in &self.plugin_paths {
-            for plugin in find_plugins_in_path(path)? {
+            for plugin in find_plugins_in_path(path, self.plugin_scan_depth)? {
                 plugin_handler.register_plugin(&mut game_state, &plugin)?;
             }
         }
 
-        let renderer = From::from(config::Renderer {
+        let mut renderer = crate::backend::Renderer::from(config::Renderer {
             max_frames_per_second: self.maximum_fps,
-            hidpi_mode: self.hidpi_mode,
+            vsync: self.vsync_enabled,
+            debug_bounds: self.debug_bounds,
+            font_path: self.font_path.take(),
+            ..config::Renderer::default()
         });
 
+        renderer.on_frame_captured = self.on_frame_captured.take();
+
+        let mut updater_config = config::Updater {
+            paused: self.paused,
+            ..config::Updater::default()
+        };
+
+        if let Some(threshold) = self.drag_threshold {
+            updater_config.drag_threshold = threshold;
+        }
+
+        if let Some((interval, radius)) = self.double_click {
+            updater_config.double_click_interval = interval;
+            updater_config.double_click_radius = radius;
+        }
+
+        if let Some(updates) = self.max_updates_per_frame {
+            updater_config.max_updates_per_frame = updates;
+        }
+
+        let mut updater = crate::backend::Updater::from(updater_config);
+
+        if let Some(mode) = self.recording.take() {
+            updater.recorder = Some(Recorder::new(mode)?);
+        }
+
+        let config = config::Engine {
+            canvas: self.canvas(),
+            window: self.canvas(),
+            target_aspect: self.target_aspect,
+            fullscreen: false,
+            cursor_hidden: self.cursor_hidden,
+            focus_ring: self.focus_ring,
+            save_path: self.save_path.take(),
+        };
+
         Ok(Engine {
-            config: self.canvas.into(),
+            config,
             plugin_handler,
             game_state,
+            updater,
             renderer,
-            ..Engine::default()
         })
     }
 }
@@ -195,7 +518,10 @@ impl Builder {
 /// Files with duplicate names are ignored. Even if two plugins reside in
 /// different directories, if their names are equal, only the first one is added
 /// to the list of plugins.
-fn find_plugins_in_path(path: &PathBuf) -> Result<Vec<PathBuf>, Error> {
+///
+/// If `max_depth` is set, directories nested deeper than the given depth are
+/// not searched.
+fn find_plugins_in_path(path: &PathBuf, max_depth: Option<usize>) -> Result<Vec<PathBuf>, Error> {
     use std::collections::HashSet;
     use std::ffi::OsStr;
     use walkdir::WalkDir;
@@ -203,7 +529,12 @@ fn find_plugins_in_path(path: &PathBuf) -> Result<Vec<PathBuf>, Error> {
     let mut paths = vec![];
     let mut duplicates = HashSet::new();
 
-    for entry in WalkDir::new(path) {
+    let mut walker = WalkDir::new(path);
+    if let Some(depth) = max_depth {
+        walker = walker.max_depth(depth);
+    }
+
+    for entry in walker {
         let entry = entry?;
 
         if entry.file_type().is_dir() {
@@ -245,6 +576,122 @@ mod tests {
         }
     }
 
+    mod find_plugins_in_path {
+        use super::*;
+        use std::fs;
+        use tempfile::TempDir;
+
+        fn plugin_dir() -> TempDir {
+            let dir = TempDir::new().expect("temporary directory");
+            fs::create_dir(dir.path().join("vendor")).unwrap();
+            fs::write(dir.path().join("flat.wasm"), "").unwrap();
+            fs::write(dir.path().join("vendor").join("nested.wasm"), "").unwrap();
+
+            dir
+        }
+
+        #[test]
+        fn unlimited_depth() {
+            let dir = plugin_dir();
+            let mut plugins = find_plugins_in_path(&dir.path().to_owned(), None).unwrap();
+            plugins.sort();
+
+            assert_eq!(
+                plugins,
+                vec![
+                    dir.path().join("flat.wasm"),
+                    dir.path().join("vendor").join("nested.wasm"),
+                ]
+            );
+        }
+
+        #[test]
+        fn limited_depth() {
+            let dir = plugin_dir();
+            let plugins = find_plugins_in_path(&dir.path().to_owned(), Some(1)).unwrap();
+
+            assert_eq!(plugins, vec![dir.path().join("flat.wasm")]);
+        }
+    }
+
+    mod with_plugin_read_retries {
+        use super::*;
+
+        #[test]
+        fn works() {
+            let mut builder = Builder::default().with_plugin_read_retries(5);
+            let mut engine = builder.build_inner().unwrap();
+
+            let manager = engine.plugin_handler.as_wasm().unwrap();
+            assert_eq!(manager.read_retries, 5);
+        }
+    }
+
+    mod with_drag_threshold {
+        use super::*;
+
+        #[test]
+        fn works() {
+            let mut builder = Builder::default().with_drag_threshold(10.0);
+            let engine = builder.build_inner().unwrap();
+
+            assert_eq!(engine.updater.config.drag_threshold, 10.0);
+        }
+    }
+
+    mod with_double_click {
+        use super::*;
+
+        #[test]
+        fn works() {
+            let interval = Duration::from_millis(300);
+            let mut builder = Builder::default().with_double_click(interval, 8.0);
+            let engine = builder.build_inner().unwrap();
+
+            assert_eq!(engine.updater.config.double_click_interval, interval);
+            assert_eq!(engine.updater.config.double_click_radius, 8.0);
+        }
+    }
+
+    mod with_max_updates_per_frame {
+        use super::*;
+
+        #[test]
+        fn works() {
+            let mut builder = Builder::default().with_max_updates_per_frame(3);
+            let engine = builder.build_inner().unwrap();
+
+            assert_eq!(engine.updater.config.max_updates_per_frame, 3);
+        }
+    }
+
+    mod with_plugin_time_budget {
+        use super::*;
+
+        #[test]
+        fn works() {
+            let budget = Duration::from_millis(5);
+            let mut builder = Builder::default().with_plugin_time_budget(budget);
+            let mut engine = builder.build_inner().unwrap();
+
+            let manager = engine.plugin_handler.as_wasm().unwrap();
+            assert_eq!(manager.time_budget, Some(budget));
+        }
+    }
+
+    mod with_event_history {
+        use super::*;
+
+        #[test]
+        fn works() {
+            let mut builder = Builder::default().with_event_history(8);
+            let mut engine = builder.build_inner().unwrap();
+
+            let manager = engine.plugin_handler.as_wasm().unwrap();
+            assert_eq!(manager.history_depth, 8);
+        }
+    }
+
     mod build {
         use super::*;
         use common::{PluginState, Value};
@@ -258,6 +705,90 @@ mod tests {
             assert!(builder.build().is_ok())
         }
 
+        #[test]
+        fn with_focus_ring() {
+            let mut builder = Builder::default();
+            assert!(builder.build_inner().unwrap().config.focus_ring.is_none());
+
+            let mut builder = Builder::default().with_focus_ring(Color::default(), 3.0);
+            let engine = builder.build_inner().unwrap();
+
+            assert_eq!(engine.config.focus_ring.map(|ring| ring.width), Some(3.0));
+        }
+
+        #[test]
+        fn with_font() {
+            let mut builder = Builder::default();
+            assert!(builder
+                .build_inner()
+                .unwrap()
+                .renderer
+                .config
+                .font_path
+                .is_none());
+
+            let mut builder = Builder::default().with_font("fonts/game.ttf");
+            let engine = builder.build_inner().unwrap();
+
+            assert_eq!(
+                engine.renderer.config.font_path,
+                Some(PathBuf::from("fonts/game.ttf"))
+            );
+        }
+
+        #[test]
+        fn on_frame_captured() {
+            let mut builder = Builder::default();
+            assert!(builder
+                .build_inner()
+                .unwrap()
+                .renderer
+                .on_frame_captured
+                .is_none());
+
+            let mut builder = Builder::default().on_frame_captured(|_| {});
+            let engine = builder.build_inner().unwrap();
+
+            assert!(engine.renderer.on_frame_captured.is_some());
+        }
+
+        #[test]
+        fn with_paused() {
+            let mut builder = Builder::default().with_paused();
+            let engine = builder.build_inner().unwrap();
+
+            assert!(engine.updater.is_paused)
+        }
+
+        #[test]
+        fn with_cursor_hidden() {
+            let mut builder = Builder::default();
+            assert!(!builder.build_inner().unwrap().config.cursor_hidden);
+
+            let mut builder = Builder::default().with_cursor_hidden();
+            let engine = builder.build_inner().unwrap();
+
+            assert!(engine.config.cursor_hidden)
+        }
+
+        #[test]
+        fn with_event_playback() {
+            let file = NamedTempFile::new().expect("temporary file");
+
+            let mut builder = Builder::default().with_event_playback(file.path());
+            let engine = builder.build_inner().unwrap();
+
+            assert!(engine.updater.recorder.is_some())
+        }
+
+        #[test]
+        fn with_invalid_event_playback() {
+            let mut builder = Builder::default().with_event_playback("foo");
+            let err = builder.build_inner().unwrap_err();
+
+            assert_eq!(err.to_string(), "event recorder error");
+        }
+
         #[test]
         fn with_valid_path() {
             let file = NamedTempFile::new().expect("temporary file");
@@ -304,5 +835,51 @@ mod tests {
                 Some(&Value::String("baz".to_owned()))
             );
         }
+
+        #[test]
+        fn with_saved_game_state() {
+            let file = NamedTempFile::new().expect("temporary file");
+            let widgets: HashMap<&str, _> = HashMap::default();
+            let mut state = HashMap::default();
+            state.insert("bar", "baz");
+
+            let mut game_state = GameState::default();
+            game_state.register_plugin_state("foo", PluginState::new(state, widgets));
+            game_state.set_plugin_paused("foo", true);
+            game_state.save_to_path(file.path()).unwrap();
+
+            let mut builder = Builder::default()
+                .with_game_state(GameState::default())
+                .with_saved_game_state(file.path());
+            let engine = builder.build_inner().unwrap();
+
+            assert!(engine.game_state.is_plugin_paused("foo"));
+            assert_eq!(
+                engine
+                    .game_state
+                    .get("foo")
+                    .and_then(|plugin| plugin.get("bar")),
+                Some(&Value::String("baz".to_owned()))
+            );
+        }
+
+        #[test]
+        fn with_save_path() {
+            let mut builder = Builder::default().with_save_path("save.json");
+            let engine = builder.build_inner().unwrap();
+
+            assert_eq!(engine.config.save_path, Some("save.json".into()));
+        }
+
+        #[test]
+        fn with_invalid_saved_game_state() {
+            let mut builder = Builder::default().with_saved_game_state("foo");
+            let err = builder.build_inner().unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "invalid saved game state `foo` (NotFound)".to_owned(),
+            )
+        }
     }
 }
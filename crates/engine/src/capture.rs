@@ -0,0 +1,434 @@
+//! Capture the rendered game state as a frame of pixels.
+//!
+//! This is the fallback of the null backend, which has no window to take a
+//! screenshot of. Frames are rasterized on the CPU from the same components
+//! the windowed backends draw, at the cost of not being pixel-perfect compared
+//! to their renderers (there is no anti-aliasing, and text is not rendered).
+
+use crate::{config::FocusRing, widget, Frame};
+use common::{Border, Canvas, Color, Component, GameState, Shape, Viewport};
+
+/// The color of the canvas outside of the viewport.
+const LETTERBOX_COLOR: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+
+/// Rasterize all visible widgets in the game state onto a canvas with the
+/// given background color, including the focus ring around the focused
+/// widget, and the bounds of all widgets, if configured.
+#[allow(clippy::integer_arithmetic)]
+pub(crate) fn capture(
+    state: &GameState,
+    canvas: Canvas,
+    viewport: Viewport,
+    background: Color,
+    focus_ring: Option<FocusRing>,
+    debug_bounds: bool,
+) -> Frame {
+    let (width, height) = canvas.dimensions();
+    let mut buffer = Buffer {
+        width,
+        height,
+        viewport,
+        pixels: vec![LETTERBOX_COLOR; usize::from(width) * usize::from(height)],
+    };
+
+    buffer.fill(background, |_, _| true);
+
+    for widget_with_position in state.widgets_in_render_order() {
+        if !widget_with_position.is_visible() {
+            continue;
+        }
+
+        let state = widget_with_position.state().clone().into();
+        let coordinates = widget_with_position.coordinates();
+
+        for component in widget::tinted_components(widget_with_position) {
+            buffer.draw(&component, coordinates);
+        }
+
+        if let Some(ring) = focus_ring.filter(|_| widget_with_position.focussed()) {
+            for component in widget::focus_ring(&state, ring) {
+                buffer.draw(&component, coordinates);
+            }
+        }
+
+        if debug_bounds {
+            for component in widget::debug_bounds(&state) {
+                buffer.draw(&component, coordinates);
+            }
+        }
+    }
+
+    let pixels = buffer
+        .pixels
+        .into_iter()
+        .flat_map(|color| {
+            let (r, g, b, a) = color.to_rgba();
+            vec![r, g, b, a]
+        })
+        .collect();
+
+    Frame {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// A buffer of colors to rasterize components onto.
+struct Buffer {
+    /// The width of the buffer.
+    width: u16,
+
+    /// The height of the buffer.
+    height: u16,
+
+    /// The area of the buffer components are drawn in, and clipped to.
+    viewport: Viewport,
+
+    /// The colors of the buffer, stored row by row.
+    pixels: Vec<Color>,
+}
+
+impl Buffer {
+    /// Draw a single component at the given viewport coordinates.
+    fn draw(&mut self, component: &Component, (x, y): (f32, f32)) {
+        let (x_rel, y_rel) = component.coordinates;
+        let (x, y) = (x + x_rel, y + y_rel);
+
+        match component.shape {
+            Shape::Circle {
+                radius,
+                fill,
+                border,
+            } => {
+                let distance = |px: f32, py: f32| {
+                    let (px, py) = component.unrotate((px - x, py - y));
+                    (px - radius).hypot(py - radius)
+                };
+
+                self.fill(component.tinted(fill), |px, py| distance(px, py) <= radius);
+
+                if let Some(border) = border {
+                    let inner_radius = radius - border.width;
+
+                    self.fill(component.tinted(border.color), |px, py| {
+                        let distance = distance(px, py);
+                        distance <= radius && distance > inner_radius
+                    });
+                }
+            }
+            Shape::Ring { color, .. } => self.fill_shape(component, color, (x, y)),
+            Shape::Rectangle { color, border, .. } => {
+                self.fill_shape(component, color, (x, y));
+
+                if let (Some(border), Some(corners)) = (border, component.shape.border_corners()) {
+                    self.outline(component, &corners, border, (x, y));
+                }
+            }
+            Shape::Line { color, .. } => self.fill_shape(component, color, (x, y)),
+            Shape::Triangle {
+                a,
+                b,
+                c,
+                fill,
+                border,
+            } => {
+                self.fill_shape(component, fill, (x, y));
+
+                if let Some(border) = border {
+                    self.outline(component, &[a, b, c], border, (x, y));
+                }
+            }
+            Shape::Polygon {
+                ref points,
+                fill,
+                border,
+            } => {
+                if points.len() < 3 {
+                    return;
+                }
+
+                self.fill_shape(component, fill, (x, y));
+
+                if let Some(border) = border {
+                    self.outline(component, points, border, (x, y));
+                }
+            }
+
+            // Text requires a font to rasterize, which the capture doesn't
+            // have access to.
+            Shape::Text { .. } => {}
+        }
+    }
+
+    /// Fill the area of the shape of the component in the given color.
+    fn fill_shape(&mut self, component: &Component, color: Color, (x, y): (f32, f32)) {
+        let shape = &component.shape;
+
+        self.fill(component.tinted(color), |px, py| {
+            let (px, py) = component.unrotate((px - x, py - y));
+            shape.contains_point(px, py)
+        })
+    }
+
+    /// Draw a border along the edges between the given points, with the last
+    /// point connecting back to the first one.
+    ///
+    /// The border is centered on the edges.
+    fn outline(
+        &mut self,
+        component: &Component,
+        points: &[(f32, f32)],
+        border: Border,
+        (x, y): (f32, f32),
+    ) {
+        let edges: Vec<_> = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(&from, &to)| Shape::Line {
+                from,
+                to,
+                width: border.width,
+                color: border.color,
+            })
+            .collect();
+
+        self.fill(component.tinted(border.color), |px, py| {
+            let (px, py) = component.unrotate((px - x, py - y));
+            edges.iter().any(|edge| edge.contains_point(px, py))
+        });
+    }
+
+    /// Blend the color onto all pixels within the viewport of which the
+    /// center lies within the area described by `contains`.
+    ///
+    /// The area is described in viewport coordinates.
+    #[allow(clippy::integer_arithmetic)]
+    fn fill(&mut self, color: Color, contains: impl Fn(f32, f32) -> bool) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (px, py) = (f32::from(x) + 0.5, f32::from(y) + 0.5);
+                if !self.viewport.contains(px, py) {
+                    continue;
+                }
+
+                let (px, py) = self.viewport.to_viewport_coordinates(px, py);
+                if !contains(px, py) {
+                    continue;
+                }
+
+                let index = usize::from(y) * usize::from(self.width) + usize::from(x);
+                if let Some(pixel) = self.pixels.get_mut(index) {
+                    *pixel = blend(color, *pixel);
+                }
+            }
+        }
+    }
+}
+
+/// Blend the source color over the destination color.
+fn blend(src: Color, dst: Color) -> Color {
+    let mix = |s: f32, d: f32| s * src.a + d * (1.0 - src.a);
+
+    Color::new(
+        mix(src.r, dst.r),
+        mix(src.g, dst.g),
+        mix(src.b, dst.b),
+        src.a + dst.a * (1.0 - src.a),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::config::BACKGROUND_COLOR;
+
+    #[test]
+    fn blend_opaque() {
+        let red = Color::from_rgb(255, 0, 0);
+
+        assert_eq!(blend(red, BACKGROUND_COLOR), red);
+    }
+
+    #[test]
+    fn blend_transparent() {
+        let transparent = Color::new(1.0, 0.0, 0.0, 0.0);
+
+        assert_eq!(blend(transparent, BACKGROUND_COLOR), BACKGROUND_COLOR);
+    }
+
+    #[test]
+    fn triangle() {
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        let shape = Shape::Triangle {
+            a: (0.0, 0.0),
+            b: (10.0, 0.0),
+            c: (0.0, 10.0),
+            fill: red,
+            border: Some(Border {
+                color: blue,
+                width: 2.0,
+                glow: None,
+            }),
+        };
+
+        let mut buffer = Buffer {
+            width: 20,
+            height: 20,
+            viewport: Canvas::new(20, 20).into(),
+            pixels: vec![BACKGROUND_COLOR; 400],
+        };
+
+        buffer.draw(&Component::new(shape, (0.0, 0.0)), (5.0, 5.0));
+
+        let pixel = |x: usize, y: usize| buffer.pixels[y * 20 + x];
+
+        assert_eq!(pixel(8, 8), red);
+        assert_eq!(pixel(10, 5), blue);
+        assert_eq!(pixel(5, 10), blue);
+        assert_eq!(pixel(12, 12), BACKGROUND_COLOR);
+        assert_eq!(pixel(2, 2), BACKGROUND_COLOR);
+    }
+
+    #[test]
+    fn ring() {
+        let red = Color::from_rgb(255, 0, 0);
+        let shape = Shape::Ring {
+            outer_radius: 8.0,
+            inner_radius: 4.0,
+            color: red,
+        };
+
+        let mut buffer = Buffer {
+            width: 20,
+            height: 20,
+            viewport: Canvas::new(20, 20).into(),
+            pixels: vec![BACKGROUND_COLOR; 400],
+        };
+
+        buffer.draw(&Component::new(shape, (0.0, 0.0)), (2.0, 2.0));
+
+        let pixel = |x: usize, y: usize| buffer.pixels[y * 20 + x];
+
+        assert_eq!(pixel(3, 9), red);
+        assert_eq!(pixel(9, 15), red);
+        assert_eq!(pixel(9, 9), BACKGROUND_COLOR);
+        assert_eq!(pixel(2, 2), BACKGROUND_COLOR);
+    }
+
+    #[test]
+    fn rectangle_border() {
+        let (red, blue) = (Color::from_rgb(255, 0, 0), Color::from_rgb(0, 0, 255));
+        let shape = Shape::Rectangle {
+            width: 10.0,
+            height: 10.0,
+            color: red,
+            rotation: 0.0,
+            border: Some(Border {
+                color: blue,
+                width: 2.0,
+                glow: None,
+            }),
+        };
+
+        let mut buffer = Buffer {
+            width: 20,
+            height: 20,
+            viewport: Canvas::new(20, 20).into(),
+            pixels: vec![BACKGROUND_COLOR; 400],
+        };
+
+        buffer.draw(&Component::new(shape, (0.0, 0.0)), (5.0, 5.0));
+
+        let pixel = |x: usize, y: usize| buffer.pixels[y * 20 + x];
+
+        assert_eq!(pixel(10, 10), red);
+        assert_eq!(pixel(5, 10), blue);
+        assert_eq!(pixel(14, 10), blue);
+
+        // The border does not extend beyond the rectangle.
+        assert_eq!(pixel(4, 10), BACKGROUND_COLOR);
+        assert_eq!(pixel(15, 10), BACKGROUND_COLOR);
+    }
+
+    #[test]
+    fn rotated_component() {
+        let red = Color::from_rgb(255, 0, 0);
+        let shape = Shape::Rectangle {
+            width: 10.0,
+            height: 2.0,
+            color: red,
+            rotation: 0.0,
+            border: None,
+        };
+
+        let mut buffer = Buffer {
+            width: 20,
+            height: 20,
+            viewport: Canvas::new(20, 20).into(),
+            pixels: vec![BACKGROUND_COLOR; 400],
+        };
+
+        // A quarter turn around the origin of the component turns the
+        // horizontal bar into a vertical one, left of the origin.
+        let component = Component {
+            rotation: std::f32::consts::FRAC_PI_2,
+            ..Component::new(shape, (0.0, 0.0))
+        };
+
+        buffer.draw(&component, (5.0, 5.0));
+
+        let pixel = |x: usize, y: usize| buffer.pixels[y * 20 + x];
+
+        assert_eq!(pixel(4, 10), red);
+        assert_eq!(pixel(3, 14), red);
+        assert_eq!(pixel(10, 5), BACKGROUND_COLOR);
+        assert_eq!(pixel(5, 10), BACKGROUND_COLOR);
+    }
+
+    #[test]
+    fn polygon() {
+        let red = Color::from_rgb(255, 0, 0);
+        let shape = |points| Shape::Polygon {
+            points,
+            fill: red,
+            border: None,
+        };
+
+        let mut buffer = Buffer {
+            width: 10,
+            height: 10,
+            viewport: Canvas::new(10, 10).into(),
+            pixels: vec![BACKGROUND_COLOR; 100],
+        };
+
+        let square = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        buffer.draw(&Component::new(shape(square), (0.0, 0.0)), (1.0, 1.0));
+
+        // Degenerate polygons are skipped.
+        let line = vec![(0.0, 0.0), (9.0, 9.0)];
+        buffer.draw(&Component::new(shape(line), (0.0, 0.0)), (0.0, 0.0));
+
+        assert_eq!(buffer.pixels[2 * 10 + 2], red);
+        assert_eq!(buffer.pixels[4 * 10 + 4], red);
+        assert_eq!(buffer.pixels[6 * 10 + 6], BACKGROUND_COLOR);
+        assert_eq!(buffer.pixels[8 * 10 + 8], BACKGROUND_COLOR);
+    }
+
+    #[test]
+    fn pixel_out_of_bounds() {
+        let frame = capture(
+            &GameState::default(),
+            Canvas::new(2, 2),
+            Canvas::new(2, 2).into(),
+            BACKGROUND_COLOR,
+            None,
+            false,
+        );
+
+        assert_eq!(frame.pixels.len(), 16);
+        assert!(frame.pixel(1, 1).is_some());
+        assert!(frame.pixel(2, 1).is_none());
+    }
+}
@@ -0,0 +1,166 @@
+//! Detect double-clicks from the single clicks reported by the backends.
+
+use common::{event, Event};
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+
+/// Tracks the last click of each mouse button, to detect double-clicks.
+#[derive(Debug)]
+pub(crate) struct Clicks {
+    /// The maximum time (in nanoseconds) between two clicks of a double-click.
+    interval: u64,
+
+    /// The maximum distance (in pixels) between two clicks of a double-click.
+    radius: f32,
+
+    /// The time (in nanoseconds) and position of the last click of each
+    /// button, that isn't already part of a double-click.
+    last: HashMap<event::MouseButton, (u64, (f32, f32))>,
+}
+
+impl Clicks {
+    /// Create a new tracker, detecting clicks of the same button within the
+    /// given interval and radius as double-clicks.
+    pub(crate) fn new(interval: Duration, radius: f32) -> Self {
+        Self {
+            interval: u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX),
+            radius,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Add a double-click event after every click that completes a
+    /// double-click.
+    ///
+    /// `now` is the elapsed game time (in nanoseconds) at which the events
+    /// occurred. The click starting a double-click can't start another one, so
+    /// a triple-click results in a single double-click.
+    pub(crate) fn detect(&mut self, events: &mut Vec<Event>, now: u64) {
+        let mut detected = Vec::with_capacity(events.len());
+
+        for event in events.drain(..) {
+            let double_click = match &event {
+                Event::Input(event::Input::MouseClick { button, x, y }) => {
+                    self.click(button, (*x, *y), now)
+                }
+                _ => None,
+            };
+
+            detected.push(event);
+            detected.extend(double_click.map(Event::Input));
+        }
+
+        *events = detected;
+    }
+
+    /// Track a click, and return a double-click event if the click completes
+    /// one.
+    fn click(
+        &mut self,
+        button: &event::MouseButton,
+        (x, y): (f32, f32),
+        now: u64,
+    ) -> Option<event::Input> {
+        let interval = self.interval;
+        let radius = self.radius;
+        let is_double_click = self.last.remove(button).map_or(false, |(time, (x0, y0))| {
+            now.saturating_sub(time) <= interval && (x - x0).hypot(y - y0) <= radius
+        });
+
+        if is_double_click {
+            let button = button.clone();
+            return Some(event::Input::DoubleClick { button, x, y });
+        }
+
+        self.last.insert(button.clone(), (now, (x, y)));
+        None
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod detect {
+        use super::*;
+
+        fn click(button: event::MouseButton, x: f32) -> Event {
+            Event::Input(event::Input::MouseClick { button, x, y: 0.0 })
+        }
+
+        fn double_clicks(clicks: &mut Clicks, events: Vec<(u64, Event)>) -> usize {
+            let mut count = 0;
+
+            for (now, event) in events {
+                let mut events = vec![event];
+                clicks.detect(&mut events, now);
+
+                count += events
+                    .iter()
+                    .filter(|e| matches!(e, Event::Input(event::Input::DoubleClick { .. })))
+                    .count();
+            }
+
+            count
+        }
+
+        fn clicks() -> Clicks {
+            Clicks::new(Duration::from_millis(500), 4.0)
+        }
+
+        const MS: u64 = 1_000_000;
+
+        #[test]
+        fn double_click() {
+            let left = || click(event::MouseButton::Left, 10.0);
+            let mut events = vec![left(), left()];
+
+            clicks().detect(&mut events, 0);
+
+            assert_eq!(
+                events,
+                vec![
+                    left(),
+                    left(),
+                    Event::Input(event::Input::DoubleClick {
+                        button: event::MouseButton::Left,
+                        x: 10.0,
+                        y: 0.0,
+                    }),
+                ]
+            );
+        }
+
+        #[test]
+        fn within_window() {
+            let left = |x| click(event::MouseButton::Left, x);
+
+            let events = vec![(0, left(10.0)), (500 * MS, left(13.0))];
+            assert_eq!(double_clicks(&mut clicks(), events), 1);
+
+            let events = vec![(0, left(10.0)), (501 * MS, left(10.0))];
+            assert_eq!(double_clicks(&mut clicks(), events), 0);
+
+            let events = vec![(0, left(10.0)), (100 * MS, left(15.0))];
+            assert_eq!(double_clicks(&mut clicks(), events), 0);
+        }
+
+        #[test]
+        fn different_buttons() {
+            let events = vec![
+                (0, click(event::MouseButton::Left, 10.0)),
+                (100 * MS, click(event::MouseButton::Right, 10.0)),
+            ];
+
+            assert_eq!(double_clicks(&mut clicks(), events), 0);
+        }
+
+        #[test]
+        fn triple_click() {
+            let left = || click(event::MouseButton::Left, 10.0);
+            let events = vec![(0, left()), (100 * MS, left()), (200 * MS, left())];
+
+            assert_eq!(double_clicks(&mut clicks(), events), 1);
+        }
+    }
+}
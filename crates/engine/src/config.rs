@@ -1,17 +1,84 @@
 //! Objects to encapsulate configurations of parts of the engine.
 
-use common::Canvas;
+use common::{Canvas, Color, Viewport};
+use std::{path::PathBuf, time::Duration};
+
+/// The color in which the background of the game is rendered, unless changed
+/// by a plugin.
+pub(super) const BACKGROUND_COLOR: Color = Color::new(0.1, 0.2, 0.3, 1.0);
 
 /// Top-level engine configuration.
 #[derive(Debug)]
 pub(super) struct Engine {
     /// The canvas the engine draws on.
+    ///
+    /// This is the size the game is laid out at by plugins, regardless of
+    /// the current size of the window.
     pub canvas: Canvas,
+
+    /// The current size of the window, which differs from the canvas once the
+    /// window is resized (e.g. when switching to fullscreen mode).
+    pub window: Canvas,
+
+    /// The aspect ratio (width / height) the game is rendered in.
+    ///
+    /// If the aspect ratio differs from that of the canvas, the game is
+    /// letterboxed within the canvas.
+    pub target_aspect: Option<f32>,
+
+    /// Whether or not the game runs in fullscreen mode.
+    pub fullscreen: bool,
+
+    /// Whether or not the mouse cursor is hidden while it is over the window.
+    pub cursor_hidden: bool,
+
+    /// The outline drawn around the focused widget, if any.
+    pub focus_ring: Option<FocusRing>,
+
+    /// The path to which the game state is saved when a plugin requests it.
+    pub save_path: Option<PathBuf>,
+}
+
+impl Engine {
+    /// The area of the window in which the game is rendered.
+    ///
+    /// The viewport is scaled to fit the game, as laid out on the (letterboxed)
+    /// canvas.
+    pub fn viewport(&self) -> Viewport {
+        let letterbox = |canvas: Canvas| match self.target_aspect {
+            Some(aspect_ratio) => canvas.with_letterbox(aspect_ratio),
+            None => canvas.into(),
+        };
+
+        let reference = letterbox(self.canvas);
+        letterbox(self.window).with_reference(reference.width, reference.height)
+    }
+
+    /// Record the new size of the window, in logical pixels.
+    ///
+    /// Dimensions are rounded to the nearest pixel.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::as_conversions
+    )]
+    pub fn resize_window(&mut self, width: f32, height: f32) {
+        self.window = Canvas::new(width.round() as u16, height.round() as u16)
+            .with_density(self.canvas.density());
+    }
 }
 
 impl From<Canvas> for Engine {
     fn from(canvas: Canvas) -> Self {
-        Self { canvas }
+        Self {
+            canvas,
+            window: canvas,
+            target_aspect: None,
+            fullscreen: false,
+            cursor_hidden: false,
+            focus_ring: None,
+            save_path: None,
+        }
     }
 }
 
@@ -19,10 +86,26 @@ impl Default for Engine {
     fn default() -> Self {
         Self {
             canvas: Canvas::new(800, 600),
+            window: Canvas::new(800, 600),
+            target_aspect: None,
+            fullscreen: false,
+            cursor_hidden: false,
+            focus_ring: None,
+            save_path: None,
         }
     }
 }
 
+/// The outline drawn around the bounds of a focused widget.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct FocusRing {
+    /// The color of the outline.
+    pub color: Color,
+
+    /// The width (in pixels) of the outline.
+    pub width: f32,
+}
+
 /// Configuration for the "updater" system.
 #[derive(Debug)]
 pub(super) struct Updater {
@@ -31,12 +114,59 @@ pub(super) struct Updater {
     /// This also means each registered plugin will run as much as this value is
     /// set to.
     pub updates_per_second: u64,
+
+    /// Whether or not the updater starts in a paused state.
+    ///
+    /// A paused updater does not update the game state, but the renderer keeps
+    /// drawing the last known state.
+    pub paused: bool,
+
+    /// The distance (in pixels) the pointer has to move while a mouse button
+    /// is pressed, before the press turns into a drag.
+    ///
+    /// Anything below this distance is considered a click.
+    pub drag_threshold: f32,
+
+    /// The maximum time between two clicks of the same mouse button, for them
+    /// to count as a double-click.
+    pub double_click_interval: Duration,
+
+    /// The maximum distance (in pixels) between two clicks of the same mouse
+    /// button, for them to count as a double-click.
+    pub double_click_radius: f32,
+
+    /// The maximum number of updates to run to catch up on a single (slow)
+    /// frame.
+    ///
+    /// Any remaining time is discarded, to prevent the updater from falling
+    /// further and further behind when the updates take longer than the time
+    /// they simulate.
+    pub max_updates_per_frame: u32,
+}
+
+impl Updater {
+    /// The fixed amount of time (in seconds) between two updates.
+    #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+    pub fn delta(&self) -> f32 {
+        1.0 / self.updates_per_second as f32
+    }
+
+    /// The fixed amount of time (in nanoseconds) between two updates.
+    #[allow(clippy::integer_arithmetic, clippy::integer_division)]
+    pub fn update_interval(&self) -> u64 {
+        1_000_000_000 / self.updates_per_second
+    }
 }
 
 impl Default for Updater {
     fn default() -> Self {
         Self {
             updates_per_second: 100,
+            paused: false,
+            drag_threshold: 4.0,
+            double_click_interval: Duration::from_millis(500),
+            double_click_radius: 4.0,
+            max_updates_per_frame: 10,
         }
     }
 }
@@ -45,19 +175,83 @@ impl Default for Updater {
 #[derive(Debug)]
 pub(super) struct Renderer {
     /// The amount of frames per second the renderer will run.
+    ///
+    /// `None` (or `Some(0)`) means the frames per second are not capped.
     pub max_frames_per_second: Option<u16>,
 
-    /// Whether or not the game should run in "high DPI" mode.
-    ///
-    /// Used for (amongst others) Retina Macs.
-    pub hidpi_mode: bool,
+    /// Whether or not to synchronize rendering with the refresh rate of the
+    /// monitor.
+    pub vsync: bool,
+
+    /// The color of the background of the game, within the viewport.
+    pub background_color: Color,
+
+    /// Whether or not to draw an outline around the bounds of every widget,
+    /// to debug layout and hit-testing issues.
+    pub debug_bounds: bool,
+
+    /// The path to the font used to render text, if any.
+    pub font_path: Option<PathBuf>,
+}
+
+impl Renderer {
+    /// The minimum amount of time (in nanoseconds) between two rendered
+    /// frames, or `0` if the frames per second are not capped.
+    #[allow(clippy::integer_division, clippy::integer_arithmetic)]
+    pub fn minimum_nanoseconds_between_renders(&self) -> u64 {
+        match self.max_frames_per_second {
+            Some(fps) if fps > 0 => 1_000_000_000 / u64::from(fps),
+            _ => 0,
+        }
+    }
 }
 
 impl Default for Renderer {
     fn default() -> Self {
         Self {
             max_frames_per_second: Some(90),
-            hidpi_mode: false,
+            vsync: false,
+            background_color: BACKGROUND_COLOR,
+            debug_bounds: false,
+            font_path: None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+
+    mod viewport {
+        use super::*;
+
+        #[test]
+        fn unchanged_window() {
+            let config = Engine::from(Canvas::new(800, 600));
+
+            assert_eq!(config.viewport(), Viewport::from(Canvas::new(800, 600)));
+        }
+
+        #[test]
+        fn resized_window() {
+            let mut config = Engine {
+                target_aspect: Some(4.0 / 3.0),
+                ..Engine::from(Canvas::new(800, 600))
+            };
+            config.resize_window(1920.0, 1080.0);
+
+            let viewport = config.viewport();
+            assert_eq!((viewport.x, viewport.y), (240.0, 0.0));
+            assert_eq!((viewport.width, viewport.height), (1440.0, 1080.0));
+            assert_eq!(viewport.scale, 1.8);
+
+            // Plugins keep laying out the game at the size of the canvas.
+            assert_eq!(Canvas::from(viewport).dimensions(), (800, 600));
+            assert_eq!(
+                viewport.to_viewport_coordinates(960.0, 540.0),
+                (400.0, 300.0)
+            );
         }
     }
 }
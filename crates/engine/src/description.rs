@@ -0,0 +1,71 @@
+//! A structured description of the engine, used for debugging.
+//!
+//! The description is a snapshot of the engine at the time it was requested.
+//! It does not change when the engine continues to run.
+
+use common::widget::Kind;
+
+/// A description of the engine, its configuration, and all loaded plugins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Engine {
+    /// The dimensions (width, height) of the canvas.
+    pub canvas: (u16, u16),
+
+    /// The aspect ratio (width / height) the game is rendered in, if any.
+    pub target_aspect: Option<f32>,
+
+    /// Whether or not the game runs in fullscreen mode.
+    pub fullscreen: bool,
+
+    /// The amount of updates per second the engine does.
+    pub updates_per_second: u64,
+
+    /// Whether or not the updater is paused.
+    pub paused: bool,
+
+    /// The loaded plugins, sorted by name.
+    pub plugins: Vec<Plugin>,
+}
+
+/// A description of a loaded plugin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plugin {
+    /// The name of the plugin.
+    pub name: String,
+
+    /// Whether or not the plugin is paused.
+    pub paused: bool,
+
+    /// The plugins this plugin depends on, whose state it can read.
+    pub dependencies: Vec<String>,
+
+    /// The minimum version of the engine the plugin is compatible with, if
+    /// any.
+    pub min_engine_version: Option<String>,
+
+    /// The minimum number of engine ticks between two runs of the plugin, or
+    /// `None` if it runs on every tick.
+    pub update_interval: Option<u32>,
+
+    /// The keys of the shared state the plugin declared, sorted by key.
+    pub shared_keys: Vec<String>,
+
+    /// The widgets owned by the plugin, sorted by name.
+    pub widgets: Vec<Widget>,
+}
+
+/// A description of a widget owned by a plugin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Widget {
+    /// The name of the widget, as named by the plugin owning it.
+    pub name: String,
+
+    /// The kind of widget.
+    pub kind: Kind,
+
+    /// The coordinates of the widget on the canvas.
+    pub coordinates: (f32, f32),
+
+    /// Whether or not the widget is drawn.
+    pub visible: bool,
+}
@@ -1,12 +1,14 @@
 //! The main engine implementation.
 
 use crate::{
-    backend::{Renderer, Updater},
-    config,
+    backend::{self, Backend, Renderer, Updater},
+    config, description,
     plugin::Handler,
     Builder, Error,
 };
-use common::GameState;
+#[cfg(any(test, feature = "backend-null"))]
+use crate::{capture, Frame};
+use common::{Command, GameState};
 
 /// The top-level object that holds all the configuration, state, and logic.
 #[derive(Debug)]
@@ -48,7 +50,545 @@ impl Engine {
     }
 
     /// Run the engine to completion or until an error occurs.
+    ///
+    /// The engine runs on the backend selected through the `backend-*`
+    /// features, see [`Backend`].
     pub fn run(self) -> Result<(), Error> {
-        crate::backend::run(self)
+        <backend::Selected as Backend>::run(self)
+    }
+
+    /// Capture the current state of the game as a frame of RGBA pixels, the
+    /// size of the window.
+    ///
+    /// Only the null backend can capture frames on demand. There is no window
+    /// to take a screenshot of, so the frame is rasterized on the CPU instead,
+    /// which is not pixel-perfect (there is no anti-aliasing, and text is not
+    /// rendered). The windowed backends consume the engine when it runs, so
+    /// plugins request their frames to be captured instead, see
+    /// [`Builder::on_frame_captured`].
+    #[cfg(any(test, feature = "backend-null"))]
+    pub fn capture_frame(&self) -> Frame {
+        capture::capture(
+            &self.game_state,
+            self.config.window,
+            self.config.viewport(),
+            self.renderer.config.background_color,
+            self.config.focus_ring,
+            self.renderer.config.debug_bounds,
+        )
+    }
+
+    /// Describe the current state of the engine.
+    ///
+    /// This aggregates the configuration, loaded plugins and their widgets into
+    /// a single structured object, which can be used to inspect the engine
+    /// while debugging, or in tests.
+    pub fn describe(&self) -> description::Engine {
+        let mut plugins: Vec<_> = self
+            .game_state
+            .plugins()
+            .into_iter()
+            .map(|(name, state)| {
+                let mut widgets: Vec<_> = state
+                    .widgets()
+                    .into_iter()
+                    .map(|(name, widget)| description::Widget {
+                        name: name.to_owned(),
+                        kind: widget.state().kind().clone(),
+                        coordinates: widget.coordinates(),
+                        visible: widget.is_visible(),
+                    })
+                    .collect();
+
+                widgets.sort_by(|a, b| a.name.cmp(&b.name));
+
+                // Plugins without a registration (e.g. state loaded from a save
+                // file) are described without registration details.
+                let registration = self.plugin_handler.registration(name);
+                let mut shared_keys: Vec<_> = registration
+                    .and_then(|registration| registration.shared.as_ref())
+                    .map(|shared| shared.keys().cloned().collect())
+                    .unwrap_or_default();
+                shared_keys.sort();
+
+                description::Plugin {
+                    name: name.to_owned(),
+                    paused: self.game_state.is_plugin_paused(name),
+                    dependencies: registration
+                        .and_then(|registration| registration.dependencies.clone())
+                        .unwrap_or_default(),
+                    min_engine_version: registration
+                        .and_then(|registration| registration.min_engine_version.clone()),
+                    update_interval: registration
+                        .and_then(|registration| registration.update_interval),
+                    shared_keys,
+                    widgets,
+                }
+            })
+            .collect();
+
+        plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+        description::Engine {
+            canvas: self.config.canvas.dimensions(),
+            target_aspect: self.config.target_aspect,
+            fullscreen: self.config.fullscreen,
+            updates_per_second: self.updater.config.updates_per_second,
+            paused: self.updater.is_paused,
+            plugins,
+        }
+    }
+
+    /// Process the commands issued by plugins, in order.
+    ///
+    /// The backend is responsible for applying any configuration changes (such
+    /// as switching to fullscreen mode) to the window.
+    pub(super) fn process_commands(&mut self, commands: Vec<Command>) {
+        for command in commands {
+            match command {
+                Command::Quit => self.updater.is_finished = true,
+                Command::Fullscreen(fullscreen) => self.config.fullscreen = fullscreen,
+                Command::CursorHidden(hidden) => self.config.cursor_hidden = hidden,
+                Command::Background(color) => self.renderer.config.background_color = color,
+                Command::Save => self.save(),
+                Command::CaptureFrame => self.renderer.capture_requested = true,
+            }
+        }
+    }
+
+    /// Save the game state to the configured save path.
+    ///
+    /// Failing to save doesn't stop the game, the error is logged instead.
+    fn save(&self) {
+        let path = match &self.config.save_path {
+            Some(path) => path,
+            None => {
+                log::warn!("unable to save game state: no save path configured");
+                return;
+            }
+        };
+
+        if let Err(err) = self.game_state.save_to_path(path) {
+            log::error!("unable to save game state to `{}`: {}", path.display(), err);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use crate::plugin::mock;
+    use common::{widget, Canvas, Color, PluginState, Registration, Time, Value};
+    use std::collections::HashMap;
+
+    mod capture_frame {
+        use super::*;
+
+        #[test]
+        fn known_widget() {
+            let red = Color::from_rgb(255, 0, 0);
+            let mut engine = Engine {
+                config: Canvas::new(100, 50).into(),
+                ..Engine::default()
+            };
+
+            let widgets: HashMap<_, _> =
+                vec![
+                    widget::Builder::new("button", widget::Kind::ButtonRectangle)
+                        .attribute("width", 20.0)
+                        .attribute("height", 10.0)
+                        .attribute("idle_color", red)
+                        .position(10.0, 10.0)
+                        .build(),
+                ]
+                .into_iter()
+                .collect();
+
+            let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+            engine.game_state.register_plugin_state("test", state);
+
+            let frame = engine.capture_frame();
+
+            assert_eq!((frame.width, frame.height), (100, 50));
+            assert_eq!(frame.pixels.len(), 100 * 50 * 4);
+            assert_eq!(frame.pixel(15, 15), Some(red));
+            assert_eq!(frame.pixel(29, 19), Some(red));
+
+            // background
+            assert_eq!(
+                frame.pixel(30, 20).map(Color::to_rgba),
+                Some((25, 51, 76, 255))
+            );
+        }
+
+        #[test]
+        fn tinted_widget() {
+            let white = Color::from_rgb(255, 255, 255);
+            let red = Color::from_rgb(255, 0, 0);
+            let mut engine = Engine {
+                config: Canvas::new(100, 50).into(),
+                ..Engine::default()
+            };
+
+            let mut button = widget::Builder::new("button", widget::Kind::ButtonRectangle)
+                .attribute("width", 20.0)
+                .attribute("height", 10.0)
+                .attribute("idle_color", white)
+                .position(10.0, 10.0)
+                .tint(red)
+                .build();
+
+            let mut capture = |button| {
+                let widgets: HashMap<_, _> = vec![button].into_iter().collect();
+                let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+                engine.game_state.register_plugin_state("test", state);
+
+                engine.capture_frame()
+            };
+
+            let frame = capture(button.clone());
+            assert_eq!(frame.pixel(15, 15), Some(red));
+
+            button.1.set_tint(white);
+            let frame = capture(button);
+            assert_eq!(frame.pixel(15, 15), Some(white));
+        }
+
+        #[test]
+        fn focus_ring() {
+            let red = Color::from_rgb(255, 0, 0);
+            let white = Color::from_rgb(255, 255, 255);
+            let background = Some((25, 51, 76, 255));
+
+            let mut engine = Engine {
+                config: Canvas::new(100, 50).into(),
+                ..Engine::default()
+            };
+
+            engine.config.focus_ring = Some(config::FocusRing {
+                color: white,
+                width: 2.0,
+            });
+
+            let mut button = widget::Builder::new("button", widget::Kind::ButtonRectangle)
+                .attribute("width", 20.0)
+                .attribute("height", 10.0)
+                .attribute("idle_color", red)
+                .position(10.0, 10.0)
+                .build();
+
+            let capture = |engine: &mut Engine, button| {
+                let widgets: HashMap<_, _> = vec![button].into_iter().collect();
+                let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+                engine.game_state.register_plugin_state("test", state);
+
+                engine.capture_frame()
+            };
+
+            // Without focus, no ring is drawn.
+            let frame = capture(&mut engine, button.clone());
+            assert_eq!(frame.pixel(9, 15).map(Color::to_rgba), background);
+
+            button.1.focus();
+            let frame = capture(&mut engine, button);
+
+            assert_eq!(frame.pixel(15, 15), Some(red));
+            assert_eq!(frame.pixel(9, 15), Some(white));
+            assert_eq!(frame.pixel(8, 8), Some(white));
+            assert_eq!(frame.pixel(30, 20), Some(white));
+            assert_eq!(frame.pixel(7, 15).map(Color::to_rgba), background);
+            assert_eq!(frame.pixel(32, 15).map(Color::to_rgba), background);
+        }
+
+        #[test]
+        fn debug_bounds() {
+            let red = Color::from_rgb(255, 0, 0);
+            let magenta = Color::from_rgb(255, 0, 255);
+
+            let mut engine = Engine {
+                config: Canvas::new(100, 50).into(),
+                ..Engine::default()
+            };
+
+            let widgets: HashMap<_, _> = vec![
+                widget::Builder::new("circle", widget::Kind::MovingCircle)
+                    .attribute("radius", 10.0)
+                    .attribute("fill_color", red)
+                    .position(10.0, 10.0)
+                    .build(),
+                widget::Builder::new("other", widget::Kind::MovingCircle)
+                    .attribute("radius", 5.0)
+                    .position(50.0, 10.0)
+                    .build(),
+            ]
+            .into_iter()
+            .collect();
+
+            let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+            engine.game_state.register_plugin_state("test", state);
+
+            // The corners of the bounds fall outside of the circles.
+            let frame = engine.capture_frame();
+            assert_eq!(
+                frame.pixel(10, 10).map(Color::to_rgba),
+                Some((25, 51, 76, 255))
+            );
+
+            engine.renderer.config.debug_bounds = true;
+            let frame = engine.capture_frame();
+
+            assert_eq!(frame.pixel(20, 20), Some(red));
+            assert_eq!(frame.pixel(10, 10), Some(magenta));
+            assert_eq!(frame.pixel(29, 29), Some(magenta));
+            assert_eq!(frame.pixel(50, 10), Some(magenta));
+            assert_eq!(frame.pixel(59, 19), Some(magenta));
+        }
+
+        #[test]
+        fn letterboxed_window() {
+            let red = Color::from_rgb(255, 0, 0);
+            let black = Some((0, 0, 0, 255));
+
+            // The game is laid out at 100x50, and scaled up twice to fit the
+            // window, leaving bars at the top and bottom.
+            let mut engine = Engine {
+                config: config::Engine {
+                    target_aspect: Some(2.0),
+                    ..Canvas::new(100, 50).into()
+                },
+                ..Engine::default()
+            };
+            engine.config.resize_window(200.0, 200.0);
+
+            let widgets: HashMap<_, _> = vec![
+                widget::Builder::new("button", widget::Kind::ButtonRectangle)
+                    .attribute("width", 20.0)
+                    .attribute("height", 10.0)
+                    .attribute("idle_color", red)
+                    .position(10.0, 10.0)
+                    .build(),
+                widget::Builder::new("overflow", widget::Kind::ButtonRectangle)
+                    .attribute("width", 20.0)
+                    .attribute("height", 10.0)
+                    .attribute("idle_color", red)
+                    .position(90.0, 45.0)
+                    .build(),
+            ]
+            .into_iter()
+            .collect();
+
+            let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+            engine.game_state.register_plugin_state("test", state);
+
+            let frame = engine.capture_frame();
+
+            assert_eq!((frame.width, frame.height), (200, 200));
+            assert_eq!(frame.pixel(30, 10).map(Color::to_rgba), black);
+            assert_eq!(frame.pixel(21, 71), Some(red));
+            assert_eq!(frame.pixel(58, 88), Some(red));
+            assert_eq!(
+                frame.pixel(15, 75).map(Color::to_rgba),
+                Some((25, 51, 76, 255))
+            );
+
+            // Widgets are clipped to the viewport.
+            assert_eq!(frame.pixel(190, 145), Some(red));
+            assert_eq!(frame.pixel(190, 155).map(Color::to_rgba), black);
+        }
+    }
+
+    mod describe {
+        use super::*;
+
+        #[test]
+        fn empty() {
+            let description = Engine::default().describe();
+
+            assert_eq!(description.canvas, (800, 600));
+            assert_eq!(description.updates_per_second, 100);
+            assert!(description.plugins.is_empty());
+        }
+
+        #[test]
+        fn plugin_widgets() {
+            let mut engine = Engine::default();
+
+            let widgets: HashMap<_, _> = vec![
+                widget::Builder::new("circle", widget::Kind::MovingCircle)
+                    .position(10.0, 20.0)
+                    .build(),
+                widget::Builder::new("button", widget::Kind::ButtonRectangle).build(),
+            ]
+            .into_iter()
+            .collect();
+
+            let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+            engine.game_state.register_plugin_state("test", state);
+            engine.game_state.set_plugin_paused("test", true);
+
+            let description = engine.describe();
+
+            assert_eq!(
+                description.plugins,
+                vec![description::Plugin {
+                    name: "test".to_owned(),
+                    paused: true,
+                    dependencies: vec![],
+                    min_engine_version: None,
+                    update_interval: None,
+                    shared_keys: vec![],
+                    widgets: vec![
+                        description::Widget {
+                            name: "button".to_owned(),
+                            kind: widget::Kind::ButtonRectangle,
+                            coordinates: (0.0, 0.0),
+                            visible: true,
+                        },
+                        description::Widget {
+                            name: "circle".to_owned(),
+                            kind: widget::Kind::MovingCircle,
+                            coordinates: (10.0, 20.0),
+                            visible: true,
+                        },
+                    ],
+                }]
+            );
+        }
+
+        #[test]
+        fn plugin_registration() {
+            let mut shared = HashMap::new();
+            shared.insert("turn".to_owned(), Value::from(0));
+            shared.insert("score".to_owned(), Value::from(0));
+
+            let registration = Registration {
+                name: "test".to_owned(),
+                dependencies: Some(vec!["hud".to_owned()]),
+                shared: Some(shared),
+                min_engine_version: Some("0.1.0".to_owned()),
+                update_interval: Some(10),
+                ..Registration::default()
+            };
+
+            let mut manager = mock::Manager::default();
+            manager.plugins.push(mock::Plugin {
+                name: "test".to_owned(),
+                registration: Some(registration),
+                ..mock::Plugin::default()
+            });
+
+            let mut engine = Engine {
+                plugin_handler: Box::new(manager),
+                ..Engine::default()
+            };
+            engine
+                .game_state
+                .register_plugin_state("test", PluginState::default());
+
+            let plugin = &engine.describe().plugins[0];
+
+            assert_eq!(plugin.dependencies, vec!["hud".to_owned()]);
+            assert_eq!(plugin.min_engine_version, Some("0.1.0".to_owned()));
+            assert_eq!(plugin.update_interval, Some(10));
+            assert_eq!(
+                plugin.shared_keys,
+                vec!["score".to_owned(), "turn".to_owned()]
+            );
+        }
+    }
+
+    mod process_commands {
+        use super::*;
+
+        fn engine(commands: Vec<Command>) -> Engine {
+            let mut manager = mock::Manager::default();
+            manager.plugins.push(mock::Plugin {
+                commands,
+                ..mock::Plugin::default()
+            });
+
+            Engine {
+                plugin_handler: Box::new(manager),
+                ..Engine::default()
+            }
+        }
+
+        fn update(engine: &mut Engine) {
+            let canvas = engine.config.viewport().into();
+            let handler = engine.plugin_handler.as_mut();
+
+            let commands = handler
+                .run_plugins(&mut engine.game_state, canvas, Time::default(), &[])
+                .unwrap();
+
+            engine.process_commands(commands);
+        }
+
+        #[test]
+        fn quit() {
+            let mut engine = engine(vec![Command::Quit]);
+            assert!(!engine.updater.is_finished);
+
+            update(&mut engine);
+            assert!(engine.updater.is_finished);
+        }
+
+        #[test]
+        fn fullscreen() {
+            let mut engine = engine(vec![Command::Fullscreen(true)]);
+            assert!(!engine.config.fullscreen);
+
+            update(&mut engine);
+            assert!(engine.config.fullscreen);
+
+            engine.process_commands(vec![Command::Fullscreen(false)]);
+            assert!(!engine.config.fullscreen);
+        }
+
+        #[test]
+        fn cursor_hidden() {
+            let mut engine = engine(vec![Command::CursorHidden(true)]);
+            assert!(!engine.config.cursor_hidden);
+
+            update(&mut engine);
+            assert!(engine.config.cursor_hidden);
+
+            engine.process_commands(vec![Command::CursorHidden(false)]);
+            assert!(!engine.config.cursor_hidden);
+        }
+
+        #[test]
+        fn background() {
+            let night = Color::from_rgb(10, 10, 40);
+            let mut engine = engine(vec![Command::Background(night)]);
+            assert_ne!(engine.renderer.config.background_color, night);
+
+            update(&mut engine);
+            assert_eq!(engine.renderer.config.background_color, night);
+            assert_eq!(engine.capture_frame().pixel(0, 0), Some(night));
+        }
+
+        #[test]
+        fn save() {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let mut engine = engine(vec![Command::Save]);
+            engine.config.save_path = Some(file.path().to_owned());
+            engine.game_state.set_plugin_paused("other", true);
+
+            update(&mut engine);
+
+            let saved = GameState::load_from_path(file.path()).unwrap();
+            assert!(saved.is_plugin_paused("other"));
+        }
+
+        #[test]
+        fn capture_frame() {
+            let mut engine = engine(vec![Command::CaptureFrame]);
+            assert!(!engine.renderer.capture_requested);
+
+            update(&mut engine);
+            assert!(engine.renderer.capture_requested);
+        }
     }
 }
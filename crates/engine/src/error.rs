@@ -15,6 +15,9 @@ pub enum Error {
     #[error("plugin handler error")]
     PluginHandler(#[from] Handler),
 
+    #[error("game update error")]
+    Updater(#[from] Updater),
+
     #[cfg(feature = "backend-coffee")]
     #[error("game error")]
     Game(#[from] coffee::Error),
@@ -31,16 +34,42 @@ pub enum Builder {
     #[error("inaccessible plugin `{path}` ({kind:?})")]
     Io { path: String, kind: io::ErrorKind },
 
+    #[error("invalid saved game state `{path}` ({kind:?})")]
+    SavedGameState { path: String, kind: io::ErrorKind },
+
     #[error("plugin handler error")]
     PluginHandler(#[from] Handler),
 
+    #[error("event recorder error")]
+    Recorder(#[from] Recorder),
+
     #[error("invalid window size: {0}")]
     WindowSize(u16),
 
+    #[error("engine already built")]
+    AlreadyBuilt,
+
     #[error("unknown builder error")]
     Unknown,
 }
 
+/// Event recording and playback related errors.
+#[derive(Debug, Error)]
+#[allow(clippy::missing_docs_in_private_items)]
+pub enum Recorder {
+    #[error("inaccessible event recording `{path}` ({kind:?})")]
+    Io { path: String, kind: io::ErrorKind },
+
+    #[error("invalid event recording entry on line {line}")]
+    Format {
+        line: usize,
+        source: common::serde_json::Error,
+    },
+
+    #[error("unable to encode recorded events")]
+    Encode(#[source] common::serde_json::Error),
+}
+
 /// `plugin::Runtime` related errors.
 #[derive(Debug, Error)]
 #[allow(clippy::missing_docs_in_private_items)]
@@ -80,6 +109,26 @@ impl From<walkdir::Error> for Builder {
     }
 }
 
+impl Builder {
+    /// Create an error for the saved game state at the given path.
+    pub(crate) fn saved_game_state(path: &std::path::Path, err: &io::Error) -> Self {
+        Self::SavedGameState {
+            path: path.to_string_lossy().into_owned(),
+            kind: err.kind(),
+        }
+    }
+}
+
+impl Recorder {
+    /// Create an I/O error for the recording at the given path.
+    pub(crate) fn io(path: &std::path::Path, err: &io::Error) -> Self {
+        Self::Io {
+            path: path.to_string_lossy().into_owned(),
+            kind: err.kind(),
+        }
+    }
+}
+
 /// Game update related error
 #[derive(Debug, Error)]
 #[allow(clippy::missing_docs_in_private_items)]
@@ -87,6 +136,9 @@ pub enum Updater {
     #[error("plugin runtime error")]
     PluginRuntime(#[from] Runtime),
 
+    #[error("event recorder error")]
+    Recorder(#[from] Recorder),
+
     #[cfg(feature = "backend-ggez")]
     #[error("game engine error")]
     GameEngine(#[from] ggez::GameError),
@@ -0,0 +1,58 @@
+//! A frame of pixels captured from the renderer.
+
+use common::Color;
+use std::fmt;
+
+/// A captured frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The width of the frame, in pixels.
+    pub width: u16,
+
+    /// The height of the frame, in pixels.
+    pub height: u16,
+
+    /// The RGBA pixels of the frame, one byte per channel, stored row by row
+    /// starting at the top-left.
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Get the color of the pixel at the given position.
+    ///
+    /// Returns `None` if the position is outside of the frame.
+    #[must_use]
+    #[allow(clippy::integer_arithmetic)]
+    pub fn pixel(&self, x: u16, y: u16) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let index = (usize::from(y) * usize::from(self.width) + usize::from(x)) * 4;
+        match *self.pixels.get(index..index + 4)? {
+            [r, g, b, a] => Some(Color::from_rgba(r, g, b, a)),
+            _ => None,
+        }
+    }
+}
+
+/// A callback receiving the frames captured when a plugin requests it.
+pub(crate) struct Callback(Box<dyn FnMut(Frame)>);
+
+impl Callback {
+    /// Wrap the given closure.
+    pub(crate) fn new(callback: impl FnMut(Frame) + 'static) -> Self {
+        Self(Box::new(callback))
+    }
+
+    /// Pass a captured frame to the callback.
+    pub(crate) fn call(&mut self, frame: Frame) {
+        (self.0)(frame)
+    }
+}
+
+impl fmt::Debug for Callback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Callback").field(&"FnMut(Frame)").finish()
+    }
+}
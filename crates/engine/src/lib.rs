@@ -20,30 +20,27 @@
     clippy::shadow_reuse
 )]
 
+mod backend;
 mod builder;
+#[cfg(any(test, feature = "backend-null"))]
+mod capture;
+mod click;
 mod config;
+mod description;
 mod engine;
 mod error;
+mod frame;
 mod plugin;
+mod recorder;
 mod widget;
 
-/// The backend-coffee implementation.
-#[cfg(all(feature = "backend-coffee", not(feature = "backend-ggez")))]
-mod backend {
-    mod coffee;
-    pub use self::coffee::*;
-}
-
-/// The backend-ggez implementation.
-#[cfg(all(feature = "backend-ggez", not(feature = "backend-coffee")))]
-mod backend {
-    mod ggez;
-    pub use self::ggez::*;
-}
-
 use builder::Builder;
 
+pub use description::{
+    Engine as EngineDescription, Plugin as PluginDescription, Widget as WidgetDescription,
+};
 pub use error::Error;
+pub use frame::Frame;
 
 /// A convenient top-level engine type exposed to start an engine with sensible
 /// defaults.
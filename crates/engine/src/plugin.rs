@@ -4,7 +4,7 @@ pub(super) mod mock;
 pub(super) mod wasm;
 
 use crate::error;
-use common::{Canvas, Event, GameState};
+use common::{Canvas, Command, Event, GameState, Registration, Time};
 use core::fmt;
 use displaydoc::Display;
 use std::path::Path;
@@ -26,16 +26,29 @@ pub enum Func {
 /// usable by the engine.
 pub trait Runtime {
     /// Run the plugin to completion.
+    ///
+    /// The `time` contains the time details of the current update, the
+    /// `history` contains the events of previous runs, starting with the
+    /// oldest run.
+    ///
+    /// Returns the commands the plugin issued while running.
     fn run(
         &mut self,
         game_state: &mut GameState,
         canvas: Canvas,
+        time: Time,
         events: &[Event],
-    ) -> Result<(), error::Runtime>;
+        history: &[Vec<Event>],
+    ) -> Result<Vec<Command>, error::Runtime>;
 
     /// The name of the plugin.
     fn name(&self) -> &str;
 
+    /// The details the plugin registered itself with, if any.
+    fn registration(&self) -> Option<&Registration> {
+        None
+    }
+
     /// Get the concrete `wasm::Plugin` implementation, if the underlying type
     /// matches.
     fn as_wasm(&mut self) -> Option<&mut wasm::Plugin> {
@@ -52,12 +65,16 @@ pub trait Runtime {
 /// A handler takes ownership of external plugins, and runs them when requested.
 pub trait Handler {
     /// Run all registered plugins.
+    ///
+    /// Returns the commands issued by all plugins, in the order in which the
+    /// plugins ran.
     fn run_plugins(
         &mut self,
         game_state: &mut GameState,
         canvas: Canvas,
+        time: Time,
         events: &[Event],
-    ) -> Result<(), error::Runtime>;
+    ) -> Result<Vec<Command>, error::Runtime>;
 
     /// Register a new plugin to handle.
     fn register_plugin(
@@ -66,6 +83,13 @@ pub trait Handler {
         file: &Path,
     ) -> Result<(), error::Handler>;
 
+    /// The details the named plugin registered itself with, if the plugin is
+    /// registered to this handler.
+    fn registration(&self, plugin: &str) -> Option<&Registration> {
+        let _ = plugin;
+        None
+    }
+
     /// Get the concrete `wasm::Manager` implementation, if the underlying type
     /// matches.
     fn as_wasm(&mut self) -> Option<&mut wasm::Manager> {
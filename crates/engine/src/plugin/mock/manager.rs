@@ -3,7 +3,7 @@
 use super::plugin::Plugin;
 use crate::error;
 use crate::plugin::{Handler, Runtime};
-use common::{Canvas, Event, GameState};
+use common::{Canvas, Command, Event, GameState, Registration, Time};
 use std::path::Path;
 
 /// A mock plugin implementation
@@ -18,13 +18,23 @@ impl Handler for Manager {
         &mut self,
         game_state: &mut GameState,
         canvas: Canvas,
+        time: Time,
         events: &[Event],
-    ) -> Result<(), error::Runtime> {
+    ) -> Result<Vec<Command>, error::Runtime> {
+        let mut commands = vec![];
+
         for plugin in &mut self.plugins {
-            plugin.run(game_state, canvas, events)?;
+            if game_state.is_plugin_paused(plugin.name()) {
+                continue;
+            }
+
+            let events = game_state.plugin_events(plugin.name(), events);
+            commands.append(&mut plugin.run(game_state, canvas, time, &events, &[])?);
         }
 
-        Ok(())
+        game_state.commit_shared();
+
+        Ok(commands)
     }
 
     fn register_plugin(&mut self, _: &mut GameState, _: &Path) -> Result<(), error::Handler> {
@@ -34,12 +44,20 @@ impl Handler for Manager {
         Ok(())
     }
 
+    fn registration(&self, plugin: &str) -> Option<&Registration> {
+        self.plugins
+            .iter()
+            .find(|p| p.name() == plugin)
+            .and_then(Runtime::registration)
+    }
+
     fn as_mock(&mut self) -> Option<&mut Self> {
         Some(self)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::restriction)]
 mod tests {
     use super::*;
 
@@ -54,7 +72,132 @@ mod tests {
             let plugin = Plugin::default();
             manager.plugins.push(plugin);
 
-            assert!(manager.run_plugins(&mut game_state, canvas, &[]).is_ok())
+            assert!(manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .is_ok())
+        }
+
+        #[test]
+        fn paused() {
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+
+            let mut plugin = Plugin::default();
+            plugin.name = "foo".to_owned();
+            manager.plugins.push(plugin);
+            manager.plugins.push(Plugin::default());
+
+            game_state.set_plugin_paused("foo", true);
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .unwrap();
+
+            assert_eq!(manager.plugins[0].runs, 0);
+            assert_eq!(manager.plugins[1].runs, 1);
+
+            game_state.set_plugin_paused("foo", false);
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .unwrap();
+
+            assert_eq!(manager.plugins[0].runs, 1);
+        }
+    }
+
+    mod events {
+        use super::*;
+        use common::{event, widget, PluginState, Value};
+        use std::collections::HashMap;
+
+        #[test]
+        fn isolated_per_plugin() {
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+
+            // Both plugins own a widget of the same name.
+            for plugin in &["left", "right"] {
+                let widgets: HashMap<_, _> =
+                    vec![widget::Builder::new("button", widget::Kind::ButtonRectangle).build()]
+                        .into_iter()
+                        .collect();
+
+                let state = PluginState::new(HashMap::<String, Value>::new(), widgets);
+                game_state.register_plugin_state(*plugin, state);
+
+                let mut mock = Plugin::default();
+                mock.name = (*plugin).to_owned();
+                manager.plugins.push(mock);
+            }
+
+            let clicked = |plugin: &str, name: &str| Event::Widget {
+                plugin: plugin.to_owned(),
+                name: name.to_owned(),
+                event: event::Widget::new("clicked"),
+            };
+
+            let input = Event::Input(event::Input::Focus);
+            let events = vec![
+                input.clone(),
+                clicked("left", "button"),
+                clicked("right", "button"),
+                clicked("left", "unknown"),
+                clicked("other", "button"),
+            ];
+
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &events)
+                .unwrap();
+
+            assert_eq!(
+                manager.plugins[0].events,
+                vec![input.clone(), clicked("left", "button")]
+            );
+            assert_eq!(
+                manager.plugins[1].events,
+                vec![input, clicked("right", "button")]
+            );
+        }
+    }
+
+    mod shared {
+        use super::*;
+        use common::Value;
+
+        #[test]
+        fn last_writer_wins() {
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            game_state.declare_shared("score", 0.into());
+
+            let plugin = |name: &str, score: u8| {
+                let mut plugin = Plugin::default();
+                plugin.name = name.to_owned();
+                plugin.shared.insert("score".to_owned(), score.into());
+                plugin
+            };
+
+            let mut manager = Manager::default();
+            manager.plugins.push(plugin("producer", 1));
+            manager.plugins.push(plugin("consumer", 2));
+
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .unwrap();
+
+            // Both plugins saw the shared state as it was at the start of the
+            // tick, and the last plugin to write the key wins.
+            assert_eq!(manager.plugins[0].seen_shared["score"], Value::from(0));
+            assert_eq!(manager.plugins[1].seen_shared["score"], Value::from(0));
+            assert_eq!(game_state.shared("score"), Some(&Value::from(2)));
+
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .unwrap();
+
+            assert_eq!(manager.plugins[0].seen_shared["score"], Value::from(2));
+            assert_eq!(manager.plugins[1].seen_shared["score"], Value::from(2));
         }
     }
 
@@ -2,27 +2,73 @@
 
 use crate::error;
 use crate::plugin::Runtime;
-use common::{Canvas, Event, GameState};
+use common::{Canvas, Command, Event, GameState, Registration, Time, Value};
+use std::collections::HashMap;
 
 /// A mock plugin implementation
 #[derive(Debug, Default)]
 pub struct Plugin {
+    /// The name of the plugin.
+    pub(crate) name: String,
+
     /// The amount of times this plugin "ran" (mocked).
     pub(crate) runs: usize,
 
     /// The state of the game.
     pub(crate) game_state: GameState,
+
+    /// The commands the plugin issues on every run.
+    pub(crate) commands: Vec<Command>,
+
+    /// The events passed to the plugin on its last run.
+    pub(crate) events: Vec<Event>,
+
+    /// The time passed to the plugin on its last run.
+    pub(crate) time: Time,
+
+    /// The values the plugin writes to the shared state on every run.
+    pub(crate) shared: HashMap<String, Value>,
+
+    /// The shared state values (of the keys the plugin writes) the plugin saw
+    /// on its last run.
+    pub(crate) seen_shared: HashMap<String, Value>,
+
+    /// The details the plugin registered itself with, if any.
+    pub(crate) registration: Option<Registration>,
 }
 
 impl Runtime for Plugin {
-    fn run(&mut self, _: &mut GameState, _: Canvas, _: &[Event]) -> Result<(), error::Runtime> {
+    fn run(
+        &mut self,
+        game_state: &mut GameState,
+        _: Canvas,
+        time: Time,
+        events: &[Event],
+        _: &[Vec<Event>],
+    ) -> Result<Vec<Command>, error::Runtime> {
         self.runs = self.runs.saturating_add(1);
+        self.events = events.to_vec();
+        self.time = time;
+
+        self.seen_shared = self
+            .shared
+            .keys()
+            .filter_map(|key| Some((key.clone(), game_state.shared(key.as_str())?.clone())))
+            .collect();
+
+        for (key, value) in &self.shared {
+            game_state.write_shared(key.as_str(), value.clone());
+        }
 
-        Ok(())
+        Ok(self.commands.clone())
     }
 
     fn name(&self) -> &str {
-        ""
+        &self.name
+    }
+
+    fn registration(&self) -> Option<&Registration> {
+        self.registration.as_ref()
     }
 
     fn as_mock(&mut self) -> Option<&mut Self> {
@@ -40,8 +86,10 @@ mod tests {
         let canvas = Canvas::default();
         let mut mock = Plugin::default();
         let mut game_state = GameState::default();
-        mock.run(&mut game_state, canvas, &[]).unwrap();
-        mock.run(&mut game_state, canvas, &[]).unwrap();
+        mock.run(&mut game_state, canvas, Time::default(), &[], &[])
+            .unwrap();
+        mock.run(&mut game_state, canvas, Time::default(), &[], &[])
+            .unwrap();
 
         assert_eq!(mock.runs, 2)
     }
@@ -3,6 +3,7 @@
 mod error;
 mod manager;
 mod plugin;
+mod watchdog;
 
 pub use error::{Handler as HandlerError, Runtime as RuntimeError};
 pub use manager::Manager;
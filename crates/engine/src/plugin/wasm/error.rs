@@ -2,9 +2,10 @@
 
 use crate::plugin::Func;
 use anyhow::Error;
-use common::serde_json;
+use common::{serde_json, RegistrationProblem};
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 use wasmtime::Trap;
 
@@ -24,6 +25,18 @@ pub enum Runtime {
     #[error("missing plugin name")]
     MissingName,
 
+    #[error("invalid registration: {}", display_problems(.0))]
+    InvalidRegistration(Vec<RegistrationProblem>),
+
+    #[error("plugin requires engine version {required} or newer, found {engine}")]
+    IncompatibleEngineVersion {
+        required: String,
+        engine: &'static str,
+    },
+
+    #[error("invalid minimum engine version `{0}`")]
+    InvalidEngineVersion(String),
+
     #[error("cannot access runtime memory")]
     MemoryAccess,
 
@@ -33,12 +46,15 @@ pub enum Runtime {
     #[error("codec error")]
     Codec(#[from] serde_json::Error),
 
-    #[error("plugin error")]
+    #[error("plugin error: {0}")]
     Plugin(String),
 
     #[error("error running `{func}`")]
     Failed { func: Func, source: Trap },
 
+    #[error("plugin `{plugin}` exceeded its time budget of {budget:?}")]
+    TimeBudgetExceeded { plugin: String, budget: Duration },
+
     #[error("unable to run module")]
     InvalidModule(#[source] anyhow::Error),
 
@@ -46,6 +62,27 @@ pub enum Runtime {
     Unknown(#[source] anyhow::Error),
 }
 
+/// Join a list of registration problems into a single message.
+fn display_problems(problems: &[RegistrationProblem]) -> String {
+    problems
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Runtime {
+    /// Whether the error is a trap caused by interrupting the running Wasm
+    /// code.
+    #[allow(clippy::wildcard_enum_match_arm)]
+    pub(super) fn is_interrupt(&self) -> bool {
+        match self {
+            Self::Failed { source, .. } => source.message().ends_with("interrupt"),
+            _ => false,
+        }
+    }
+}
+
 impl From<std::num::TryFromIntError> for Runtime {
     fn from(_: std::num::TryFromIntError) -> Self {
         Self::MemoryAccess
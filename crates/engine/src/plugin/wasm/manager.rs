@@ -1,20 +1,70 @@
 //! Wasm Manager implementation.
 
-use super::HandlerError;
+use super::{watchdog::Watchdog, HandlerError, RuntimeError};
 use crate::error;
 use crate::plugin::{wasm::Plugin, Handler, Runtime};
-use common::{Canvas, Event, GameState};
-use std::{fmt, fs, path::Path};
-use wasmtime::Store;
+use common::{Canvas, Command, Event, GameState, Registration, Time};
+use std::{fmt, fs, io, path::Path, thread, time::Duration};
+use wasmtime::{Config, Engine, Store};
+
+/// The default number of times reading a plugin is retried.
+const DEFAULT_READ_RETRIES: u8 = 3;
+
+/// The time to wait before the first retry. This doubles with every attempt.
+const READ_BACKOFF: Duration = Duration::from_millis(10);
+
+/// A function used to read the source of a plugin.
+type Reader = Box<dyn FnMut(&Path) -> io::Result<Vec<u8>>>;
 
 /// The object responsible for "managing" Wasm plugins.
-#[derive(Default)]
 pub struct Manager {
     /// The list of plugins this plugin manager is responsible for.
     plugins: Vec<Plugin>,
 
     /// The wasm cache used by the `wasmtime` Wasm runtime.
     plugin_store: Store,
+
+    /// The number of times reading a plugin is retried after a transient I/O
+    /// error.
+    pub(crate) read_retries: u8,
+
+    /// The function used to read plugins from disk.
+    reader: Reader,
+
+    /// The maximum (wall-clock) time a single plugin run is allowed to take.
+    ///
+    /// A plugin exceeding its budget is interrupted, and paused.
+    pub(crate) time_budget: Option<Duration>,
+
+    /// The watchdog interrupting plugins exceeding their time budget.
+    ///
+    /// This is only started once a time budget is configured.
+    watchdog: Option<Watchdog>,
+
+    /// The number of previous runs of which the events are kept in the
+    /// history.
+    pub(crate) history_depth: usize,
+
+    /// The events of previous runs, starting with the oldest run.
+    history: Vec<Vec<Event>>,
+}
+
+impl Default for Manager {
+    fn default() -> Self {
+        let mut config = Config::default();
+        config.interruptable(true);
+
+        Self {
+            plugins: vec![],
+            plugin_store: Store::new(&Engine::new(&config)),
+            read_retries: DEFAULT_READ_RETRIES,
+            reader: Box::new(|path| fs::read(path)),
+            time_budget: None,
+            watchdog: None,
+            history_depth: 0,
+            history: vec![],
+        }
+    }
 }
 
 impl fmt::Debug for Manager {
@@ -22,22 +72,121 @@ impl fmt::Debug for Manager {
         f.debug_struct("PluginManager")
             .field("plugins", &self.plugins)
             .field("plugin_store", &"wasmtime::Store".to_string())
+            .field("read_retries", &self.read_retries)
+            .field("time_budget", &self.time_budget)
+            .field("history_depth", &self.history_depth)
             .finish()
     }
 }
 
+impl Manager {
+    /// Read the source of a plugin.
+    ///
+    /// Transient I/O errors (e.g. on slow or network filesystems) are retried
+    /// with an exponential backoff, up to the configured number of retries.
+    fn read(&mut self, file: &Path) -> io::Result<Vec<u8>> {
+        let mut attempt = 0_u8;
+
+        loop {
+            match (self.reader)(file) {
+                Ok(source) => return Ok(source),
+                Err(err) if attempt < self.read_retries && is_transient(err.kind()) => {
+                    thread::sleep(READ_BACKOFF * 2_u32.saturating_pow(attempt.into()));
+                    attempt = attempt.saturating_add(1);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Add the events of a run to the history, dropping the oldest run once
+    /// the history exceeds its configured depth.
+    fn record_history(&mut self, events: &[Event]) {
+        if self.history_depth == 0 {
+            return;
+        }
+
+        self.history.push(events.to_vec());
+
+        if self.history.len() > self.history_depth {
+            let excess = self.history.len().saturating_sub(self.history_depth);
+            self.history.drain(..excess);
+        }
+    }
+}
+
 impl Handler for Manager {
     fn run_plugins(
         &mut self,
         game_state: &mut GameState,
         canvas: Canvas,
+        time: Time,
         events: &[Event],
-    ) -> Result<(), error::Runtime> {
+    ) -> Result<Vec<Command>, error::Runtime> {
+        let mut commands = vec![];
+
+        if self.time_budget.is_some() && self.watchdog.is_none() {
+            let watchdog = Watchdog::new(&self.plugin_store).map_err(RuntimeError::from)?;
+            self.watchdog = Some(watchdog);
+        }
+
         for plugin in &mut self.plugins {
-            plugin.run(game_state, canvas, events)?;
+            if game_state.is_plugin_paused(plugin.name()) {
+                continue;
+            }
+
+            // A plugin only sees the events of the widgets it owns.
+            let events = game_state.plugin_events(plugin.name(), events);
+
+            // Plugins that skip this tick receive its events (and time) once
+            // they run again.
+            let (time, events) = match plugin.tick(time, events) {
+                Some(run) => run,
+                None => continue,
+            };
+            let history: Vec<_> = self
+                .history
+                .iter()
+                .map(|run| game_state.plugin_events(plugin.name(), run))
+                .collect();
+
+            let (watchdog, budget) = match (&self.watchdog, self.time_budget) {
+                (Some(watchdog), Some(budget)) => (watchdog, budget),
+                _ => {
+                    commands.append(&mut plugin.run(game_state, canvas, time, &events, &history)?);
+                    continue;
+                }
+            };
+
+            watchdog.arm(budget);
+            let result = plugin.run(game_state, canvas, time, &events, &history);
+
+            // The deadline might pass after the plugin finished running, in
+            // which case its result is kept.
+            #[allow(clippy::wildcard_enum_match_arm)]
+            let trapped = match &result {
+                Err(error::Runtime::WasmRuntime(err)) => err.is_interrupt(),
+                _ => false,
+            };
+
+            if watchdog.disarm(trapped) {
+                let name = plugin.name().to_owned();
+                game_state.set_plugin_paused(&name, true);
+
+                return Err(RuntimeError::TimeBudgetExceeded {
+                    plugin: name,
+                    budget,
+                }
+                .into());
+            }
+
+            commands.append(&mut result?);
         }
 
-        Ok(())
+        game_state.commit_shared();
+        self.record_history(events);
+
+        Ok(commands)
     }
 
     fn register_plugin(
@@ -45,7 +194,8 @@ impl Handler for Manager {
         game_state: &mut GameState,
         file: &Path,
     ) -> Result<(), error::Handler> {
-        let source = fs::read(file)
+        let source = self
+            .read(file)
             .map_err(|err| (file.to_owned(), err))
             .map_err(HandlerError::from)?;
 
@@ -53,18 +203,40 @@ impl Handler for Manager {
             .map_err(|err| (file.to_owned(), err))
             .map_err(HandlerError::from)?;
 
-        #[allow(clippy::print_stdout)] // temporary debuggin
-        println!("plugin registered: {}", plugin.name());
+        log::info!("plugin registered: {}", plugin.name());
         self.plugins.push(plugin);
 
         Ok(())
     }
 
+    fn registration(&self, plugin: &str) -> Option<&Registration> {
+        self.plugins
+            .iter()
+            .find(|p| p.name() == plugin)
+            .and_then(Runtime::registration)
+    }
+
     fn as_wasm(&mut self) -> Option<&mut Self> {
         Some(self)
     }
 }
 
+/// Whether an I/O error might resolve itself when retried.
+///
+/// Besides interrupted or timed out reads, this includes dropped connections,
+/// which happen when plugins are loaded from a network filesystem.
+#[allow(clippy::wildcard_enum_match_arm)]
+const fn is_transient(kind: io::ErrorKind) -> bool {
+    match kind {
+        io::ErrorKind::Interrupted
+        | io::ErrorKind::WouldBlock
+        | io::ErrorKind::TimedOut
+        | io::ErrorKind::ConnectionReset
+        | io::ErrorKind::ConnectionAborted => true,
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::restriction)]
 mod tests {
@@ -81,7 +253,9 @@ mod tests {
             let mut game_state = GameState::default();
             let mut manager = Manager::default();
 
-            assert!(manager.run_plugins(&mut game_state, canvas, &[]).is_ok())
+            assert!(manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .is_ok())
         }
 
         #[test]
@@ -98,7 +272,63 @@ mod tests {
             let p = plugin(WAT_VALID);
             manager.plugins.push(p);
 
-            assert!(manager.run_plugins(&mut game_state, canvas, &[]).is_ok())
+            assert!(manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .is_ok())
+        }
+
+        #[test]
+        fn event_history() {
+            use common::event::Input;
+
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let mut manager = Manager {
+                history_depth: 2,
+                ..Manager::default()
+            };
+
+            let focus = Event::Input(Input::Focus);
+            let blur = Event::Input(Input::Blur);
+
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[focus.clone()])
+                .unwrap();
+            assert_eq!(manager.history, vec![vec![focus.clone()]]);
+
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[blur.clone()])
+                .unwrap();
+            manager
+                .run_plugins(
+                    &mut game_state,
+                    canvas,
+                    Time::default(),
+                    &[focus.clone(), blur.clone()],
+                )
+                .unwrap();
+
+            assert_eq!(manager.history, vec![vec![blur.clone()], vec![focus, blur]]);
+
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .unwrap();
+            assert_eq!(manager.history.len(), 2);
+            assert!(manager.history[1].is_empty());
+        }
+
+        #[test]
+        fn without_event_history() {
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+
+            let events = vec![Event::Input(common::event::Input::Focus)];
+            manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &events)
+                .unwrap();
+
+            assert!(manager.history.is_empty());
         }
 
         #[test]
@@ -117,7 +347,7 @@ mod tests {
 
             let err = anyhow::Error::new(
                 manager
-                    .run_plugins(&mut game_state, canvas, &[])
+                    .run_plugins(&mut game_state, canvas, Time::default(), &[])
                     .unwrap_err(),
             );
 
@@ -129,10 +359,44 @@ mod tests {
                      missing exported `_run` function"
             )
         }
+
+        #[test]
+        fn time_budget_exceeded() {
+            use crate::plugin::wasm::plugin::tests::WAT_INFINITE_LOOP;
+
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+            manager.time_budget = Some(Duration::from_millis(10));
+            manager.reader = Box::new(|_| Ok(WAT_INFINITE_LOOP.as_bytes().to_vec()));
+
+            manager
+                .register_plugin(&mut game_state, Path::new("plugin.wasm"))
+                .unwrap();
+
+            let err = manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .unwrap_err();
+
+            assert_eq!(
+                format!("{:?}", anyhow::Error::new(err)),
+                "wasm runtime error\n\n\
+
+                 Caused by:\n    \
+                     plugin `test` exceeded its time budget of 10ms"
+            );
+
+            // The plugin is paused, and no longer runs.
+            assert!(game_state.is_plugin_paused("test"));
+            assert!(manager
+                .run_plugins(&mut game_state, canvas, Time::default(), &[])
+                .is_ok());
+        }
     }
 
     mod register_plugin {
         use super::*;
+        use std::{cell::Cell, rc::Rc};
 
         #[test]
         fn valid() {
@@ -171,6 +435,67 @@ mod tests {
             )
         }
 
+        #[test]
+        fn transient_read_error() {
+            use crate::plugin::wasm::plugin::tests::WAT_VALID;
+
+            let attempts = Rc::new(Cell::new(0));
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+
+            let counter = Rc::clone(&attempts);
+            manager.reader = Box::new(move |_| {
+                counter.set(counter.get() + 1);
+
+                match counter.get() {
+                    1 | 2 => Err(io::ErrorKind::Interrupted.into()),
+                    _ => Ok(WAT_VALID.as_bytes().to_vec()),
+                }
+            });
+
+            assert!(manager
+                .register_plugin(&mut game_state, Path::new("plugin.wasm"))
+                .is_ok());
+            assert_eq!(attempts.get(), 3);
+        }
+
+        #[test]
+        fn retries_exhausted() {
+            let attempts = Rc::new(Cell::new(0));
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+            manager.read_retries = 1;
+
+            let counter = Rc::clone(&attempts);
+            manager.reader = Box::new(move |_| {
+                counter.set(counter.get() + 1);
+                Err(io::ErrorKind::TimedOut.into())
+            });
+
+            assert!(manager
+                .register_plugin(&mut game_state, Path::new("plugin.wasm"))
+                .is_err());
+            assert_eq!(attempts.get(), 2);
+        }
+
+        #[test]
+        fn permanent_read_error() {
+            let attempts = Rc::new(Cell::new(0));
+            let mut game_state = GameState::default();
+            let mut manager = Manager::default();
+
+            let counter = Rc::clone(&attempts);
+            manager.reader = Box::new(move |_| {
+                counter.set(counter.get() + 1);
+                Err(io::ErrorKind::UnexpectedEof.into())
+            });
+
+            assert!(manager
+                .register_plugin(&mut game_state, Path::new("plugin.wasm"))
+                .is_err());
+            assert_eq!(attempts.get(), 1);
+        }
+
         #[test]
         fn missing_file() {
             let path = "/missing/file";
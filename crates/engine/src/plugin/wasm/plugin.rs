@@ -6,8 +6,8 @@ use crate::{
     plugin::{Func, Runtime},
 };
 use common::{
-    serde_json, Canvas, DeserializeOwned, Event, GameState, PluginState, Registration, RunResult,
-    StateTransfer,
+    serde_json, Canvas, Command, DeserializeOwned, Event, GameState, PluginState, Registration,
+    RunResult, StateTransfer, Time,
 };
 use std::cell::Cell;
 use std::collections::HashMap;
@@ -40,6 +40,15 @@ pub struct Plugin {
     /// 3. The `Plugin::run` method then takes this value and uses its results,
     ///    leaving `None` in its place.
     run_result: Rc<Cell<Option<RunResult>>>,
+
+    /// The number of ticks left to skip before the plugin runs again.
+    ticks_until_run: u32,
+
+    /// The time spanning all ticks skipped since the plugin last ran.
+    skipped_time: Option<Time>,
+
+    /// The events of all ticks skipped since the plugin last ran.
+    skipped_events: Vec<Event>,
 }
 
 impl Plugin {
@@ -60,11 +69,9 @@ impl Plugin {
         ];
 
         // TODO: limit what resources the modules have access to.
-        #[allow(clippy::match_wild_err_arm)]
-        let ctx = match WasiCtx::new(std::env::args()) {
-            Ok(ctx) => ctx,
-            Err(_) => todo!("logging"),
-        };
+        let ctx = WasiCtx::new(std::env::args()).map_err(|err| {
+            RuntimeError::Unknown(anyhow::anyhow!("unable to create WASI context: {}", err))
+        })?;
 
         let wasi = Wasi::new(store, ctx);
         for import in module.imports() {
@@ -80,15 +87,26 @@ impl Plugin {
 
         Self::call(&instance, Func::Init)?;
 
-        let mut registration: Registration = match registration.take() {
+        let registration: Registration = match registration.take() {
             Some(registration) => registration,
-            None => todo!("logging"),
+            None => {
+                log::error!("plugin did not register itself during `{}`", Func::Init);
+                return Err(RuntimeError::Registration);
+            }
         };
 
         if registration.name.is_empty() {
             return Err(RuntimeError::MissingName);
         }
 
+        let mut registration = registration
+            .build()
+            .map_err(RuntimeError::InvalidRegistration)?;
+
+        if let Some(required) = &registration.min_engine_version {
+            check_engine_version(required)?;
+        }
+
         // Only register state plugin if anything needs to be tracked.
         let state = match &mut registration.state {
             Some(state) => mem::take(state),
@@ -102,15 +120,56 @@ impl Plugin {
 
         let plugin_state = PluginState::new(state, widgets);
 
-        game_state.register_plugin_state(registration.name.clone(), plugin_state);
+        if let Some(shared) = &registration.shared {
+            for (key, value) in shared {
+                game_state.declare_shared(key.as_str(), value.clone());
+            }
+        }
+
+        // A previously saved state of the plugin takes precedence over its
+        // initial state.
+        if game_state.get(&registration.name).is_none() {
+            game_state.register_plugin_state(registration.name.clone(), plugin_state);
+        }
 
         Ok(Self {
             instance,
             registration,
             run_result,
+            ticks_until_run: 0,
+            skipped_time: None,
+            skipped_events: vec![],
         })
     }
 
+    /// Advance the plugin by a single engine tick, with the time and events of
+    /// that tick.
+    ///
+    /// Returns the time and events to run the plugin with, or `None` if the
+    /// plugin skips this tick, based on its requested update interval. The
+    /// events of skipped ticks are buffered, and passed on (in order) once the
+    /// plugin runs, with a time delta spanning all ticks since its last run.
+    ///
+    /// A plugin always runs on its first tick.
+    pub(super) fn tick(&mut self, time: Time, events: Vec<Event>) -> Option<(Time, Vec<Event>)> {
+        let time = self
+            .skipped_time
+            .take()
+            .map_or(time, |skipped| skipped.merge(time));
+        self.skipped_events.extend(events);
+
+        if let Some(ticks) = self.ticks_until_run.checked_sub(1) {
+            self.ticks_until_run = ticks;
+            self.skipped_time = Some(time);
+            return None;
+        }
+
+        let interval = self.registration.update_interval.unwrap_or(1);
+        self.ticks_until_run = interval.saturating_sub(1);
+
+        Some((time, mem::take(&mut self.skipped_events)))
+    }
+
     /// Call into the wasm instance for a given function that takes no arguments.
     fn call(instance: &Instance, func: Func) -> Result<(), RuntimeError> {
         let call = instance
@@ -177,7 +236,10 @@ impl Plugin {
             // See: https://docs.rs/wasmtime/0.16.0/wasmtime/struct.Memory.html#memory-and-safety
             let data = unsafe {
                 #[allow(clippy::as_conversions, clippy::cast_sign_loss)]
-                let slice = get_data(&mut memory, pos as usize, len as usize);
+                let slice = match get_data(&mut memory, pos as usize, len as usize) {
+                    Ok(slice) => slice,
+                    Err(err) => return Err(Trap::new(err.to_string())),
+                };
 
                 match serde_json::from_slice(slice) {
                     Ok(value) => value,
@@ -202,6 +264,44 @@ impl Plugin {
     }
 }
 
+/// The version of the engine, checked against the minimum engine version of a
+/// plugin.
+const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Check if the engine is at least the `required` version.
+///
+/// Versions are compared by their major, minor and patch numbers, missing
+/// numbers count as zero (e.g. `"0.2"` equals `"0.2.0"`).
+fn check_engine_version(required: &str) -> Result<(), RuntimeError> {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        let mut parts = version
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        if parts.len() > 3 {
+            return None;
+        }
+
+        parts.resize(3, 0);
+        Some(parts)
+    };
+
+    let required_parts = parse(required.trim())
+        .ok_or_else(|| RuntimeError::InvalidEngineVersion(required.to_owned()))?;
+    let engine_parts = parse(ENGINE_VERSION)
+        .ok_or_else(|| RuntimeError::InvalidEngineVersion(ENGINE_VERSION.to_owned()))?;
+
+    if required_parts > engine_parts {
+        return Err(RuntimeError::IncompatibleEngineVersion {
+            required: required.to_owned(),
+            engine: ENGINE_VERSION,
+        });
+    }
+
+    Ok(())
+}
+
 impl fmt::Debug for Plugin {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Wasm")
@@ -219,8 +319,10 @@ impl Runtime for Plugin {
         &mut self,
         game_state: &mut GameState,
         canvas: Canvas,
+        time: Time,
         events: &[Event],
-    ) -> Result<(), error::Runtime> {
+        history: &[Vec<Event>],
+    ) -> Result<Vec<Command>, error::Runtime> {
         let owned = game_state.get(self.name()).cloned().unwrap_or_default();
 
         let mut borrowed = HashMap::default();
@@ -232,11 +334,23 @@ impl Runtime for Plugin {
             }
         }
 
+        let mut shared = HashMap::default();
+        if let Some(keys) = &self.registration.shared {
+            for key in keys.keys() {
+                if let Some(value) = game_state.shared(key.as_str()) {
+                    shared.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
         let state = StateTransfer {
             owned,
             borrowed,
             canvas,
             events: events.to_vec(),
+            history: history.to_vec(),
+            shared: shared.clone(),
+            time,
         };
 
         let vec = serde_json::to_vec(&state).map_err(RuntimeError::from)?;
@@ -245,26 +359,38 @@ impl Runtime for Plugin {
         let offset: i32 = Self::call1(&self.instance, Func::Malloc, vec_size)?;
         let offset_size: usize = offset.try_into().map_err(RuntimeError::from)?;
 
-        let mut memory = match self.instance.get_memory("memory") {
-            Some(mem) => mem,
-            None => todo!("logging"),
-        };
+        let mut memory = self
+            .instance
+            .get_memory("memory")
+            .ok_or(RuntimeError::MemoryAccess)?;
 
         unsafe {
-            let mut slice = get_data(&mut memory, offset_size, vec.len());
+            let mut slice = get_data(&mut memory, offset_size, vec.len())?;
 
-            if slice.write_all(&vec).is_err() {
-                todo!("logging")
+            if let Err(err) = slice.write_all(&vec) {
+                log::error!("unable to write state to plugin memory: {}", err);
+                return Err(RuntimeError::MemoryAccess.into());
             }
         }
 
-        Self::call2(&self.instance, Func::Run, offset, vec_size)?;
+        if let Err(err) = Self::call2::<()>(&self.instance, Func::Run, offset, vec_size) {
+            // A panicking plugin reports its panic right before it traps, which
+            // is more useful to surface than the trap itself.
+            let error = match self.run_result.take().and_then(|run| run.error) {
+                Some(error) => RuntimeError::Plugin(error),
+                None => err,
+            };
+
+            return Err(error.into());
+        }
 
+        // A plugin is required to report back the result of its run, if it
+        // doesn't, something went wrong inside the plugin.
         let run = match self.run_result.take() {
             Some(run) => run,
             None => {
-                // TODO: logging
-                RunResult::default()
+                log::warn!("plugin `{}` returned no result", self.name());
+                RunResult::with_error("plugin returned no result")
             }
         };
 
@@ -275,17 +401,45 @@ impl Runtime for Plugin {
         // If `state` is `None`, it means no state was changed by the plugin, so
         // the game state doesn't have to be updated.
         if let Some(mut state) = run.state {
-            let StateTransfer { owned, .. } = mem::take(&mut state);
-            game_state.register_plugin_state(self.name(), owned);
+            let StateTransfer {
+                owned,
+                shared: written,
+                ..
+            } = mem::take(&mut state);
+
+            // Unchanged state isn't registered again, so that observers are
+            // only notified of actual changes.
+            if game_state.get(self.name()) != Some(&owned) {
+                game_state.register_plugin_state(self.name(), owned);
+            }
+
+            // Only values that changed count as writes, so that a plugin
+            // returning the shared values it received doesn't undo the writes
+            // of other plugins. Keys the plugin didn't declare are ignored.
+            for (key, value) in written {
+                if shared.get(&key).map_or(false, |old| old != &value) {
+                    game_state.write_shared(key, value);
+                }
+            }
         }
 
-        Ok(())
+        if let Some(paused) = run.paused {
+            for (plugin, paused) in paused {
+                game_state.set_plugin_paused(plugin, paused);
+            }
+        }
+
+        Ok(run.commands.unwrap_or_default())
     }
 
     fn name(&self) -> &str {
         &self.registration.name
     }
 
+    fn registration(&self) -> Option<&Registration> {
+        Some(&self.registration)
+    }
+
     fn as_wasm(&mut self) -> Option<&mut Self> {
         Some(self)
     }
@@ -296,20 +450,25 @@ impl Runtime for Plugin {
 ///
 /// # Safety
 ///
-/// This expects all three provided values to be correct.
-unsafe fn get_data(memory: &mut Memory, pos: usize, len: usize) -> &mut [u8] {
+/// This expects all three provided values to be correct. If the requested
+/// range falls outside of the memory, an error is returned instead.
+unsafe fn get_data(memory: &mut Memory, pos: usize, len: usize) -> Result<&mut [u8], RuntimeError> {
     let data = memory.data_unchecked_mut();
 
-    #[allow(clippy::as_conversions, clippy::cast_sign_loss)]
     let total_len = match pos.checked_add(len) {
         Some(len) => len,
-        None => todo!("logging"),
+        None => {
+            log::error!("memory range overflows: {} + {}", pos, len);
+            return Err(RuntimeError::MemoryAccess);
+        }
     };
 
-    #[allow(clippy::cast_sign_loss, clippy::as_conversions)]
     match data.get_mut(pos..total_len) {
-        Some(slice) => slice,
-        None => todo!("logging"),
+        Some(slice) => Ok(slice),
+        None => {
+            log::error!("memory range out of bounds: {}..{}", pos, total_len);
+            Err(RuntimeError::MemoryAccess)
+        }
     }
 }
 
@@ -344,6 +503,111 @@ pub(super) mod tests {
                         | ^"
             )
         }
+
+        #[test]
+        fn newer_engine_version() {
+            let err = plugin(WAT_NEWER_ENGINE_VERSION).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "plugin requires engine version 99.0.0 or newer, found {}",
+                    ENGINE_VERSION
+                )
+            )
+        }
+
+        #[test]
+        fn missing_registration() {
+            let err = plugin(WAT_MISSING_REGISTRATION).unwrap_err();
+
+            assert!(matches!(err, RuntimeError::Registration))
+        }
+    }
+
+    mod check_engine_version {
+        use super::*;
+
+        #[test]
+        fn compatible() {
+            assert!(check_engine_version("0").is_ok());
+            assert!(check_engine_version("0.1").is_ok());
+            assert!(check_engine_version(ENGINE_VERSION).is_ok());
+        }
+
+        #[test]
+        fn incompatible() {
+            assert!(matches!(
+                check_engine_version("99.0.0"),
+                Err(RuntimeError::IncompatibleEngineVersion { .. })
+            ));
+        }
+
+        #[test]
+        fn invalid() {
+            for version in &["", "one", "1.0.0-beta", "1.0.0.0"] {
+                assert!(matches!(
+                    check_engine_version(version),
+                    Err(RuntimeError::InvalidEngineVersion(_))
+                ));
+            }
+        }
+    }
+
+    mod tick {
+        use super::*;
+
+        #[test]
+        fn every_tick() {
+            let mut plugin = plugin(WAT_VALID).unwrap();
+
+            assert!((0..30).all(|_| plugin.tick(Time::default(), vec![]).is_some()));
+        }
+
+        #[test]
+        fn update_interval() {
+            let mut plugin = plugin(WAT_UPDATE_INTERVAL).unwrap();
+            let runs: Vec<_> = (0..30)
+                .filter(|_| plugin.tick(Time::default(), vec![]).is_some())
+                .collect();
+
+            assert_eq!(runs, vec![0, 10, 20]);
+        }
+
+        #[test]
+        fn skipped_ticks_buffered() {
+            use common::event;
+
+            let mut plugin = plugin(WAT_UPDATE_INTERVAL).unwrap();
+            let time = |tick: u64| Time::new(tick * 10, 10);
+            let click = |x: f32| {
+                Event::Input(event::Input::MouseClick {
+                    button: event::MouseButton::Left,
+                    x,
+                    y: 0.0,
+                })
+            };
+
+            assert!(plugin.tick(time(0), vec![]).is_some());
+
+            // A click arriving on a skipped tick is delivered on the next run,
+            // in order with the events of later ticks.
+            assert!(plugin.tick(time(1), vec![click(1.0)]).is_none());
+            for tick in 2..10 {
+                assert!(plugin.tick(time(tick), vec![]).is_none());
+            }
+
+            let (run_time, events) = plugin.tick(time(10), vec![click(10.0)]).unwrap();
+            assert_eq!(events, vec![click(1.0), click(10.0)]);
+            assert_eq!(run_time, Time::new(10, 100));
+
+            // Nothing is delivered twice.
+            for tick in 11..20 {
+                assert!(plugin.tick(time(tick), vec![]).is_none());
+            }
+            let (_, events) = plugin.tick(time(20), vec![]).unwrap();
+            assert!(events.is_empty());
+        }
     }
 
     mod run {
@@ -356,7 +620,7 @@ pub(super) mod tests {
 
             assert!(plugin(WAT_VALID)
                 .expect("valid plugin")
-                .run(&mut game_state, canvas, &[])
+                .run(&mut game_state, canvas, Time::default(), &[], &[])
                 .is_ok())
         }
 
@@ -364,10 +628,13 @@ pub(super) mod tests {
         fn missing_function() {
             let canvas = Canvas::default();
             let mut game_state = GameState::default();
-            let result =
-                plugin(WAT_MISSING_FUNC)
-                    .expect("valid plugin")
-                    .run(&mut game_state, canvas, &[]);
+            let result = plugin(WAT_MISSING_FUNC).expect("valid plugin").run(
+                &mut game_state,
+                canvas,
+                Time::default(),
+                &[],
+                &[],
+            );
             let err = anyhow::Error::new(result.unwrap_err());
 
             assert_eq!(
@@ -379,13 +646,57 @@ pub(super) mod tests {
             )
         }
 
+        #[test]
+        fn missing_run_result() {
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let result = plugin(WAT_MISSING_RUN_RESULT).expect("valid plugin").run(
+                &mut game_state,
+                canvas,
+                Time::default(),
+                &[],
+                &[],
+            );
+            let err = anyhow::Error::new(result.unwrap_err());
+
+            assert_eq!(
+                format!("{:?}", err),
+                "wasm runtime error\n\n\
+
+                 Caused by:\n    \
+                     plugin error: plugin returned no result"
+            )
+        }
+
+        #[test]
+        fn panic() {
+            let canvas = Canvas::default();
+            let mut game_state = GameState::default();
+            let result = plugin(WAT_PANIC).expect("valid plugin").run(
+                &mut game_state,
+                canvas,
+                Time::default(),
+                &[],
+                &[],
+            );
+            let err = anyhow::Error::new(result.unwrap_err());
+
+            assert_eq!(
+                format!("{:?}", err),
+                "wasm runtime error\n\n\
+
+                 Caused by:\n    \
+                     plugin error: plugin panicked: boom"
+            )
+        }
+
         #[test]
         fn invalid_function_signature() {
             let canvas = Canvas::default();
             let mut game_state = GameState::default();
             let result = plugin(WAT_INVALID_FUNC_SIGNATURE)
                 .expect("valid plugin")
-                .run(&mut game_state, canvas, &[]);
+                .run(&mut game_state, canvas, Time::default(), &[], &[]);
             let err = anyhow::Error::new(result.unwrap_err());
 
             assert_eq!(
@@ -411,6 +722,117 @@ pub(super) mod tests {
     }
 
     pub const WAT_VALID: &str = r#"(module
+        (import "" "init_callback" (func $init_callback (param i32 i32)))
+        (import "" "run_callback" (func $run_callback (param i32 i32)))
+        (func (export "_init")
+            i32.const 1048576
+            i32.const 12
+            call $init_callback)
+        (func (export "_run") (param i32 i32)
+            i32.const 1048592
+            i32.const 2
+            call $run_callback)
+        (func (export "_malloc") (param i32) (result i32)
+            i32.const 0)
+        (data (;0;) (i32.const 1048576) "{\22n\22:\22test\22}")
+        (data (;1;) (i32.const 1048592) "{}")
+        (memory (;0;) 17)
+        (export "memory" (memory 0)))
+    "#;
+
+    // Registers with a minimum engine version of `99.0.0`.
+    pub const WAT_NEWER_ENGINE_VERSION: &str = r#"(module
+        (import "" "init_callback" (func $init_callback (param i32 i32)))
+        (import "" "run_callback" (func $run_callback (param i32 i32)))
+        (func (export "_init")
+            i32.const 1048576
+            i32.const 25
+            call $init_callback)
+        (func (export "_run") (param i32 i32)
+            i32.const 1048608
+            i32.const 2
+            call $run_callback)
+        (func (export "_malloc") (param i32) (result i32)
+            i32.const 0)
+        (data (;0;) (i32.const 1048576) "{\22n\22:\22test\22,\22v\22:\2299.0.0\22}")
+        (data (;1;) (i32.const 1048608) "{}")
+        (memory (;0;) 17)
+        (export "memory" (memory 0)))
+    "#;
+
+    // `_init` never calls `init_callback`
+    pub const WAT_MISSING_REGISTRATION: &str = r#"(module
+        (import "" "init_callback" (func (param i32 i32)))
+        (import "" "run_callback" (func (param i32 i32)))
+        (func (export "_init"))
+        (func (export "_run") (param i32 i32))
+        (func (export "_malloc") (param i32) (result i32)
+            i32.const 0)
+        (memory (;0;) 17)
+        (export "memory" (memory 0)))
+    "#;
+
+    // Registers with an update interval of 10 ticks.
+    pub const WAT_UPDATE_INTERVAL: &str = r#"(module
+        (import "" "init_callback" (func $init_callback (param i32 i32)))
+        (import "" "run_callback" (func $run_callback (param i32 i32)))
+        (func (export "_init")
+            i32.const 1048576
+            i32.const 19
+            call $init_callback)
+        (func (export "_run") (param i32 i32)
+            i32.const 1048608
+            i32.const 2
+            call $run_callback)
+        (func (export "_malloc") (param i32) (result i32)
+            i32.const 0)
+        (data (;0;) (i32.const 1048576) "{\22n\22:\22test\22,\22u\22:10}")
+        (data (;1;) (i32.const 1048608) "{}")
+        (memory (;0;) 17)
+        (export "memory" (memory 0)))
+    "#;
+
+    // `_run` never returns
+    pub const WAT_INFINITE_LOOP: &str = r#"(module
+        (import "" "init_callback" (func $init_callback (param i32 i32)))
+        (import "" "run_callback" (func (param i32 i32)))
+        (func (export "_init")
+            i32.const 1048576
+            i32.const 12
+            call $init_callback)
+        (func (export "_run") (param i32 i32)
+            (loop $loop
+                br $loop))
+        (func (export "_malloc") (param i32) (result i32)
+            i32.const 0)
+        (data (;0;) (i32.const 1048576) "{\22n\22:\22test\22}")
+        (memory (;0;) 17)
+        (export "memory" (memory 0)))
+    "#;
+
+    // `_run` reports a panic, and traps
+    pub const WAT_PANIC: &str = r#"(module
+        (import "" "init_callback" (func $init_callback (param i32 i32)))
+        (import "" "run_callback" (func $run_callback (param i32 i32)))
+        (func (export "_init")
+            i32.const 1048576
+            i32.const 12
+            call $init_callback)
+        (func (export "_run") (param i32 i32)
+            i32.const 1048592
+            i32.const 29
+            call $run_callback
+            unreachable)
+        (func (export "_malloc") (param i32) (result i32)
+            i32.const 0)
+        (data (;0;) (i32.const 1048576) "{\22n\22:\22test\22}")
+        (data (;1;) (i32.const 1048592) "{\22e\22:\22plugin panicked: boom\22}")
+        (memory (;0;) 17)
+        (export "memory" (memory 0)))
+    "#;
+
+    // `_run` never calls `run_callback`
+    pub const WAT_MISSING_RUN_RESULT: &str = r#"(module
         (import "" "init_callback" (func $init_callback (param i32 i32)))
         (import "" "run_callback" (func (param i32 i32)))
         (func (export "_init")
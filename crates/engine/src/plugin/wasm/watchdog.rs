@@ -0,0 +1,203 @@
+//! A watchdog to interrupt plugins that exceed their time budget.
+
+use std::{
+    mem,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use wasmtime::{Func, Instance, InterruptHandle, Module, Store};
+
+/// A Wasm module exporting a single `noop` function, which does nothing.
+///
+/// This is the binary encoding of `(module (func (export "noop")))`.
+const NOOP_MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+    0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: () -> ()
+    0x03, 0x02, 0x01, 0x00, // function section
+    0x07, 0x08, 0x01, 0x04, 0x6e, 0x6f, 0x6f, 0x70, 0x00, 0x00, // export "noop"
+    0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // code section: empty body
+];
+
+/// The state shared between the watchdog and its background thread.
+#[derive(Debug, Default)]
+struct State {
+    /// The moment at which the running plugin is interrupted, if any.
+    deadline: Option<Instant>,
+
+    /// Whether the running plugin was interrupted.
+    interrupted: bool,
+
+    /// Whether the background thread should stop.
+    shutdown: bool,
+}
+
+/// Interrupts the execution of Wasm code in a store after a wall-clock
+/// deadline.
+///
+/// A single background thread waits for the deadline of the plugin that is
+/// currently running, and interrupts it once the deadline passes.
+///
+/// Note that Wasm code is only interrupted at function entries and loop
+/// headers. Code blocked on a host function is interrupted once the host
+/// function returns.
+///
+/// An interrupt requested while no Wasm code is running stays pending, until
+/// Wasm code in the store is called again, which then traps immediately.
+#[derive(Debug)]
+pub(super) struct Watchdog {
+    /// The state shared with the background thread.
+    shared: Arc<(Mutex<State>, Condvar)>,
+
+    /// A function in the watched store that does nothing, called to clear a
+    /// pending interrupt.
+    noop: Func,
+}
+
+impl Watchdog {
+    /// Create a new watchdog, interrupting Wasm code running in the given
+    /// store.
+    pub(super) fn new(store: &Store) -> anyhow::Result<Self> {
+        let handle = store.interrupt_handle()?;
+        let noop = Instance::new(&Module::new(store, NOOP_MODULE)?, &[])?
+            .get_func("noop")
+            .ok_or_else(|| anyhow::anyhow!("missing `noop` function"))?;
+
+        let shared = Arc::new((Mutex::new(State::default()), Condvar::new()));
+        let thread_shared = Arc::clone(&shared);
+
+        thread::spawn(move || watch(&thread_shared, &handle));
+
+        Ok(Self { shared, noop })
+    }
+
+    /// Start the countdown for the given time budget.
+    pub(super) fn arm(&self, budget: Duration) {
+        self.update(|state| {
+            state.deadline = Some(Instant::now() + budget);
+            state.interrupted = false;
+        });
+    }
+
+    /// Stop the countdown, after the run it was armed for finished.
+    ///
+    /// `trapped` is whether the run trapped because it was interrupted. Only
+    /// such runs count as interrupted, for which `true` is returned.
+    ///
+    /// If the deadline passed after the run finished, but before the
+    /// countdown was stopped, the run completed normally. The interrupt that
+    /// was requested is then still pending, and is cleared so that it doesn't
+    /// interrupt the next run.
+    pub(super) fn disarm(&self, trapped: bool) -> bool {
+        let mut deadline_passed = false;
+        self.update(|state| {
+            state.deadline = None;
+            deadline_passed = mem::take(&mut state.interrupted);
+        });
+
+        if deadline_passed && !trapped {
+            // The call traps if an interrupt is pending, which clears it.
+            drop(self.noop.call(&[]));
+        }
+
+        deadline_passed && trapped
+    }
+
+    /// Update the shared state, and wake up the background thread.
+    fn update(&self, f: impl FnOnce(&mut State)) {
+        let (lock, condvar) = &*self.shared;
+
+        if let Ok(mut state) = lock.lock() {
+            f(&mut state);
+            condvar.notify_one();
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.update(|state| state.shutdown = true);
+    }
+}
+
+/// Wait for deadlines to pass, and interrupt the running Wasm code when they
+/// do.
+fn watch(shared: &(Mutex<State>, Condvar), handle: &InterruptHandle) {
+    let (lock, condvar) = shared;
+    let mut state = match lock.lock() {
+        Ok(state) => state,
+        Err(_) => return,
+    };
+
+    while !state.shutdown {
+        let result = match state.deadline {
+            None => condvar.wait(state).ok(),
+            Some(deadline) => {
+                let now = Instant::now();
+
+                if now >= deadline {
+                    handle.interrupt();
+                    state.deadline = None;
+                    state.interrupted = true;
+                    continue;
+                }
+
+                condvar
+                    .wait_timeout(state, deadline - now)
+                    .ok()
+                    .map(|(state, _)| state)
+            }
+        };
+
+        // A poisoned lock means the engine panicked, stop watching.
+        state = match result {
+            Some(state) => state,
+            None => return,
+        };
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use wasmtime::{Config, Engine};
+
+    fn store() -> Store {
+        let mut config = Config::default();
+        config.interruptable(true);
+
+        Store::new(&Engine::new(&config))
+    }
+
+    mod disarm {
+        use super::*;
+
+        #[test]
+        fn deadline_after_run() {
+            let store = store();
+            let watchdog = Watchdog::new(&store).unwrap();
+            let func = Instance::new(&Module::new(&store, NOOP_MODULE).unwrap(), &[])
+                .unwrap()
+                .get_func("noop")
+                .unwrap();
+
+            // The deadline passes after the (empty) run finished.
+            watchdog.arm(Duration::from_millis(0));
+            thread::sleep(Duration::from_millis(50));
+
+            assert!(!watchdog.disarm(false));
+
+            // No interrupt is left pending for the next run.
+            assert!(func.call(&[]).is_ok());
+        }
+
+        #[test]
+        fn before_deadline() {
+            let watchdog = Watchdog::new(&store()).unwrap();
+
+            watchdog.arm(Duration::from_secs(60));
+            assert!(!watchdog.disarm(false));
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Record input events to a file, to play them back in a later session.
+//!
+//! This is useful to reproduce bugs, or to replay a game session from start to
+//! finish.
+
+use crate::error::Recorder as Error;
+use common::{serde_json, Deserialize, Event, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
+
+/// The mode in which the recorder operates.
+#[derive(Debug, Clone)]
+pub(crate) enum Mode {
+    /// Record all input events to the file at the given path.
+    Record(PathBuf),
+
+    /// Replace all input events with those recorded in the file at the given
+    /// path.
+    Playback(PathBuf),
+}
+
+/// A single entry in the recording, storing all input events of one tick.
+///
+/// Entries are stored as JSON, one entry per line. Ticks without any input
+/// events are not stored.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    /// The tick (update) of the updater in which the events occurred.
+    #[serde(rename = "t")]
+    tick: u64,
+
+    /// The input events of the tick.
+    #[serde(rename = "e")]
+    events: Vec<Event>,
+}
+
+/// Taps the input event stream of the updater, to either record or play back
+/// events.
+#[derive(Debug)]
+pub(crate) enum Recorder {
+    /// Writes all input events to a file.
+    Record {
+        /// The path of the recording, used for error reporting.
+        path: PathBuf,
+
+        /// The file to write to.
+        file: File,
+    },
+
+    /// Sources input events from a previous recording.
+    Playback {
+        /// The recorded entries that have not yet been played back.
+        entries: VecDeque<Entry>,
+    },
+}
+
+impl Recorder {
+    /// Create a new recorder.
+    ///
+    /// In record mode, the file is created (or truncated), in playback mode the
+    /// existing recording is read in full.
+    pub(crate) fn new(mode: Mode) -> Result<Self, Error> {
+        match mode {
+            Mode::Record(path) => {
+                let file = File::create(&path).map_err(|err| Error::io(&path, &err))?;
+
+                Ok(Self::Record { path, file })
+            }
+            Mode::Playback(path) => {
+                let data = fs::read_to_string(&path).map_err(|err| Error::io(&path, &err))?;
+
+                let entries = data
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(index, line)| {
+                        serde_json::from_str(line).map_err(|source| Error::Format {
+                            line: index.saturating_add(1),
+                            source,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Self::Playback { entries })
+            }
+        }
+    }
+
+    /// Tap into the input events of the given tick.
+    ///
+    /// When recording, the events are written to the recording. When playing
+    /// back, the events are replaced by the recorded events of the tick.
+    pub(crate) fn tap(&mut self, tick: u64, events: &mut Vec<Event>) -> Result<(), Error> {
+        match self {
+            Self::Record { path, file } => {
+                if events.is_empty() {
+                    return Ok(());
+                }
+
+                let entry = Entry {
+                    tick,
+                    events: events.clone(),
+                };
+
+                let mut line = serde_json::to_vec(&entry).map_err(Error::Encode)?;
+                line.push(b'\n');
+
+                file.write_all(&line).map_err(|err| Error::io(path, &err))
+            }
+            Self::Playback { entries } => {
+                events.clear();
+
+                while let Some(entry) = entries.front() {
+                    if entry.tick > tick {
+                        break;
+                    }
+
+                    if let Some(entry) = entries.pop_front() {
+                        if entry.tick == tick {
+                            *events = entry.events;
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use common::event;
+    use tempfile::NamedTempFile;
+
+    mod tap {
+        use super::*;
+
+        #[test]
+        fn record_and_playback() {
+            let file = NamedTempFile::new().unwrap();
+            let path = file.path().to_owned();
+            let event = Event::Input(event::Input::Focus);
+
+            let mut recorder = Recorder::new(Mode::Record(path.clone())).unwrap();
+            recorder.tap(0, &mut vec![event.clone()]).unwrap();
+            recorder.tap(1, &mut vec![]).unwrap();
+            recorder
+                .tap(2, &mut vec![event.clone(), event.clone()])
+                .unwrap();
+
+            let mut recorder = Recorder::new(Mode::Playback(path)).unwrap();
+            let mut lengths = vec![];
+
+            for tick in 0..4 {
+                // Events from the backend are ignored during playback.
+                let mut events = vec![Event::Input(event::Input::Blur)];
+                recorder.tap(tick, &mut events).unwrap();
+
+                lengths.push(events.len());
+            }
+
+            assert_eq!(lengths, vec![1, 0, 2, 0]);
+        }
+
+        #[test]
+        fn missing_recording() {
+            let err = Recorder::new(Mode::Playback("foo".into())).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "inaccessible event recording `foo` (NotFound)"
+            );
+        }
+    }
+}
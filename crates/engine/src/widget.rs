@@ -1,33 +1,252 @@
 //! Helper methods to handle widgets in the engine.
 
+use crate::config::FocusRing;
 use common::{
     event,
-    widget::{ButtonRectangle, MovingCircle, Runtime, Widget},
-    Component, Event, WidgetWithPosition,
+    widget::{
+        ButtonRectangle, Checkbox, Kind, MovingCircle, ProgressBar, Role, Runtime, Slider, Widget,
+    },
+    Border, Color, Component, Event, GameState, Key, Shape, WidgetError, WidgetState,
+    WidgetWithPosition,
 };
-use std::convert::TryFrom;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::TryFrom,
+    mem,
+};
+
+/// The color of the outline drawn around the bounds of widgets, when debugging.
+const DEBUG_BOUNDS_COLOR: Color = Color::new(1.0, 0.0, 1.0, 1.0);
+
+/// Run all widgets with the given input events, and return the events to pass
+/// on to the plugins.
+///
+/// The events are returned in a deterministic order:
+///
+/// 1. The input events, in the order in which they were received.
+/// 2. The widget events, with widgets updated in render order (see
+///    [`GameState::widgets_in_render_order`]). The events of a single widget
+///    are in the order in which the widget triggered them.
+///
+/// While a widget has captured the pointer (see
+/// [`WidgetWithPosition::has_pointer_capture`]), no other widget receives
+/// mouse button events.
+///
+/// Pressing the `Tab` key moves the focus to the next focusable widget, see
+/// [`traverse_focus`].
+pub(super) fn update_all(
+    state: &mut GameState,
+    input_events: &[Event],
+    delta: f32,
+    drag_threshold: f32,
+) -> Vec<Event> {
+    let mut events = input_events.to_vec();
+    let pointer_captured = state
+        .widgets_in_render_order()
+        .iter()
+        .any(|widget| widget.has_pointer_capture());
+
+    let focus_changes = traverse_focus(state, input_events);
+
+    for (plugin, name, widget) in state.widgets_mut() {
+        let captured_elsewhere = pointer_captured && !widget.has_pointer_capture();
+
+        // Widgets whose focus changed receive the matching focus or blur
+        // event along with the other input events.
+        let widget_input = focus_changes
+            .iter()
+            .find(|((owner, widget_name), _)| owner == plugin && widget_name == name)
+            .map_or(input_events, |(_, events)| events.as_slice());
+
+        events.append(&mut update(
+            plugin,
+            name,
+            widget,
+            widget_input,
+            delta,
+            drag_threshold,
+            captured_elsewhere,
+        ))
+    }
+
+    events
+}
+
+/// Move the focus to the next focusable widget for every press of the `Tab`
+/// key.
+///
+/// Widgets are traversed in render order, wrapping around to the first
+/// focusable widget after the last one. Only visible buttons, sliders and
+/// checkboxes can be focused using the keyboard.
+///
+/// Returns the input events for every widget whose focus changed, identified by
+/// the name of its plugin and its own name, with a focus or blur event added
+/// right after the key press that changed it.
+fn traverse_focus(
+    state: &mut GameState,
+    input_events: &[Event],
+) -> Vec<((String, String), Vec<Event>)> {
+    let tab_presses: Vec<_> = input_events
+        .iter()
+        .enumerate()
+        .filter(|(_, event)| event == &&Event::Input(event::Input::KeyDown(Key::Tab)))
+        .map(|(index, _)| index)
+        .collect();
+
+    if tab_presses.is_empty() {
+        return vec![];
+    }
+
+    let mut widgets: Vec<_> = state
+        .widgets_mut()
+        .into_iter()
+        .filter(|(_, _, widget)| focusable(widget))
+        .collect();
+
+    let mut changes: Vec<((String, String), Vec<(usize, event::Input)>)> = vec![];
+    let mut change = |plugin: &str, name: &str, index: usize, input: event::Input| {
+        let key = (plugin.to_owned(), name.to_owned());
+
+        match changes.iter_mut().find(|(widget, _)| widget == &key) {
+            Some((_, inputs)) => inputs.push((index, input)),
+            None => changes.push((key, vec![(index, input)])),
+        }
+    };
+
+    for index in tab_presses {
+        // The focus moves on from the last focused widget. Any other focused
+        // widget (e.g. one hovered by the pointer) loses its focus as well.
+        let mut next = 0;
+        for (position, (plugin, name, widget)) in widgets.iter_mut().enumerate() {
+            if widget.focussed() {
+                widget.blur();
+                change(*plugin, *name, index, event::Input::Blur);
+                next = position.saturating_add(1);
+            }
+        }
+
+        if next >= widgets.len() {
+            next = 0;
+        }
+
+        if let Some((plugin, name, widget)) = widgets.get_mut(next) {
+            widget.focus();
+            change(*plugin, *name, index, event::Input::Focus);
+        }
+    }
+
+    changes
+        .into_iter()
+        .map(|(widget, inputs)| {
+            let mut events = input_events.to_vec();
+
+            // Inserting the events from back to front keeps the indices of the
+            // key presses intact.
+            for (index, input) in inputs.into_iter().rev() {
+                events.insert(index.saturating_add(1), Event::Input(input));
+            }
+
+            (widget, events)
+        })
+        .collect()
+}
+
+/// Whether or not a widget can be focused using the keyboard.
+fn focusable(widget: &WidgetWithPosition) -> bool {
+    if !widget.is_visible() {
+        return false;
+    }
+
+    valid_runtime(&widget.state().clone().into()).map_or(false, |rt| match rt.role() {
+        Role::Button | Role::Slider | Role::Checkbox => true,
+        Role::Generic | Role::Graphic | Role::ProgressBar => false,
+    })
+}
 
 /// Take a list of widgets, and a list of input events, and run each widget with
 /// the given events.
 ///
 /// The resulting widget events are returned to the caller, and the global
 /// widget state is updated if the widget changed its internal state.
-pub(super) fn update(
+///
+/// The `delta` is the time (in seconds) since the previous update, the
+/// `drag_threshold` is the distance the pointer has to move while pressed
+/// before a press turns into a drag. If `captured_elsewhere` is set, another
+/// widget captured the pointer, and mouse button events are ignored.
+fn update(
+    plugin: &str,
     name: &str,
     widget_with_position: &mut WidgetWithPosition,
     input_events: &[Event],
+    delta: f32,
+    drag_threshold: f32,
+    captured_elsewhere: bool,
 ) -> Vec<Event> {
+    let update = match widget_with_position.state().kind() {
+        Kind::MovingCircle => update_runtime::<MovingCircle>,
+        Kind::ButtonRectangle => update_runtime::<ButtonRectangle>,
+        Kind::Slider => update_runtime::<Slider>,
+        Kind::Checkbox => update_runtime::<Checkbox>,
+        Kind::ProgressBar => update_runtime::<ProgressBar>,
+    };
+
+    update(
+        plugin,
+        name,
+        widget_with_position,
+        input_events,
+        delta,
+        drag_threshold,
+        captured_elsewhere,
+    )
+}
+
+/// Run a widget of a known kind, see [`update`].
+///
+/// The runtime keeps a copy of the widget it was created from, to track if the
+/// widget changed while running. The widget state is only rebuilt from the
+/// runtime if it did, so that widgets that didn't change (e.g. static widgets
+/// without any input) don't have their state rebuilt and compared.
+fn update_runtime<W>(
+    plugin: &str,
+    name: &str,
+    widget_with_position: &mut WidgetWithPosition,
+    input_events: &[Event],
+    delta: f32,
+    drag_threshold: f32,
+    captured_elsewhere: bool,
+) -> Vec<Event>
+where
+    W: Runtime + Clone + PartialEq + for<'a> TryFrom<&'a WidgetState, Error = WidgetError>,
+{
     let mut all_widget_events = vec![];
     let coordinates = widget_with_position.coordinates();
-    let state = widget_with_position.state().clone().into();
-    let mut rt = runtime(&state);
+    let mut rt = match W::try_from(widget_with_position.state()) {
+        Ok(rt) => rt,
+        Err(err) => {
+            log::warn!("skipping invalid widget: {}", err);
+
+            return vec![];
+        }
+    };
+    let original = rt.clone();
 
     for event in input_events {
-        for widget_event in widget_events(event.clone(), &*rt, widget_with_position, coordinates) {
+        let widget_events = widget_events(
+            event.clone(),
+            &rt,
+            widget_with_position,
+            coordinates,
+            drag_threshold,
+            captured_elsewhere,
+        );
+
+        for widget_event in widget_events {
             let mut widget_events = rt
-                .interact(&widget_event)
+                .interact(&widget_event, delta)
                 .into_iter()
                 .map(|event| Event::Widget {
+                    plugin: plugin.to_owned(),
                     name: name.to_owned(),
                     event,
                 })
@@ -37,16 +256,188 @@ pub(super) fn update(
         }
     }
 
-    // Store the updated widget state, since the `interact` action
-    // might have modified it.
-    *widget_with_position.state_mut() = rt.state();
+    let mut tick_events = rt
+        .tick(delta)
+        .into_iter()
+        .map(|event| Event::Widget {
+            plugin: plugin.to_owned(),
+            name: name.to_owned(),
+            event,
+        })
+        .collect();
+
+    all_widget_events.append(&mut tick_events);
+
+    // Store the updated widget state, since the `interact` and `tick` actions
+    // might have modified it. Unchanged widgets keep their existing state.
+    if rt != original {
+        widget_with_position.set_state(rt.state());
+    }
 
     all_widget_events
 }
 
 /// Return the components for a given widget.
 pub(super) fn components(widget: &Widget) -> Vec<Component> {
-    runtime(widget).render()
+    valid_runtime(widget).map_or_else(Vec::new, |rt| rt.render())
+}
+
+/// Return the components for a given widget, tinted by the tint of the widget
+/// (see [`WidgetWithPosition::tint`]).
+///
+/// The tint is multiplied into the tint of each component, which the renderers
+/// apply to the colors of the component's shape.
+pub(super) fn tinted_components(widget: &WidgetWithPosition) -> Vec<Component> {
+    let tint = widget.tint();
+    let mut components = components(&widget.state().clone().into());
+
+    for component in &mut components {
+        component.tint = component.tint.multiply(tint);
+    }
+
+    components
+}
+
+/// A cache of the components of each widget, to avoid calling
+/// [`Runtime::render`] every frame for widgets that didn't change.
+///
+/// The components of a widget are cached together with the revision of the
+/// widget state they were rendered from (see [`WidgetWithPosition::revision`]),
+/// and the tint of the widget. Once the state of a widget changes, so does its
+/// revision, and its components are rendered again. The same goes for a change
+/// of its tint.
+#[derive(Debug, Default)]
+pub(super) struct ComponentCache {
+    /// The cached components, by plugin name and widget name.
+    entries: HashMap<(String, String), CachedComponents>,
+}
+
+/// The cached components of a single widget.
+#[derive(Debug)]
+struct CachedComponents {
+    /// The revision of the widget state from which the components were
+    /// rendered.
+    revision: u64,
+
+    /// The tint of the widget applied to the components.
+    tint: Color,
+
+    /// The components of the widget.
+    components: Vec<Component>,
+
+    /// Whether or not the components were used since the last finished frame.
+    used: bool,
+}
+
+impl ComponentCache {
+    /// Return the components for a given widget, owned by the given plugin.
+    ///
+    /// The widget is only rendered if its state or tint changed, or if it
+    /// wasn't rendered before.
+    pub(super) fn components(
+        &mut self,
+        plugin: &str,
+        name: &str,
+        widget: &WidgetWithPosition,
+    ) -> &[Component] {
+        self.get_or_render(plugin, name, widget, tinted_components)
+    }
+
+    /// Drop the components of all widgets that weren't rendered since the
+    /// previous call, e.g. because they were removed or hidden.
+    ///
+    /// This is called once at the end of every frame.
+    pub(super) fn finish_frame(&mut self) {
+        self.entries
+            .retain(|_, entry| mem::replace(&mut entry.used, false));
+    }
+
+    /// Return the cached components of a widget, using `render` to (re)render
+    /// them if needed.
+    fn get_or_render(
+        &mut self,
+        plugin: &str,
+        name: &str,
+        widget: &WidgetWithPosition,
+        render: impl FnOnce(&WidgetWithPosition) -> Vec<Component>,
+    ) -> &[Component] {
+        let revision = widget.revision();
+        let tint = widget.tint();
+        let render = || render(widget);
+
+        let entry = match self.entries.entry((plugin.to_owned(), name.to_owned())) {
+            Entry::Occupied(entry) => {
+                let entry = entry.into_mut();
+                if entry.revision != revision || entry.tint != tint {
+                    entry.revision = revision;
+                    entry.tint = tint;
+                    entry.components = render();
+                }
+
+                entry
+            }
+            Entry::Vacant(entry) => entry.insert(CachedComponents {
+                revision,
+                tint,
+                components: render(),
+                used: false,
+            }),
+        };
+
+        entry.used = true;
+        &entry.components
+    }
+}
+
+/// Return the components of the focus ring to draw around a given widget.
+///
+/// The ring is drawn just outside of the bounding box of the widget's bounds,
+/// as the border of a transparent rectangle around it. Borders are drawn
+/// within the bounds of their rectangle.
+pub(super) fn focus_ring(widget: &Widget, ring: FocusRing) -> Vec<Component> {
+    let (x, y, width, height) = match valid_runtime(widget) {
+        Some(rt) => rt.bounds_shape().bounding_box(),
+        None => return vec![],
+    };
+
+    let FocusRing { color, width: w } = ring;
+
+    let shape = Shape::Rectangle {
+        width: width + w * 2.0,
+        height: height + w * 2.0,
+        color: Color::new(0.0, 0.0, 0.0, 0.0),
+        rotation: 0.0,
+        border: Some(Border {
+            color,
+            width: w,
+            glow: None,
+        }),
+    };
+
+    vec![Component::new(shape, (x - w, y - w))]
+}
+
+/// Return the components of the outline to draw around the (boxed) dimensions
+/// of a given widget, to debug its layout and hit-testing.
+pub(super) fn debug_bounds(widget: &Widget) -> Vec<Component> {
+    let (width, height) = match valid_runtime(widget) {
+        Some(rt) => rt.dimensions(),
+        None => return vec![],
+    };
+
+    let shape = Shape::Rectangle {
+        width,
+        height,
+        color: Color::new(0.0, 0.0, 0.0, 0.0),
+        rotation: 0.0,
+        border: Some(Border {
+            color: DEBUG_BOUNDS_COLOR,
+            width: 1.0,
+            glow: None,
+        }),
+    };
+
+    vec![Component::new(shape, (0.0, 0.0))]
 }
 
 /// Check whether the widget wants to know about a given event.
@@ -55,12 +446,25 @@ fn widget_events(
     rt: &dyn Runtime,
     widget: &mut WidgetWithPosition,
     widget_coordinates: (f32, f32),
+    drag_threshold: f32,
+    captured_elsewhere: bool,
 ) -> Vec<Event> {
     let mut events = vec![];
 
+    // Pointer positions passed on to the widget are relative to the widget.
+    let (x_widget, y_widget) = widget_coordinates;
+
     let mut handle_event = |kind: usize, button: event::MouseButton, pointer: (f32, f32)| {
-        let (relative_coordinates, event) =
-            handle_pointer_widget_bounds(rt, widget, widget_coordinates, pointer);
+        // A widget that captured the pointer receives all pointer events,
+        // even when the pointer is outside of its bounds. It keeps its focus
+        // until the capture is released.
+        let (relative_coordinates, event) = if widget.has_pointer_capture() {
+            let (x, y) = pointer;
+
+            (Some((x - x_widget, y - y_widget)), None)
+        } else {
+            handle_pointer_widget_bounds(rt, widget, widget_coordinates, pointer)
+        };
 
         if let Some(event) = event {
             events.push(Event::Input(event));
@@ -71,6 +475,8 @@ fn widget_events(
                 _ if kind == 0 => event::Input::Pointer(x, y),
                 _ if kind == 1 => event::Input::MouseClick { button, x, y },
                 _ if kind == 2 => event::Input::MousePress { button, x, y },
+                _ if kind == 3 => event::Input::MouseRelease { button, x, y },
+                _ if kind == 4 => event::Input::DoubleClick { button, x, y },
                 _ => return None,
             };
 
@@ -81,43 +487,106 @@ fn widget_events(
     };
 
     match event {
+        Event::Input(event::Input::MouseClick { .. })
+        | Event::Input(event::Input::MousePress { .. })
+        | Event::Input(event::Input::MouseRelease { .. })
+        | Event::Input(event::Input::DoubleClick { .. })
+            if captured_elsewhere => {}
+
+        // Drag events are derived from the presses and releases of each
+        // widget below, they aren't passed on as-is.
+        Event::Input(event::Input::DragStart { .. })
+        | Event::Input(event::Input::DragMove { .. })
+        | Event::Input(event::Input::DragEnd { .. }) => {}
+
         Event::Input(event::Input::Pointer(x, y)) => {
             match handle_event(0, event::MouseButton::Left /* dummy */, (x, y)) {
                 Some(event) => events.push(event),
 
-                // If `None` is returned, it means the cursor is no longer over
-                // the widget, so we stop tracking a long-press.
-                //
-                // FIXME: this makes it so that dragging too fast moves the
-                // cursor out of the widget and stop the drag.
-                // None => widget.press_counter = 0,
-                //
-                // Actually no, even with this `None` below, it still stops
-                // tracking the drag. Probably need to have to separate events
-                // fire: "press" and "release", which differ from "click" in
-                // that click is a quick press/release.
+                // If `None` is returned, the cursor is not over the widget. A
+                // drag continues regardless, as the widget captured the
+                // pointer when the drag started.
                 None => {}
             }
         }
 
         Event::Input(event::Input::MouseClick { button, x, y }) => {
-            if let Some(event) = handle_event(1, button, (x, y)) {
+            let event = handle_event(1, button.clone(), (x, y));
+
+            // Releasing the mouse button at the end of a drag is not a click.
+            if let Some(event) = event {
+                if !widget.dragging {
+                    events.push(event);
+                }
+            }
+
+            // A click without a preceding release ends the drag by itself.
+            if widget.dragging && widget.has_pointer_capture() {
+                let (x, y) = (x - x_widget, y - y_widget);
+                events.push(Event::Input(event::Input::DragEnd { button, x, y }));
+            }
+
+            // reset the press tracking, as the mouse is no longer held down.
+            widget.press_counter = 0;
+            widget.press_origin = None;
+            widget.dragging = false;
+        }
+
+        Event::Input(event::Input::DoubleClick { button, x, y }) => {
+            if let Some(event) = handle_event(4, button, (x, y)) {
                 events.push(event);
+            }
+        }
+
+        Event::Input(event::Input::MouseRelease { button, x, y }) => {
+            if let Some(event) = handle_event(3, button.clone(), (x, y)) {
+                events.push(event);
+            }
 
-                // reset the press counter, as the mouse is no longer held down.
-                widget.press_counter = 0;
+            if widget.dragging && widget.has_pointer_capture() {
+                let (x, y) = (x - x_widget, y - y_widget);
+                events.push(Event::Input(event::Input::DragEnd { button, x, y }));
             }
+
+            // Releasing the mouse button releases the pointer capture. Whether
+            // the press was a drag is kept until the next press, so that a
+            // click following the release isn't passed on at the end of a
+            // drag.
+            widget.press_counter = 0;
+            widget.press_origin = None;
         }
 
         Event::Input(event::Input::MousePress { button, x, y }) => {
-            // TODO: track how many "press" events were seen, if above a
-            // certain threshold, we consider it to be a "long press" instead of
-            // a "click in progress".
-            if let Some(event) = handle_event(2, button, (x, y)) {
-                // Only once the mouse button is held down long enough, does it
-                // count as an actual "press".
-                if widget.press_counter > 10 {
-                    events.push(event)
+            if let Some(event) = handle_event(2, button.clone(), (x, y)) {
+                // A new press starts out as a click, not a drag.
+                if widget.press_origin.is_none() {
+                    widget.dragging = false;
+                }
+
+                let (x_origin, y_origin) = *widget.press_origin.get_or_insert((x, y));
+
+                // Only once the pointer moved far enough away from where the
+                // mouse button was pressed, does the press turn into a drag.
+                let drag_start =
+                    !widget.dragging && (x - x_origin).hypot(y - y_origin) > drag_threshold;
+
+                if drag_start {
+                    widget.dragging = true;
+                }
+
+                if widget.dragging {
+                    events.push(event);
+
+                    // The drag starts where the mouse button was pressed, and
+                    // moves along with the pointer until it is released.
+                    if drag_start {
+                        let (x, y) = (x_origin - x_widget, y_origin - y_widget);
+                        let button = button.clone();
+                        events.push(Event::Input(event::Input::DragStart { button, x, y }));
+                    }
+
+                    let (x, y) = (x - x_widget, y - y_widget);
+                    events.push(Event::Input(event::Input::DragMove { button, x, y }));
                 }
 
                 widget.press_counter += 1;
@@ -131,17 +600,33 @@ fn widget_events(
 }
 
 /// Get the runtime implementation of a widget.
-fn runtime(widget: &Widget) -> Box<dyn Runtime> {
-    #[allow(clippy::match_wild_err_arm)]
-    match &widget {
-        Widget::MovingCircle(state) => match MovingCircle::try_from(state) {
-            Ok(widget) => Box::new(widget),
-            Err(_) => todo!("logging"),
-        },
-        Widget::ButtonRectangle(state) => match ButtonRectangle::try_from(state) {
-            Ok(widget) => Box::new(widget),
-            Err(_) => todo!("logging"),
-        },
+///
+/// Returns an error if the state of the widget is invalid for its kind.
+fn runtime(widget: &Widget) -> Result<Box<dyn Runtime>, WidgetError> {
+    let runtime: Box<dyn Runtime> = match widget {
+        Widget::MovingCircle(state) => Box::new(MovingCircle::try_from(state)?),
+        Widget::ButtonRectangle(state) => Box::new(ButtonRectangle::try_from(state)?),
+        Widget::Slider(state) => Box::new(Slider::try_from(state)?),
+        Widget::Checkbox(state) => Box::new(Checkbox::try_from(state)?),
+        Widget::ProgressBar(state) => Box::new(ProgressBar::try_from(state)?),
+    };
+
+    Ok(runtime)
+}
+
+/// Get the runtime implementation of a widget, or `None` if the state of the
+/// widget is invalid.
+///
+/// Invalid widgets are skipped, so that a single malformed widget doesn't take
+/// down the game.
+fn valid_runtime(widget: &Widget) -> Option<Box<dyn Runtime>> {
+    match runtime(widget) {
+        Ok(runtime) => Some(runtime),
+        Err(err) => {
+            log::warn!("skipping invalid widget: {}", err);
+
+            None
+        }
     }
 }
 
@@ -161,6 +646,13 @@ fn handle_pointer_widget_bounds(
     let (x_widget, y_widget) = widget_coordinates;
     let (x, y) = pointer_coordinates;
 
+    let bounds_shape = rt.bounds_shape();
+    let (x_offset, y_offset, width, height) = bounds_shape.bounding_box();
+
+    // The bounding box can be offset from the widget coordinates (e.g. for
+    // rotated shapes), shift the pointer by the same amount in reverse.
+    let inside_bounding_box = widget.is_point_inside((width, height), x - x_offset, y - y_offset);
+
     let mut blur = || {
         if widget.focussed() {
             widget.blur();
@@ -170,22 +662,14 @@ fn handle_pointer_widget_bounds(
         (None, None)
     };
 
-    // pointer is to the left or top of widget.
-    if x < x_widget || y < y_widget {
-        return blur();
-    }
-
-    let (widget_width, widget_height) = rt.dimensions();
-
-    // pointer is to the right or bottom of widget.
-    if x > x_widget + widget_width || y > y_widget + widget_height {
+    if !inside_bounding_box {
         return blur();
     }
 
     let x_relative = x - x_widget;
     let y_relative = y - y_widget;
 
-    if !rt.is_within_bounds(x_relative, y_relative) {
+    if !bounds_shape.contains_point(x_relative, y_relative) {
         return blur();
     }
 
@@ -198,3 +682,641 @@ fn handle_pointer_widget_bounds(
 
     (Some((x_relative, y_relative)), event)
 }
+
+#[cfg(test)]
+#[allow(clippy::restriction)]
+mod tests {
+    use super::*;
+    use common::{widget, PluginState, Value};
+    use std::collections::HashMap;
+
+    mod widget_events {
+        use super::*;
+
+        fn circle() -> WidgetWithPosition {
+            widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 50.0)
+                .build()
+                .1
+        }
+
+        /// Press the left mouse button at the center of the circle, move the
+        /// pointer `distance` pixels to the right while pressed, and release
+        /// the button there.
+        ///
+        /// Returns the number of press and click events passed to the widget.
+        fn press_and_release(widget: &mut WidgetWithPosition, distance: f32) -> (usize, usize) {
+            let rt = runtime(&widget.state().clone().into()).unwrap();
+            let button = event::MouseButton::Left;
+            let mut events = vec![];
+
+            let mut send = |widget: &mut WidgetWithPosition, input| {
+                events.append(&mut widget_events(
+                    Event::Input(input),
+                    &*rt,
+                    widget,
+                    (0.0, 0.0),
+                    4.0,
+                    false,
+                ))
+            };
+
+            for step in 0..=20_u8 {
+                let x = 50.0 + distance * f32::from(step) / 20.0;
+                let button = button.clone();
+
+                send(widget, event::Input::MousePress { button, x, y: 50.0 });
+            }
+
+            let x = 50.0 + distance;
+            send(widget, event::Input::MouseClick { button, x, y: 50.0 });
+
+            let count = |f: fn(&Event) -> bool| events.iter().filter(|e| f(e)).count();
+
+            (
+                count(|e| matches!(e, Event::Input(event::Input::MousePress { .. }))),
+                count(|e| matches!(e, Event::Input(event::Input::MouseClick { .. }))),
+            )
+        }
+
+        #[test]
+        fn small_move_is_click() {
+            let mut widget = circle();
+
+            assert_eq!(press_and_release(&mut widget, 2.0), (0, 1));
+            assert!(!widget.dragging);
+        }
+
+        #[test]
+        fn large_move_is_drag() {
+            let mut widget = circle();
+            let (presses, clicks) = press_and_release(&mut widget, 20.0);
+
+            assert!(presses > 0);
+            assert_eq!(clicks, 0);
+
+            // The drag ends once the mouse button is released.
+            assert!(!widget.dragging);
+            assert_eq!(widget.press_origin, None);
+        }
+
+        #[test]
+        fn drag_lifecycle() {
+            let mut widget = circle();
+            widget.set_coordinates(100.0, 100.0);
+
+            let rt = runtime(&widget.state().clone().into()).unwrap();
+            let drags = |widget: &mut WidgetWithPosition, input| {
+                widget_events(
+                    Event::Input(input),
+                    &*rt,
+                    widget,
+                    (100.0, 100.0),
+                    4.0,
+                    false,
+                )
+                .into_iter()
+                .filter(|e| {
+                    matches!(
+                        e,
+                        Event::Input(event::Input::DragStart { .. })
+                            | Event::Input(event::Input::DragMove { .. })
+                            | Event::Input(event::Input::DragEnd { .. })
+                    )
+                })
+                .collect::<Vec<_>>()
+            };
+
+            let left = || event::MouseButton::Left;
+            let press = |x| event::Input::MousePress {
+                button: left(),
+                x,
+                y: 150.0,
+            };
+
+            // The press doesn't turn into a drag until the pointer moves far
+            // enough away.
+            assert!(drags(&mut widget, press(150.0)).is_empty());
+            assert!(drags(&mut widget, press(152.0)).is_empty());
+
+            assert_eq!(
+                drags(&mut widget, press(160.0)),
+                vec![
+                    Event::Input(event::Input::DragStart {
+                        button: left(),
+                        x: 50.0,
+                        y: 50.0,
+                    }),
+                    Event::Input(event::Input::DragMove {
+                        button: left(),
+                        x: 60.0,
+                        y: 50.0,
+                    }),
+                ]
+            );
+
+            assert_eq!(
+                drags(&mut widget, press(170.0)),
+                vec![Event::Input(event::Input::DragMove {
+                    button: left(),
+                    x: 70.0,
+                    y: 50.0,
+                })]
+            );
+
+            // The drag ends outside of the bounds of the widget.
+            let release = event::Input::MouseRelease {
+                button: left(),
+                x: 300.0,
+                y: 150.0,
+            };
+
+            assert_eq!(
+                drags(&mut widget, release),
+                vec![Event::Input(event::Input::DragEnd {
+                    button: left(),
+                    x: 200.0,
+                    y: 50.0,
+                })]
+            );
+
+            let click = event::Input::MouseClick {
+                button: left(),
+                x: 300.0,
+                y: 150.0,
+            };
+
+            assert!(drags(&mut widget, click).is_empty());
+        }
+
+        #[test]
+        fn double_click_relative_coordinates() {
+            let mut widget = circle();
+            widget.set_coordinates(100.0, 100.0);
+            widget.focus();
+
+            let rt = runtime(&widget.state().clone().into()).unwrap();
+            let double_click = |x, y| {
+                Event::Input(event::Input::DoubleClick {
+                    button: event::MouseButton::Left,
+                    x,
+                    y,
+                })
+            };
+
+            let events = widget_events(
+                double_click(150.0, 140.0),
+                &*rt,
+                &mut widget,
+                (100.0, 100.0),
+                4.0,
+                false,
+            );
+
+            assert_eq!(events, vec![double_click(50.0, 40.0)]);
+        }
+
+        #[test]
+        fn drag_outside_bounds() {
+            let mut widget = circle();
+
+            // The circle has a diameter of 100 pixels, the pointer is
+            // released 150 pixels to the right of where the drag started.
+            let (presses, clicks) = press_and_release(&mut widget, 150.0);
+
+            assert_eq!(presses, 20);
+            assert_eq!(clicks, 0);
+            assert!(!widget.has_pointer_capture());
+        }
+    }
+
+    mod pointer_capture {
+        use super::*;
+
+        fn circle(name: &str, x: f32) -> (String, WidgetWithPosition) {
+            widget::Builder::new(name, widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .position(x, 0.0)
+                .build()
+        }
+
+        fn press(x: f32) -> Event {
+            Event::Input(event::Input::MousePress {
+                button: event::MouseButton::Left,
+                x,
+                y: 10.0,
+            })
+        }
+
+        fn widget_events_of<'a>(events: &'a [Event], widget: &str) -> Vec<&'a str> {
+            events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Widget { name, event, .. } if name == widget => Some(event.name()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn drag_continues_outside_bounds() {
+            let widgets: HashMap<_, _> = vec![circle("dragged", 0.0), circle("other", 100.0)]
+                .into_iter()
+                .collect();
+
+            let mut state = GameState::default();
+            state.register_plugin_state(
+                "test",
+                PluginState::new(HashMap::<String, Value>::new(), widgets),
+            );
+
+            // Start a drag inside the first circle.
+            update_all(&mut state, &[press(10.0), press(20.0)], 0.01, 4.0);
+
+            let widget = state.get("test").unwrap().widget("dragged").unwrap();
+            assert!(widget.has_pointer_capture());
+            assert!(widget.dragging);
+
+            // Drag the pointer across the second circle, outside of the first.
+            let events = update_all(&mut state, &[press(110.0)], 0.01, 4.0);
+
+            assert_eq!(widget_events_of(&events, "dragged"), vec!["drag"]);
+            assert!(widget_events_of(&events, "other").is_empty());
+
+            let other = state.get("test").unwrap().widget("other").unwrap();
+            assert!(!other.has_pointer_capture());
+
+            // Releasing the mouse button releases the capture.
+            let release = Event::Input(event::Input::MouseClick {
+                button: event::MouseButton::Left,
+                x: 110.0,
+                y: 10.0,
+            });
+            update_all(&mut state, &[release], 0.01, 4.0);
+
+            let widget = state.get("test").unwrap().widget("dragged").unwrap();
+            assert!(!widget.has_pointer_capture());
+            assert!(!widget.dragging);
+        }
+
+        #[test]
+        fn release_outside_bounds() {
+            let (_, mut widget) = circle("dragged", 0.0);
+            let rt = runtime(&widget.state().clone().into()).unwrap();
+            let mut send = |widget: &mut WidgetWithPosition, event| {
+                widget_events(event, &*rt, widget, (0.0, 0.0), 4.0, false)
+            };
+
+            send(&mut widget, press(10.0));
+            send(&mut widget, press(20.0));
+            assert!(widget.has_pointer_capture());
+
+            // The release is passed to the widget that captured the pointer,
+            // relative to the widget, even outside of its bounds.
+            let release = Event::Input(event::Input::MouseRelease {
+                button: event::MouseButton::Left,
+                x: 110.0,
+                y: 10.0,
+            });
+
+            assert_eq!(
+                send(&mut widget, release),
+                vec![Event::Input(event::Input::MouseRelease {
+                    button: event::MouseButton::Left,
+                    x: 110.0,
+                    y: 10.0,
+                })]
+            );
+            assert!(!widget.has_pointer_capture());
+
+            // The click that accompanies the release at the end of a drag is
+            // not passed on, the pointer only blurs the widget.
+            let click = Event::Input(event::Input::MouseClick {
+                button: event::MouseButton::Left,
+                x: 110.0,
+                y: 10.0,
+            });
+
+            assert_eq!(
+                send(&mut widget, click),
+                vec![Event::Input(event::Input::Blur)]
+            );
+            assert!(!widget.dragging);
+        }
+    }
+
+    mod traverse_focus {
+        use super::*;
+
+        fn button(name: &str) -> widget::Builder {
+            widget::Builder::new(name, widget::Kind::ButtonRectangle)
+                .attribute("width", 10.0)
+                .attribute("height", 10.0)
+                .attribute("idle_color", Color::default())
+        }
+
+        fn state(widgets: Vec<widget::Builder>) -> GameState {
+            let widgets: HashMap<_, _> = widgets.into_iter().map(widget::Builder::build).collect();
+
+            let mut state = GameState::default();
+            state.register_plugin_state(
+                "test",
+                PluginState::new(HashMap::<String, Value>::new(), widgets),
+            );
+
+            state
+        }
+
+        fn focussed(state: &GameState) -> Vec<&str> {
+            state
+                .named_widgets_in_render_order()
+                .into_iter()
+                .filter(|(_, _, widget)| widget.focussed())
+                .map(|(_, name, _)| name)
+                .collect()
+        }
+
+        fn key_down(key: Key) -> Event {
+            Event::Input(event::Input::KeyDown(key))
+        }
+
+        #[test]
+        fn tab_cycles_focusable_widgets() {
+            let circle = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0);
+
+            let mut state = state(vec![
+                button("a"),
+                circle,
+                button("b"),
+                button("hidden").hidden(),
+            ]);
+
+            update_all(&mut state, &[key_down(Key::Tab)], 0.01, 4.0);
+            assert_eq!(focussed(&state), vec!["a"]);
+
+            update_all(&mut state, &[key_down(Key::Tab)], 0.01, 4.0);
+            assert_eq!(focussed(&state), vec!["b"]);
+
+            // The focus wraps around to the first focusable widget.
+            update_all(&mut state, &[key_down(Key::Tab)], 0.01, 4.0);
+            assert_eq!(focussed(&state), vec!["a"]);
+
+            // The widgets themselves are notified of their focus.
+            let a = state.get("test").unwrap().widget("a").unwrap();
+            let b = state.get("test").unwrap().widget("b").unwrap();
+            assert_eq!(a.state().get_as::<bool>("focus"), Some(true));
+            assert_eq!(b.state().get_as::<bool>("focus"), Some(false));
+        }
+
+        #[test]
+        fn no_tab_press() {
+            let mut state = state(vec![button("a")]);
+
+            update_all(&mut state, &[key_down(Key::Enter)], 0.01, 4.0);
+            assert!(focussed(&state).is_empty());
+        }
+
+        #[test]
+        fn enter_activates_focused_button() {
+            let mut state = state(vec![button("a"), button("b")]);
+
+            let input = vec![key_down(Key::Tab), key_down(Key::Tab), key_down(Key::Enter)];
+            let events = update_all(&mut state, &input, 0.01, 4.0);
+
+            let activated: Vec<_> = events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Widget { name, event, .. } => Some((name.as_str(), event.name())),
+                    _ => None,
+                })
+                .collect();
+
+            assert_eq!(activated, vec![("b", "activated")]);
+        }
+    }
+
+    mod invalid_widget {
+        use super::*;
+
+        #[test]
+        fn skipped() {
+            let (name, broken) = widget::Builder::new("broken", widget::Kind::MovingCircle).build();
+            let (_, circle) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .build();
+
+            let widget: Widget = broken.state().clone().into();
+            assert_eq!(
+                runtime(&widget).err().map(|err| err.to_string()),
+                Some("missing `radius` attribute of MovingCircle widget".to_owned())
+            );
+
+            assert!(components(&widget).is_empty());
+            assert!(debug_bounds(&widget).is_empty());
+
+            let widgets: HashMap<_, _> = vec![(name, broken), ("circle".to_owned(), circle)]
+                .into_iter()
+                .collect();
+
+            let mut state = GameState::default();
+            state.register_plugin_state(
+                "test",
+                PluginState::new(HashMap::<String, Value>::new(), widgets),
+            );
+
+            let input = vec![Event::Input(event::Input::Focus)];
+            let events = update_all(&mut state, &input, 0.01, 4.0);
+
+            // The valid widget is still updated.
+            assert_eq!(events[0], input[0]);
+            assert!(state.get("test").unwrap().widget("broken").is_some());
+            let circle = state.get("test").unwrap().widget("circle").unwrap();
+            assert_eq!(circle.state().get_as::<bool>("focus"), Some(true));
+        }
+    }
+
+    mod update {
+        use super::*;
+
+        fn run(widget: &mut WidgetWithPosition, input_events: &[Event]) -> Vec<Event> {
+            update("test", "widget", widget, input_events, 0.01, 4.0, false)
+        }
+
+        #[test]
+        fn static_widget_untouched() {
+            let (_, mut widget) = widget::Builder::new("bar", widget::Kind::ProgressBar)
+                .attribute("width", 100.0)
+                .attribute("height", 10.0)
+                .attribute("progress", 0.5)
+                .build();
+
+            let state = widget.state().clone();
+            let revision = widget.revision();
+
+            let input = vec![Event::Input(event::Input::Focus)];
+            assert!(run(&mut widget, &[]).is_empty());
+            assert!(run(&mut widget, &input).is_empty());
+
+            assert_eq!(widget.state(), &state);
+            assert_eq!(widget.revision(), revision);
+        }
+
+        #[test]
+        fn changed_widget_stored() {
+            let (_, mut widget) = widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .build();
+
+            let revision = widget.revision();
+            let _ = run(&mut widget, &[Event::Input(event::Input::Focus)]);
+
+            assert_eq!(widget.state().get_as::<bool>("focus"), Some(true));
+            assert_ne!(widget.revision(), revision);
+        }
+    }
+
+    mod component_cache {
+        use super::*;
+        use std::cell::Cell;
+
+        fn circle(radius: f32) -> WidgetWithPosition {
+            widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", radius)
+                .build()
+                .1
+        }
+
+        fn state(widget: &WidgetWithPosition) -> Widget {
+            widget.state().clone().into()
+        }
+
+        /// Render the widget like the engine does, while counting the number
+        /// of renders.
+        fn render<'a>(
+            cache: &'a mut ComponentCache,
+            widget: &WidgetWithPosition,
+            renders: &Cell<usize>,
+        ) -> &'a [Component] {
+            cache.get_or_render("test", "circle", widget, |widget| {
+                renders.set(renders.get() + 1);
+                tinted_components(widget)
+            })
+        }
+
+        #[test]
+        fn unchanged_widget_rendered_once() {
+            let mut cache = ComponentCache::default();
+            let renders = Cell::new(0);
+            let widget = circle(10.0);
+
+            let first = render(&mut cache, &widget, &renders).to_vec();
+            cache.finish_frame();
+            let second = render(&mut cache, &widget, &renders).to_vec();
+
+            assert_eq!(renders.get(), 1);
+            assert_eq!(first, second);
+            assert_eq!(first, components(&state(&widget)));
+        }
+
+        #[test]
+        fn changed_widget_rendered_again() {
+            let mut cache = ComponentCache::default();
+            let renders = Cell::new(0);
+            let mut widget = circle(10.0);
+
+            render(&mut cache, &widget, &renders);
+            cache.finish_frame();
+
+            widget.set_state(circle(20.0).state().clone());
+            let rendered = render(&mut cache, &widget, &renders).to_vec();
+
+            assert_eq!(renders.get(), 2);
+            assert_eq!(rendered, components(&state(&circle(20.0))));
+        }
+
+        #[test]
+        fn tinted_widget_rendered_again() {
+            let mut cache = ComponentCache::default();
+            let renders = Cell::new(0);
+            let mut widget = circle(10.0);
+            let red = Color::new(1.0, 0.0, 0.0, 1.0);
+
+            render(&mut cache, &widget, &renders);
+            cache.finish_frame();
+
+            widget.set_tint(red);
+            let rendered = render(&mut cache, &widget, &renders).to_vec();
+
+            assert_eq!(renders.get(), 2);
+            assert!(rendered.iter().all(|component| component.tint == red));
+        }
+
+        #[test]
+        fn replaced_widget_rendered_again() {
+            let mut cache = ComponentCache::default();
+            let renders = Cell::new(0);
+
+            render(&mut cache, &circle(10.0), &renders);
+            cache.finish_frame();
+            render(&mut cache, &circle(10.0), &renders);
+
+            assert_eq!(renders.get(), 2);
+        }
+
+        #[test]
+        fn unused_entries_dropped() {
+            let mut cache = ComponentCache::default();
+            let renders = Cell::new(0);
+            let widget = circle(10.0);
+
+            render(&mut cache, &widget, &renders);
+            cache.finish_frame();
+            assert_eq!(cache.entries.len(), 1);
+
+            // The widget isn't rendered during this frame.
+            cache.finish_frame();
+            assert!(cache.entries.is_empty());
+
+            render(&mut cache, &widget, &renders);
+            assert_eq!(renders.get(), 2);
+        }
+    }
+
+    mod handle_pointer_widget_bounds {
+        use super::*;
+
+        fn circle() -> WidgetWithPosition {
+            widget::Builder::new("circle", widget::Kind::MovingCircle)
+                .attribute("radius", 10.0)
+                .position(100.0, 100.0)
+                .build()
+                .1
+        }
+
+        #[test]
+        fn inside_circle() {
+            let mut widget = circle();
+            let rt = runtime(&widget.state().clone().into()).unwrap();
+
+            let (coordinates, event) =
+                handle_pointer_widget_bounds(&*rt, &mut widget, (100.0, 100.0), (110.0, 110.0));
+
+            assert_eq!(coordinates, Some((10.0, 10.0)));
+            assert_eq!(event, Some(event::Input::Focus));
+        }
+
+        #[test]
+        fn corner_of_circle() {
+            let mut widget = circle();
+            let rt = runtime(&widget.state().clone().into()).unwrap();
+
+            // Inside the bounding box of the circle, but outside the circle.
+            let (coordinates, event) =
+                handle_pointer_widget_bounds(&*rt, &mut widget, (100.0, 100.0), (101.0, 119.0));
+
+            assert_eq!(coordinates, None);
+            assert_eq!(event, None);
+        }
+    }
+}
@@ -21,19 +21,42 @@
 )]
 
 use engine::Engine;
+use std::env;
 
 fn main() -> anyhow::Result<()> {
-    Engine::builder()
+    // The engine reports recoverable problems (invalid widgets, failed draws,
+    // plugins without results, etc.) through the `log` facade. Nothing is
+    // printed until a logger is initialized, e.g. `env_logger::init()` here,
+    // with `RUST_LOG=warn` set when running the game.
+    let mut builder = Engine::builder()
         .with_plugin_path("plugins")
         .with_window_dimensions(800, 600)
-        // Currently broken.
-        //
-        // see: https://git.io/Jfzlh
-        //
-        // .with_maximum_fps(90)
+        .with_maximum_fps(90)
         .with_vsync()
-        .with_hidpi_mode()
-        .build()?
-        .run()
-        .map_err(Into::into)
+        .with_hidpi_mode();
+
+    // Resume a saved game using `--load <path>`, and allow plugins to save the
+    // game using `--save <path>`.
+    if let Some(path) = argument("--load") {
+        builder = builder.with_saved_game_state(path);
+    }
+
+    if let Some(path) = argument("--save") {
+        builder = builder.with_save_path(path);
+    }
+
+    builder.build()?.run().map_err(Into::into)
+}
+
+/// The value of a command-line flag, passed as `<flag> <value>`.
+fn argument(flag: &str) -> Option<String> {
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+    }
+
+    None
 }
@@ -14,4 +14,8 @@ pub enum Error {
     /// run error
     #[error(transparent)]
     Run(#[from] anyhow::Error),
+
+    /// duplicate widget error
+    #[error("widget `{0}` already exists")]
+    DuplicateWidget(String),
 }
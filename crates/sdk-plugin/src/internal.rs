@@ -9,11 +9,11 @@
 // see: https://discordapp.com/channels/442252698964721669/443151097398296587/712193675702042626
 #![allow(clippy::inline_always)]
 
-use crate::State;
-use anyhow::Result;
+use crate::{Sdk, State};
+use anyhow::{format_err, Result};
 use common::{serde_json, Registration, RunResult, StateTransfer};
 use core::mem;
-use std::convert::TryInto;
+use std::{any::Any, convert::TryInto, panic};
 
 /// An internal function called by the `plugin!()` macro.
 ///
@@ -21,23 +21,60 @@ use std::convert::TryInto;
 ///
 /// The `registration` attribute contains the details set by the plugin to be
 /// used by the engine to validate the plugin registration.
+///
+/// If the registration cannot be sent to the engine, the error is logged and
+/// the plugin stays unregistered, which the engine reports as a failed
+/// registration.
 #[inline(always)]
-#[allow(clippy::match_wild_err_arm, clippy::as_conversions)]
+#[allow(clippy::as_conversions)]
 pub fn init(registration: &Registration) {
     let data = match serde_json::to_vec(registration) {
         Ok(data) => data,
-        Err(_) => todo!("logging"),
+        Err(err) => {
+            log::error!("unable to serialize registration: {}", err);
+            return;
+        }
     };
 
     let mut slice = data.into_boxed_slice();
     let len = match slice.len().try_into() {
         Ok(len) => len,
-        Err(_) => todo!("logging: struct too large"),
+        Err(_) => {
+            log::error!("registration too large: {} bytes", slice.len());
+            return;
+        }
     };
 
     unsafe { ffi::init_callback(slice.as_mut_ptr() as i32, len) };
 }
 
+/// An internal function called by the `plugin!()` macro.
+///
+/// This function is called by the engine when a new plugin is loaded, and
+/// makes sure a panicking plugin reports its panic as a regular plugin error.
+///
+/// On targets that abort on panic (such as `wasm32-unknown-unknown`), the
+/// panic can't be caught. Instead, the hook reports the error to the engine
+/// right before the plugin traps, so that the engine can surface it.
+#[inline(always)]
+pub fn set_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        report(&RunResult::with_error(panic_error(info.payload())));
+    }));
+}
+
+/// An internal function called by the `plugin!()` macro.
+///
+/// This function wraps the `run` function of the plugin, turning a panic into
+/// an error, which is reported to the engine like any other plugin error.
+#[inline(always)]
+pub fn catch_panic(run: impl FnOnce() -> Result<()>) -> Result<()> {
+    match panic::catch_unwind(panic::AssertUnwindSafe(run)) {
+        Ok(result) => result,
+        Err(payload) => Err(format_err!("{}", panic_error(&*payload))),
+    }
+}
+
 /// An internal function called by the `plugin!()` macro.
 ///
 /// This function is called by the engine every time a plugin runs.
@@ -45,7 +82,7 @@ pub fn init(registration: &Registration) {
 /// The `result` attribute contains any errors the plugin generated while
 /// running.
 #[inline(always)]
-pub fn run(mut state: State, result: Result<()>) {
+pub fn run(sdk: &Sdk, mut state: State, result: Result<()>) {
     let error = result.err().map(|err| format!("{:#}", err));
 
     // Populate the run result with the updated state, if any.
@@ -53,25 +90,54 @@ pub fn run(mut state: State, result: Result<()>) {
     if state.updated {
         let mut state_transfer = StateTransfer::default();
         state_transfer.owned = mem::take(&mut state.owned);
+        state_transfer.shared = mem::take(&mut state.shared);
         new_state = Some(state_transfer)
     }
 
+    // Pass along any plugins this plugin wants to pause or resume.
+    let paused = if state.paused.is_empty() {
+        None
+    } else {
+        Some(mem::take(&mut state.paused))
+    };
+
+    // Pass along any commands for the engine to process.
+    let commands = sdk.take_commands();
+    let commands = if commands.is_empty() {
+        None
+    } else {
+        Some(commands)
+    };
+
     let run = RunResult {
         error,
         state: new_state,
+        paused,
+        commands,
     };
 
-    let data = match serde_json::to_vec(&run) {
+    report(&run);
+}
+
+/// Report the result of a run back to the engine.
+///
+/// A result that is too large to report is logged and dropped, which the
+/// engine reports as a missing run result.
+#[inline(always)]
+fn report(run: &RunResult) {
+    let data = match serde_json::to_vec(run) {
         Ok(vec) => vec,
         Err(err) => format!(r#"{{"error":"{:#}"}}"#, err).into_bytes(),
     };
 
     let mut slice = data.into_boxed_slice();
 
-    #[allow(clippy::match_wild_err_arm)]
     let len = match slice.len().try_into() {
         Ok(len) => len,
-        Err(_) => todo!("logging: struct too large"),
+        Err(_) => {
+            log::error!("run result too large: {} bytes", slice.len());
+            return;
+        }
     };
 
     unsafe {
@@ -80,14 +146,30 @@ pub fn run(mut state: State, result: Result<()>) {
     };
 }
 
+/// Describe the panic of a plugin, based on the payload of the panic.
+fn panic_error(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown reason");
+
+    format!("plugin panicked: {}", message)
+}
+
 /// Allocate memory on the guest.
+///
+/// A negative length allocates nothing, and returns a null pointer.
 #[inline(always)]
 #[must_use]
-#[allow(clippy::match_wild_err_arm, clippy::as_conversions)]
+#[allow(clippy::as_conversions)]
 pub fn malloc(len: i32) -> i32 {
     let vec = match len.try_into() {
         Ok(len) => Vec::<u8>::with_capacity(len),
-        Err(_) => todo!("logging"),
+        Err(_) => {
+            log::error!("invalid allocation length: {}", len);
+            return 0;
+        }
     };
 
     mem::ManuallyDrop::new(vec).as_mut_ptr() as i32
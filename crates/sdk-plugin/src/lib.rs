@@ -28,5 +28,5 @@ mod sdk;
 pub mod widget;
 
 pub use error::Error;
-pub use internal::{init, malloc, run};
+pub use internal::{catch_panic, init, malloc, run, set_panic_hook};
 pub use sdk::{Data, Sdk, State};
@@ -8,6 +8,8 @@ macro_rules! plugin {
         #[no_mangle]
         /// Initialize the plugin by exposing its configuration to the engine.
         pub extern "C" fn _init() {
+            $crate::set_panic_hook();
+
             // Explicit type to improve compiler error for plugin authors.
             let registration: Registration = init();
 
@@ -20,17 +22,15 @@ macro_rules! plugin {
             // Get data transfered from host to guest.
             let transfer = unsafe { StateTransfer::from_raw(ptr as *mut u8, len as usize) };
 
-            // Destructure into SDK, state and events.
-            let $crate::Data {
-                sdk,
-                mut state,
-                events,
-            } = transfer.into();
+            // Destructure into SDK and state.
+            let $crate::Data { sdk, mut state } = transfer.into();
 
             // Explicit type to improve compiler error for plugin authors.
-            let result: Result<()> = run(&sdk, &mut state, &events);
+            //
+            // A panicking plugin reports the panic as a regular error.
+            let result: Result<()> = $crate::catch_panic(|| run(&sdk, &mut state, sdk.events()));
 
-            $crate::run(state, result);
+            $crate::run(&sdk, state, result);
         }
 
         #[no_mangle]
@@ -3,6 +3,6 @@
 pub use crate::{widget, Sdk, State};
 pub use anyhow::{self, bail, format_err, Result};
 pub use common::{
-    event, serde_json, Border, Canvas, Color, Deserialize, Event, Key, PluginState, Registration,
-    Serialize, StateTransfer, Value,
+    event, serde_json, Border, Canvas, Color, Command, Deserialize, Event, Key, PluginState,
+    Registration, Serialize, StateTransfer, Value,
 };
@@ -1,10 +1,11 @@
 //! Types used to convert and expose SDK functionality.
 
+use crate::Error;
 use common::{
-    serde_json, Canvas, DeserializeOwned, Event, PluginState, StateTransfer, Value,
-    WidgetWithPosition,
+    event, serde_json, widget, Canvas, Color, Command, DeserializeOwned, Event, PluginState,
+    StateTransfer, Time, Value, WidgetState, WidgetWithPosition,
 };
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom, mem, time::Duration};
 
 /// A data container used to unwrap data transfered from the engine to the
 /// plugin.
@@ -14,14 +15,6 @@ pub struct Data {
 
     /// The state of the plugin as reported by the engine.
     pub state: State,
-
-    /// The events reported by the engine to have been emitted since this plugin
-    /// last ran.
-    // TODO:
-    //
-    // Move events into `sdk`, and have it expose helper methods to get events
-    // of a named widget, or get a slice of all available events.
-    pub events: Vec<Event>,
 }
 
 impl From<StateTransfer> for Data {
@@ -31,17 +24,28 @@ impl From<StateTransfer> for Data {
             owned,
             borrowed,
             events,
+            history,
+            shared,
+            time,
             canvas,
         } = transfer;
 
-        let sdk = Sdk { canvas };
+        let sdk = Sdk {
+            canvas,
+            time,
+            events,
+            history,
+            commands: RefCell::default(),
+        };
         let state = State {
             owned,
             borrowed,
+            shared,
             updated: false,
+            paused: HashMap::default(),
         };
 
-        Self { sdk, state, events }
+        Self { sdk, state }
     }
 }
 
@@ -55,8 +59,15 @@ pub struct State {
     /// This data can only be read, not mutated.
     borrowed: HashMap<String, PluginState>,
 
+    /// The state shared with other plugins, which the plugin declared in its
+    /// registration.
+    pub(super) shared: HashMap<String, Value>,
+
     /// A flag indicating if the `owned_state` has been modified.
     pub updated: bool,
+
+    /// A list of plugins this plugin wants to pause or resume.
+    pub(super) paused: HashMap<String, bool>,
 }
 
 impl State {
@@ -90,17 +101,157 @@ impl State {
         self.owned.get_widget_mut(&key.into())
     }
 
+    /// Add a new widget to this plugin, under the given name.
+    ///
+    /// The widget is built from the given builder, and is rendered starting
+    /// with the next frame. The widget is stored under the given name, not the
+    /// name passed to the builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this plugin already owns a widget with the same
+    /// name.
+    #[inline]
+    pub fn add_widget(
+        &mut self,
+        name: impl Into<String>,
+        widget: widget::Builder,
+    ) -> Result<(), Error> {
+        let name = name.into();
+        if self.owned.widget(name.as_str()).is_some() {
+            return Err(Error::DuplicateWidget(name));
+        }
+
+        let (_, widget) = widget.build();
+        self.owned.insert_widget(name, widget);
+        self.updated = true;
+
+        Ok(())
+    }
+
+    /// Show or hide a widget owned by this plugin.
+    ///
+    /// Returns `false` if the plugin owns no widget with the given name.
+    #[inline]
+    pub fn set_visible(&mut self, name: &str, visible: bool) -> bool {
+        match self.owned.get_widget_mut(name) {
+            Some(widget) => {
+                widget.set_visible(visible);
+                self.updated = true;
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the color multiplied into the colors of a widget owned by this
+    /// plugin when it is drawn, e.g. to flash the widget red.
+    ///
+    /// Returns `false` if the plugin owns no widget with the given name.
+    #[inline]
+    pub fn set_tint(&mut self, name: &str, tint: Color) -> bool {
+        match self.owned.get_widget_mut(name) {
+            Some(widget) => {
+                widget.set_tint(tint);
+                self.updated = true;
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get a typed view of a widget owned by this plugin.
+    ///
+    /// For example, `state.widget_as::<MovingCircle>("circle")` gives access
+    /// to the circle as a `MovingCircle`, instead of its raw state.
+    #[inline]
+    pub fn widget_as<T>(&self, key: impl Into<String>) -> Option<T>
+    where
+        T: for<'a> TryFrom<&'a WidgetState>,
+    {
+        self.owned.widget_as(key)
+    }
+
+    /// Get all widget events for which an action was registered using
+    /// [`widget::Builder::on_event`](common::widget::Builder::on_event).
+    ///
+    /// Returns a list of `(action, event)` tuples.
+    #[inline]
+    #[must_use]
+    pub fn handled_events<'a>(&'a self, events: &'a [Event]) -> Vec<(&'a str, &'a event::Widget)> {
+        self.owned.handled_events(events)
+    }
+
     /// Get an immutable reference to the state of another plugin.
     #[inline]
     pub fn plugin(&self, name: impl Into<String>) -> Option<&PluginState> {
         self.borrowed.get(&name.into())
     }
+
+    /// Get an immutable reference to a widget owned by another plugin.
+    ///
+    /// This allows following the widgets of other plugins, e.g. a HUD plugin
+    /// can track the position of the player widget of a game plugin. The
+    /// plugin has to be listed as a dependency of this plugin.
+    #[inline]
+    pub fn borrowed_widget(
+        &self,
+        plugin: impl Into<String>,
+        widget: impl Into<String>,
+    ) -> Option<&WidgetWithPosition> {
+        self.plugin(plugin).and_then(|state| state.widget(widget))
+    }
+
+    /// Get an immutable reference to a value shared with other plugins.
+    ///
+    /// Only the keys declared using
+    /// [`Registration::shared`](common::Registration::shared) are available.
+    #[inline]
+    pub fn shared(&self, key: impl Into<String>) -> Option<&Value> {
+        self.shared.get(&key.into())
+    }
+
+    /// Get a mutable reference to a value shared with other plugins.
+    ///
+    /// Writes become visible to all plugins (including this one) once all
+    /// plugins ran. If multiple plugins change the same value, the change of
+    /// the plugin that ran last wins.
+    #[inline]
+    pub fn shared_mut(&mut self, key: impl Into<String>) -> Option<&mut Value> {
+        self.updated = true;
+
+        self.shared.get_mut(&key.into())
+    }
+
+    /// Request the engine to pause (or resume) a plugin.
+    ///
+    /// The request is applied once this plugin finishes running. A plugin can
+    /// pause itself, but it can't resume itself, as it no longer runs.
+    #[inline]
+    pub fn set_plugin_paused(&mut self, name: impl Into<String>, paused: bool) {
+        self.paused.insert(name.into(), paused);
+    }
 }
 
 /// The top-level SDK helper struct.
 pub struct Sdk {
     /// The game screen canvas.
     canvas: Canvas,
+
+    /// The time details of the current update.
+    time: Time,
+
+    /// The events reported by the engine to have been emitted since this
+    /// plugin last ran.
+    events: Vec<Event>,
+
+    /// The events of previous runs, starting with the oldest run.
+    history: Vec<Vec<Event>>,
+
+    /// The commands issued by the plugin during this run.
+    commands: RefCell<Vec<Command>>,
 }
 
 impl Sdk {
@@ -110,4 +261,115 @@ impl Sdk {
     pub const fn canvas(&self) -> Canvas {
         self.canvas
     }
+
+    /// Get the position and dimensions `((x, y), (width, height))` of a widget
+    /// exactly covering the canvas.
+    ///
+    /// This is useful to size fullscreen backgrounds.
+    #[inline]
+    #[must_use]
+    pub fn canvas_fit(&self) -> ((f32, f32), (f32, f32)) {
+        self.canvas.fit()
+    }
+
+    /// Get the total time the game was updated for, before the current update.
+    ///
+    /// This is the sum of all [`Sdk::delta`]s, time does not pass while the
+    /// game is paused.
+    #[inline]
+    #[must_use]
+    pub const fn elapsed(&self) -> Duration {
+        self.time.elapsed()
+    }
+
+    /// Get the fixed time between two updates.
+    ///
+    /// The plugin runs once per update, so this allows for movement that is
+    /// independent of the update rate, e.g. by multiplying a velocity (per
+    /// second) by the delta (in seconds).
+    #[inline]
+    #[must_use]
+    pub const fn delta(&self) -> Duration {
+        self.time.delta()
+    }
+
+    /// Get the events reported by the engine to have been emitted since this
+    /// plugin last ran.
+    #[inline]
+    #[must_use]
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Get the events emitted by the named widget since this plugin last ran.
+    #[inline]
+    pub fn widget_events<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a event::Widget> {
+        self.events.iter().filter_map(move |event| match event {
+            Event::Widget { name: n, event, .. } if n == name => Some(event),
+            Event::Widget { .. } | Event::Input(_) => None,
+        })
+    }
+
+    /// Get the events of previous runs, one list of events per run, starting
+    /// with the oldest run.
+    ///
+    /// This allows detecting sequences of input spanning multiple runs (e.g.
+    /// a combo in a fighting game). The history is empty unless the engine is
+    /// configured to keep one, and does not include the events of this run.
+    #[inline]
+    #[must_use]
+    pub fn event_history(&self) -> &[Vec<Event>] {
+        &self.history
+    }
+
+    /// Request the engine to quit the game.
+    #[inline]
+    pub fn quit(&self) {
+        self.command(Command::Quit)
+    }
+
+    /// Request the engine to switch to (`true`) or from (`false`) fullscreen
+    /// mode.
+    #[inline]
+    pub fn request_fullscreen(&self, fullscreen: bool) {
+        self.command(Command::Fullscreen(fullscreen))
+    }
+
+    /// Request the engine to hide (`true`) or show (`false`) the mouse cursor
+    /// while it is over the window.
+    #[inline]
+    pub fn request_cursor_hidden(&self, hidden: bool) {
+        self.command(Command::CursorHidden(hidden))
+    }
+
+    /// Request the engine to save the current state of the game.
+    #[inline]
+    pub fn request_save(&self) {
+        self.command(Command::Save)
+    }
+
+    /// Request the engine to capture the next frame drawn.
+    #[inline]
+    pub fn request_frame_capture(&self) {
+        self.command(Command::CaptureFrame)
+    }
+
+    /// Request the engine to render the background of the game in the given
+    /// color, starting with the next frame.
+    #[inline]
+    pub fn set_background_color(&self, color: Color) {
+        self.command(Command::Background(color))
+    }
+
+    /// Queue a command for the engine to process once the plugin finishes
+    /// running.
+    #[inline]
+    pub fn command(&self, command: Command) {
+        self.commands.borrow_mut().push(command);
+    }
+
+    /// Take all commands issued by the plugin.
+    pub(super) fn take_commands(&self) -> Vec<Command> {
+        mem::take(&mut *self.commands.borrow_mut())
+    }
 }
@@ -2,7 +2,7 @@
 
 use common::widget::Builder;
 pub use common::{
-    widget::{Kind, Kind::*},
+    widget::{Kind, Kind::*, Layer},
     WidgetWithPosition,
 };
 
@@ -29,22 +29,10 @@ vienna::plugin!();
 #[allow(clippy::missing_docs_in_private_items)]
 struct Movement {
     position: Option<(f32, f32)>,
-    direction: Option<Direction>,
+    direction: Option<(f32, f32)>,
     speed: Speed,
 }
 
-/// Direction the `MovingCircle` widget wants to move in.
-///
-/// This is an attribute of the `move` event it triggers.
-#[derive(Debug, Copy, Clone, Deserialize)]
-#[allow(clippy::missing_docs_in_private_items)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 /// Speed with which the `MovingCircle` widget wants to move.
 ///
 /// This is an attribute of the `move` event it triggers.
@@ -76,14 +64,14 @@ fn init() -> Registration {
 }
 
 /// Runs on every game tick.
-fn run(sdk: &Sdk, state: &mut State, events: &[Event]) -> Result<()> {
-    let window_dimensions = sdk.canvas().dimensions();
+fn run(sdk: &Sdk, state: &mut State, _: &[Event]) -> Result<()> {
+    let window_dimensions = sdk.canvas().dimensions_f32();
     let widget = state
         .get_widget_mut("my_circle")
         .ok_or_else(|| format_err!("unable to find widget"))?;
 
-    for event in events {
-        if let Some(movement) = event_to_movement("my_circle", event) {
+    for event in sdk.widget_events("my_circle") {
+        if let Some(movement) = event_to_movement(event) {
             transform_widget(widget, movement, window_dimensions)
         }
     }
@@ -92,16 +80,11 @@ fn run(sdk: &Sdk, state: &mut State, events: &[Event]) -> Result<()> {
 }
 
 /// Given a widget, and any movement details fetched from the widget events,
-#[allow(
-    clippy::cast_possible_truncation,
-    clippy::as_conversions,
-    clippy::cast_lossless
-)]
+#[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
 fn transform_widget(
     widget: &mut widget::WidgetWithPosition,
     movement: Movement,
-    // TODO: change to f32
-    (x_max, y_max): (u16, u16),
+    (x_max, y_max): (f32, f32),
 ) {
     let (x, y) = widget.coordinates();
     let state = widget.state_mut();
@@ -109,28 +92,22 @@ fn transform_widget(
     if let widget::Kind::MovingCircle = state.kind() {
         let radius = match state.get("radius").and_then(Value::as_f64) {
             Some(value) => value as f32,
-            None => todo!("logging"),
+            None => return,
         };
 
         let diameter = radius * 2.0;
 
-        let (x_max, y_max) = (x_max as f32, y_max as f32);
-
         let dv = match movement.speed {
             Speed::Normal => 1.0,
             Speed::Fast => 3.0,
             Speed::Turbo => 5.0,
         };
 
-        let (dv_x, dv_y) = movement
-            .position
-            .unwrap_or_else(|| match movement.direction {
-                Some(Direction::Up) => (0.0, -dv),
-                Some(Direction::Left) => (-dv, 0.0),
-                Some(Direction::Down) => (0.0, dv),
-                Some(Direction::Right) => (dv, 0.0),
-                None => (0.0, 0.0),
-            });
+        let (dv_x, dv_y) = movement.position.unwrap_or_else(|| {
+            movement
+                .direction
+                .map_or((0.0, 0.0), |(x, y)| (x * dv, y * dv))
+        });
 
         // min/max so that the circle cannot move off the canvas.
         let x = (x + dv_x).min(x_max - diameter).max(0.0);
@@ -139,16 +116,18 @@ fn transform_widget(
     }
 }
 
-/// Convert an event to a movement type, if applicable.
-#[allow(clippy::shadow_same)]
-fn event_to_movement(widget_name: &str, event: &Event) -> Option<Movement> {
-    match event {
-        // Ignore any events that don't belong to the requested widget.
-        Event::Widget { name, .. } if name != widget_name => None,
+/// Convert a widget event to a movement type, if applicable.
+fn event_to_movement(event: &event::Widget) -> Option<Movement> {
+    match event.name() {
+        "move" => {
+            let x: f64 = event
+                .attribute("x")
+                .cloned()
+                .map(serde_json::from_value)?
+                .ok()?;
 
-        Event::Widget { event, .. } if event.name() == "move" => {
-            let direction = event
-                .attribute("direction")
+            let y: f64 = event
+                .attribute("y")
                 .cloned()
                 .map(serde_json::from_value)?
                 .ok()?;
@@ -161,12 +140,12 @@ fn event_to_movement(widget_name: &str, event: &Event) -> Option<Movement> {
 
             Some(Movement {
                 position: None,
-                direction,
+                direction: Some((x as f32, y as f32)),
                 speed,
             })
         }
 
-        Event::Widget { event, .. } if event.name() == "drag" => {
+        "drag" | "move_by" => {
             let x: f64 = event
                 .attribute("x")
                 .cloned()
@@ -188,7 +167,7 @@ fn event_to_movement(widget_name: &str, event: &Event) -> Option<Movement> {
 
         // After a resize we need to make sure the circle still fits within the
         // canvas boundaries.
-        Event::Widget { event, .. } if event.name() == "resized" => {
+        "resized" => {
             let position = event
                 .attribute("delta")
                 .and_then(Value::as_f64)